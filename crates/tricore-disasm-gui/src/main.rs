@@ -4,16 +4,19 @@ use iced::widget::{button, column, container, row, scrollable, text, text_input,
 use iced::{executor, theme, Application, Command, Element, Length, Theme, Color};
 use iced::widget::canvas::{self, Canvas, Frame, Path as CanvasPath, Stroke, Program, Style as CanvasStyle, Renderer as CanvasRenderer, Text as CanvasText};
 use iced::mouse;
+use iced::keyboard::{self, KeyCode};
+use iced::Subscription;
 use iced::{Point, Size};
 use iced::Rectangle;
 use std::path::Path;
 use std::time::Instant;
 
-use tricore_disasm::{analyze_entries, load_raw_bin, read_u8, read_u32, Image};
-use tricore_disasm::analyze::{Edge, EdgeKind};
-use tricore_disasm::model::read_u16;
-use tricore_rs::disasm::fmt_decoded;
-use tricore_rs::decoder::Decoder;
+use tricore_disasm::{build_report, checksum, disasm_jsonl, fetch_insn, find_instruction_start, detect_format, find_seeds, iter_insns, load_input, reanalyze_around, xrefs_to, ChecksumAlgo, Report, Endian, InputFormat, read_u8, write_elf, Image};
+use tricore_disasm::analyze::{Block, Edge, EdgeKind};
+use tricore_disasm::asm::encode_one;
+use tricore_disasm::query::Query;
+use tricore_rs::disasm::{abs_target, fmt_decoded, fmt_decoded_with_style, MnemonicStyle};
+use tricore_rs::decoder::{Decoded, Decoder};
 use tricore_rs::isa::tc16::Tc16Decoder;
 
 #[derive(Debug, Default, Clone)]
@@ -21,14 +24,22 @@ struct AppState {
     path: String,
     base: String,
     skip: String,
+    // Container `path` was last loaded as; None sniffs it on the next load
+    format: Option<InputFormat>,
     status: String,
     show_bytes: bool,
+    // Code views show `segment+offset` addresses instead of absolute ones
+    seg_relative: bool,
     image: Option<Image>,
+    // Segment the Disasm tab walks (index into `image.segments`)
+    selected_segment: usize,
     visited: Vec<u32>,
     tab: Tab,
     selection: Option<u32>,
     selected_addr: Option<u32>,
     label_edit: String,
+    // Assembly line to patch over the selected instruction
+    patch_edit: String,
     labels: std::collections::HashMap<u32, String>,
     search: String,
     logs: Vec<String>,
@@ -40,15 +51,109 @@ struct AppState {
     theme: Theme,
     font_size: u16,
     code_color: Option<Color>,
+    // Instruction syntax of the code views
+    syntax: MnemonicStyle,
     // Analysis results
     edges: Vec<Edge>,
+    // Last full report, the starting point for re-analysis after a hex edit
+    report: Option<Report>,
     // Graph filters
     show_ft: bool,
     show_br: bool,
     show_cbr: bool,
     show_call: bool,
+    show_ind: bool,
+    // Recenter the graph on the selected node when the selection changes
+    follow_selection: bool,
+    // Draw one box per basic block instead of one node per instruction
+    graph_blocks: bool,
+    // Overview of the whole graph in a corner of the canvas
+    show_minimap: bool,
     // Labels persistence
     labels_path: String,
+    // Session persistence
+    session_path: String,
+    // Recently opened images (most recent first), persisted in settings
+    recent: Vec<RecentFile>,
+    settings_path: String,
+}
+
+/// Widget id of the search box, focused by Ctrl+G.
+const SEARCH_INPUT: &str = "search";
+
+/// Edge kinds drawn as lines in the graph, in legend order.
+const GRAPH_EDGE_KINDS: [(EdgeKind, &str); 5] = [
+    (EdgeKind::Fallthrough, "FT"),
+    (EdgeKind::Branch, "BR"),
+    (EdgeKind::CondBranch, "CBR"),
+    (EdgeKind::Call, "CALL"),
+    (EdgeKind::Indirect, "IND"),
+];
+
+/// Line color for an edge kind; `Return` is drawn as a node style instead.
+fn edge_color(kind: EdgeKind) -> Option<Color> {
+    match kind {
+        EdgeKind::Fallthrough => Some(Color::from_rgb(0.6,0.6,0.6)),
+        EdgeKind::Branch => Some(Color::from_rgb(0.9,0.7,0.2)),
+        EdgeKind::CondBranch => Some(Color::from_rgb(0.2,0.7,0.9)),
+        EdgeKind::Call => Some(Color::from_rgb(0.4,0.95,0.4)),
+        EdgeKind::Indirect => Some(Color::from_rgb(0.8,0.5,0.95)),
+        EdgeKind::Return => None,
+    }
+}
+
+/// Lines the Disasm tab decodes per view.
+const DISASM_MAX_LINES: usize = 4000;
+
+/// A segment entry in the Disasm tab's picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SegmentChoice {
+    index: usize,
+    label: String,
+}
+
+impl std::fmt::Display for SegmentChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// How many recently opened images the settings file remembers.
+const MAX_RECENT: usize = 8;
+
+/// An image reference as last opened, so reopening reuses its base/skip.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct RecentFile {
+    path: String,
+    base: String,
+    skip: String,
+}
+
+impl std::fmt::Display for RecentFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+/// Preferences persisted across runs.
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+struct Settings {
+    #[serde(default)]
+    recent: Vec<RecentFile>,
+}
+
+/// Everything needed to resume work: the image reference plus annotations and view state.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Session {
+    path: String,
+    base: String,
+    skip: String,
+    /// Container `path` was loaded as, so a restore uses the same loader
+    format: Option<InputFormat>,
+    labels: std::collections::HashMap<u32, String>,
+    tab: Tab,
+    selection: Option<u32>,
+    selected_addr: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -57,27 +162,38 @@ enum Msg {
     BaseChanged(String),
     SkipChanged(String),
     ToggleBytes(bool),
+    ToggleSegRelative(bool),
     SwitchTab(Tab),
     SearchChanged(String),
     SelectPc(u32),
+    // Keyboard navigation in the code view
+    SelectNext,
+    SelectPrev,
+    FollowTarget,
+    FocusSearch,
     LabelEditChanged(String),
     SaveLabel,
     OpenExample,
     Load,
-    LoadedOk(Image),
+    LoadedOk(Image, InputFormat),
     LoadedErr(String),
     Analyze,
-    AnalyzedOk(Vec<u32>, Vec<Edge>),
+    AnalyzedOk(Box<Report>),
     AnalyzedErr(String),
     ToggleSettings,
     ThemePicked(ThemeChoice),
     FontSizePicked(u16),
     CodeColorPicked(ColorChoice),
+    SyntaxPicked(MnemonicStyle),
     SearchGo,
     ToggleEdgeFt(bool),
     ToggleEdgeBr(bool),
     ToggleEdgeCbr(bool),
     ToggleEdgeCall(bool),
+    ToggleEdgeInd(bool),
+    ToggleFollow(bool),
+    ToggleGraphBlocks(bool),
+    ToggleMinimap(bool),
     SaveLabels,
     LabelsSaved(Result<(), String>),
     LoadLabels,
@@ -87,13 +203,24 @@ enum Msg {
     HexEditCommit(u32),
     CopySelection,
     PasteToSearch,
+    Pasted(Option<String>),
+    PatchEditChanged(String),
+    ApplyPatch,
     SaveDisasm,
-    DisasmSaved(Result<(), String>),
+    SaveDisasmJsonl,
+    /// File written, or the error
+    DisasmSaved(Result<&'static str, String>),
     SaveImageBin,
-    ImageSaved(Result<(), String>),
+    ImageSaved(Result<&'static str, String>),
+    SaveSession,
+    SessionSaved(Result<(), String>),
+    OpenSession,
+    SessionOpened(Result<Session, String>),
+    OpenRecent(RecentFile),
+    SegmentPicked(SegmentChoice),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 enum Tab { Code, Disasm, Graph, Hex }
 
 impl Default for Tab { fn default() -> Self { Tab::Code } }
@@ -131,6 +258,10 @@ impl Application for App {
     type Flags = ();
 
     fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let settings_path = "settings.json".to_string();
+        // Forget recent files that were moved or deleted since the last run
+        let mut recent = load_settings(&settings_path).recent;
+        recent.retain(|r| Path::new(&r.path).exists());
         (
             App(AppState {
                 base: "0x0".into(),
@@ -143,7 +274,11 @@ impl Application for App {
                 show_br: true,
                 show_cbr: true,
                 show_call: true,
+                show_ind: true,
                 labels_path: "labels.json".into(),
+                session_path: "session.json".into(),
+                recent,
+                settings_path,
                 ..Default::default()
             }),
             Command::none(),
@@ -151,18 +286,49 @@ impl Application for App {
     }
 
     fn title(&self) -> String { "TriCore Disassembler GUI".into() }
+
+    fn subscription(&self) -> Subscription<Msg> {
+        iced::subscription::events_with(|event, status| {
+            // Keys a focused widget consumed (typing in an input) are not shortcuts
+            if status == iced::event::Status::Captured { return None; }
+            let iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) = event else { return None };
+            match key_code {
+                KeyCode::Up => Some(Msg::SelectPrev),
+                KeyCode::Down => Some(Msg::SelectNext),
+                KeyCode::Enter => Some(Msg::FollowTarget),
+                KeyCode::G if modifiers.control() => Some(Msg::FocusSearch),
+                _ => None,
+            }
+        })
+    }
     fn theme(&self) -> Theme { self.0.theme.clone() }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         eprintln!("[Msg] {:?}", &message);
         match message {
-            Msg::PathChanged(s) => { self.0.path = s.clone(); self.push_log(format!("PathChanged: {}", s)); },
+            Msg::PathChanged(s) => { self.0.path = s.clone(); self.0.format = None; self.push_log(format!("PathChanged: {}", s)); },
             Msg::BaseChanged(s) => { self.0.base = s.clone(); self.push_log(format!("BaseChanged: {}", s)); },
             Msg::SkipChanged(s) => { self.0.skip = s.clone(); self.push_log(format!("SkipChanged: {}", s)); },
             Msg::ToggleBytes(b) => { self.0.show_bytes = b; self.push_log(format!("ToggleBytes: {}", b)); },
+            Msg::ToggleSegRelative(b) => { self.0.seg_relative = b; self.push_log(format!("ToggleSegRelative: {}", b)); },
             Msg::SwitchTab(t) => self.0.tab = t,
             Msg::SearchChanged(s) => { self.0.search = s.clone(); self.push_log(format!("Search: {}", s)); },
-            Msg::SelectPc(pc) => { self.0.selection = Some(pc); self.0.label_edit = self.0.labels.get(&pc).cloned().unwrap_or_default(); self.push_log(format!("SelectPc: {:#010x}", pc)); },
+            Msg::SelectPc(pc) => { self.0.selection = Some(pc); self.0.selected_addr = Some(pc); self.0.label_edit = self.0.labels.get(&pc).cloned().unwrap_or_default(); self.push_log(format!("SelectPc: {:#010x}", pc)); },
+            Msg::SelectNext | Msg::SelectPrev => {
+                if let Some(pc) = step_pc(&self.0.visited, self.0.selection, matches!(message, Msg::SelectNext)) {
+                    return self.update(Msg::SelectPc(pc));
+                }
+            }
+            Msg::FollowTarget => {
+                let target = self.0.selection.zip(self.0.image.as_ref()).and_then(|(pc, img)| {
+                    fetch_insn(img, pc).and_then(|raw32| Tc16Decoder::new().decode(raw32)).and_then(|d| abs_target(&d, pc))
+                });
+                match target {
+                    Some(t) => return self.update(Msg::SelectPc(t)),
+                    None => self.0.status = "No branch or call target at the selection".into(),
+                }
+            }
+            Msg::FocusSearch => return text_input::focus(text_input::Id::new(SEARCH_INPUT)),
             Msg::LabelEditChanged(s) => { self.0.label_edit = s.clone(); self.push_log(format!("LabelEdit: {}", s)); },
             Msg::SaveLabel => {
                 if let Some(pc) = self.0.selection {
@@ -186,14 +352,15 @@ impl Application for App {
                 }
                 self.0.status = format!("Loading {} base={:#x} skip={}…", path, base, skip);
                 self.push_log(self.0.status.clone());
-                return Command::perform(load_image_async(path, base, skip), |res| match res {
-                    Ok(img) => Msg::LoadedOk(img),
+                return Command::perform(load_image_async(path, self.0.format, base, skip), |res| match res {
+                    Ok((img, format)) => Msg::LoadedOk(img, format),
                     Err(e) => Msg::LoadedErr(e.to_string()),
                 });
             }
             Msg::OpenExample => {
                 let ex = "examples/out/00-basic.bin".to_string();
                 self.0.path = ex.clone();
+                self.0.format = None;
                 let base = parse_hex(&self.0.base).unwrap_or(0);
                 let skip = self.0.skip.trim().parse::<usize>().unwrap_or(0);
                 if !Path::new(&ex).exists() {
@@ -203,44 +370,59 @@ impl Application for App {
                 }
                 self.0.status = format!("Loading {} base={:#x} skip={}…", ex, base, skip);
                 self.push_log(self.0.status.clone());
-                return Command::perform(load_image_async(ex, base, skip), |res| match res {
-                    Ok(img) => Msg::LoadedOk(img),
+                return Command::perform(load_image_async(ex, None, base, skip), |res| match res {
+                    Ok((img, format)) => Msg::LoadedOk(img, format),
                     Err(e) => Msg::LoadedErr(e.to_string()),
                 });
             }
-            Msg::LoadedOk(img) => {
+            Msg::LoadedOk(img, format) => {
                 // Store image and auto-run analysis so code shows up immediately
                 self.0.image = Some(img.clone());
-                let seeds = vec![img.segments.first().map(|s| s.base).unwrap_or(0)];
+                self.0.format = Some(format);
+                self.0.selected_segment = 0;
+                self.0.report = None;
+                self.0.note_recent();
+                if let Err(e) = save_settings(&self.0.settings_path, &Settings { recent: self.0.recent.clone() }) {
+                    self.push_log(format!("Settings save error: {e}"));
+                }
+                let seeds = find_seeds(&img);
                 self.0.status = format!("Loaded. Analyzing… seeds={:?}", seeds);
                 self.0.analyze_started = Some(Instant::now());
                 self.push_log(self.0.status.clone());
                 return Command::perform(analyze_async(img, seeds), |res| match res {
-                    Ok((v, e)) => Msg::AnalyzedOk(v, e),
+                    Ok(r) => Msg::AnalyzedOk(Box::new(r)),
                     Err(e) => Msg::AnalyzedErr(e.to_string()),
                 });
             }
             Msg::LoadedErr(e) => { self.0.status = format!("Load error: {e}"); self.0.image = None; self.0.visited.clear(); self.push_log(self.0.status.clone()); }
             Msg::Analyze => {
                 if let Some(img) = &self.0.image {
-                    let seeds = vec![img.segments.first().map(|s| s.base).unwrap_or(0)];
+                    let seeds = find_seeds(img);
                     let img2 = img.clone();
                     self.0.status = format!("Analyzing… seeds={:?}", seeds);
                     self.0.analyze_started = Some(Instant::now());
                     self.push_log(self.0.status.clone());
                     return Command::perform(analyze_async(img2, seeds), |res| match res {
-                        Ok((v, e)) => Msg::AnalyzedOk(v, e),
+                        Ok(r) => Msg::AnalyzedOk(Box::new(r)),
                         Err(e) => Msg::AnalyzedErr(e.to_string()),
                     });
                 }
             }
-            Msg::AnalyzedOk(mut pcs, edges) => {
+            Msg::AnalyzedOk(report) => {
+                let mut pcs: Vec<u32> = report.visited.iter().copied().collect();
                 pcs.sort_unstable();
                 self.0.visited = pcs;
-                self.0.edges = edges;
+                self.0.edges = report.insn_edges.clone();
                 let dt = self.0.analyze_started.take().map(|t| t.elapsed()).map(|d| format!(" in {:?}", d)).unwrap_or_default();
-                self.0.status = format!("Analysis done{} (visited={}, edges={})", dt, self.0.visited.len(), self.0.edges.len());
+                self.0.status = format!("Analysis done{} (visited={}, edges={}, overlaps={})", dt, self.0.visited.len(), self.0.edges.len(), report.overlaps.len());
                 self.push_log(self.0.status.clone());
+                for o in &report.overlaps {
+                    self.push_log(format!("overlap: edge target {:#010x} lands inside the instruction at {:#010x}", o.addr, o.insn));
+                }
+                for d in &report.diagnostics {
+                    self.push_log(format!("{:#010x}: {}", d.addr, d.message));
+                }
+                self.0.report = Some(*report);
             }
             Msg::AnalyzedErr(e) => { self.0.status = format!("Analyze error: {e}"); self.0.visited.clear(); self.push_log(self.0.status.clone()); }
             Msg::ToggleSettings => { self.0.show_settings = !self.0.show_settings; }
@@ -248,6 +430,7 @@ impl Application for App {
                 self.0.theme = match t { ThemeChoice::Dark => Theme::Dark, ThemeChoice::Light => Theme::Light };
             }
             Msg::FontSizePicked(sz) => { self.0.font_size = sz; }
+            Msg::SyntaxPicked(style) => { self.0.syntax = style; }
             Msg::CodeColorPicked(choice) => {
                 self.0.code_color = match choice {
                     ColorChoice::Default => None,
@@ -270,6 +453,10 @@ impl Application for App {
             Msg::ToggleEdgeBr(b) => { self.0.show_br = b; }
             Msg::ToggleEdgeCbr(b) => { self.0.show_cbr = b; }
             Msg::ToggleEdgeCall(b) => { self.0.show_call = b; }
+            Msg::ToggleEdgeInd(b) => { self.0.show_ind = b; }
+            Msg::ToggleFollow(b) => { self.0.follow_selection = b; }
+            Msg::ToggleGraphBlocks(b) => { self.0.graph_blocks = b; }
+            Msg::ToggleMinimap(b) => { self.0.show_minimap = b; }
             Msg::SaveLabels => {
                 let path = self.0.labels_path.clone();
                 let map = self.0.labels.clone();
@@ -302,7 +489,13 @@ impl Application for App {
                 }
                 self.push_log(self.0.status.clone());
             }
-            Msg::SelectAddr(a) => { self.0.selected_addr = Some(a); }
+            Msg::SelectAddr(a) => {
+                self.0.selected_addr = Some(a);
+                // Select the instruction containing the byte so the Code view follows
+                if let Some(start) = self.0.image.as_ref().and_then(|img| containing_insn(img, &self.0.visited, a)) {
+                    self.0.selection = Some(start);
+                }
+            }
             Msg::HexEditChanged(addr, s) => {
                 // Keep only hex chars, limit to 2
                 let filtered: String = s.chars().filter(|c| c.is_ascii_hexdigit()).take(2).collect();
@@ -310,111 +503,150 @@ impl Application for App {
                 self.0.selected_addr = Some(addr);
                 // Auto-commit when two hex digits entered
                 if filtered.len() == 2 {
-                    if let Some(img) = &mut self.0.image {
-                        for s in &mut img.segments {
-                            let start = s.base; let end = s.base + s.bytes.len() as u32;
-                            if addr >= start && addr < end {
-                                if let Ok(v) = u8::from_str_radix(&filtered, 16) {
-                                    s.bytes[(addr - start) as usize] = v;
-                                    self.0.status = format!("Wrote {:#04x} @ {:#010x}", v, addr);
-                                    self.push_log(self.0.status.clone());
-                                }
-                                break;
-                            }
+                    if let (Some(img), Ok(v)) = (&mut self.0.image, u8::from_str_radix(&filtered, 16)) {
+                        if write_byte(img, addr, v) {
+                            self.0.status = format!("Wrote {:#04x} @ {:#010x}", v, addr);
+                            self.push_log(self.0.status.clone());
                         }
                     }
                     self.0.hex_edits.remove(&addr);
-                    if let Some(img2) = self.0.image.clone() {
-                        let seeds = vec![img2.segments.first().map(|s| s.base).unwrap_or(0)];
-                        self.0.status = "Analyzing after hex edit…".into();
-                        self.0.analyze_started = Some(Instant::now());
-                        self.push_log(self.0.status.clone());
-                        return Command::perform(analyze_async(img2, seeds), |res| match res {
-                            Ok((v, e)) => Msg::AnalyzedOk(v, e),
-                            Err(e) => Msg::AnalyzedErr(e.to_string()),
-                        });
-                    }
+                    return self.reanalyze_after_edit(addr);
                 }
             }
             Msg::HexEditCommit(addr) => {
-                if let Some(img) = &mut self.0.image {
-                    // Find segment containing addr
-                    for s in &mut img.segments {
-                        let start = s.base; let end = s.base + s.bytes.len() as u32;
-                        if addr >= start && addr < end {
-                            if let Some(buf) = self.0.hex_edits.get(&addr) {
-                                if let Ok(v) = u8::from_str_radix(buf, 16) {
-                                    s.bytes[(addr - start) as usize] = v;
-                                    self.0.status = format!("Wrote {:#04x} @ {:#010x}", v, addr);
-                                    self.push_log(self.0.status.clone());
-                                }
-                            }
-                            break;
-                        }
+                let v = self.0.hex_edits.get(&addr).and_then(|buf| u8::from_str_radix(buf, 16).ok());
+                if let (Some(img), Some(v)) = (&mut self.0.image, v) {
+                    if write_byte(img, addr, v) {
+                        self.0.status = format!("Wrote {:#04x} @ {:#010x}", v, addr);
+                        self.push_log(self.0.status.clone());
                     }
                 }
                 // Clear the edit buffer after commit
                 self.0.hex_edits.remove(&addr);
                 // Re-run analysis so Code/Graph reflect new bytes
-                if let Some(img2) = self.0.image.clone() {
-                    let seeds = vec![img2.segments.first().map(|s| s.base).unwrap_or(0)];
-                    self.0.status = "Analyzing after hex edit…".into();
-                    self.0.analyze_started = Some(Instant::now());
-                    self.push_log(self.0.status.clone());
-                    return Command::perform(analyze_async(img2, seeds), |res| match res {
-                        Ok((v, e)) => Msg::AnalyzedOk(v, e),
-                        Err(e) => Msg::AnalyzedErr(e.to_string()),
-                    });
-                }
+                return self.reanalyze_after_edit(addr);
             }
-            Msg::CopySelection => {
-                // Compose text from current tab selection
-                let text = match self.0.tab {
-                    Tab::Code => {
-                        if let (Some(img), Some(pc)) = (&self.0.image, self.0.selection) {
-                            let dec = Tc16Decoder::new();
-                            if let Some(raw32) = read_u32(img, pc) { if let Some(d) = dec.decode(raw32) { format!("{pc:#010x}: {}", fmt_decoded(&d)) } else { format!("{pc:#010x}") } } else { format!("{pc:#010x}") }
-                        } else { String::new() }
+            Msg::PatchEditChanged(s) => self.0.patch_edit = s,
+            Msg::ApplyPatch => {
+                let (Some(pc), Some(img)) = (self.0.selection, self.0.image.as_mut()) else { return Command::none() };
+                match patch_at(img, pc, &self.0.patch_edit) {
+                    Ok(bytes) => {
+                        let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                        self.0.status = format!("Patched {pc:#010x}: {}", hex.join(" "));
+                        if let Some(crc) = segment_crc32(img, pc) { self.0.status.push_str(&format!(" (segment crc32 {crc:#010x})")); }
+                        self.push_log(self.0.status.clone());
+                        self.0.patch_edit.clear();
+                        return self.reanalyze_after_edit(pc);
                     }
-                    Tab::Hex | Tab::Disasm | Tab::Graph => {
-                        if let Some(addr) = self.0.selected_addr { if let Some(img) = &self.0.image { let b = read_u8(img, addr).unwrap_or(0); format!("{addr:#010x}: {:#04x}", b) } else { String::new() } } else { String::new() }
+                    Err(e) => {
+                        self.0.status = format!("Patch failed: {e}");
+                        self.push_log(self.0.status.clone());
                     }
-                };
-                if !text.is_empty() { self.0.status = format!("Copied: {}", text); }
+                }
+            }
+            Msg::CopySelection => {
+                let text = self.0.copy_text();
+                if text.is_empty() {
+                    self.0.status = "Nothing selected to copy".into();
+                    return Command::none();
+                }
+                self.0.status = format!("Copied: {}", text);
                 self.push_log(self.0.status.clone());
+                return iced::clipboard::write(text);
             }
-            Msg::PasteToSearch => {
-                // Without OS clipboard, reuse last status tail as a fallback paste stub
-                // In a real app, integrate iced clipboard write/read.
-                let t = self.0.status.clone();
-                if let Some(idx) = t.find(": ") { self.0.search = t[idx+2..].to_string(); }
+            Msg::PasteToSearch => return iced::clipboard::read(Msg::Pasted),
+            Msg::Pasted(contents) => {
+                // Only the first line: a search box cannot hold a multi-line paste
+                match contents.as_deref().and_then(|c| c.lines().map(str::trim).find(|l| !l.is_empty())) {
+                    Some(line) => {
+                        self.0.search = line.to_string();
+                        self.0.status = format!("Pasted: {}", line);
+                    }
+                    None => self.0.status = "Clipboard is empty".into(),
+                }
+                self.push_log(self.0.status.clone());
             }
             Msg::SaveDisasm => {
                 if let Some(img) = &self.0.image {
                     let dec = Tc16Decoder::new();
                     let mut lines = Vec::new();
                     for &pc in &self.0.visited {
-                        if let Some(raw32) = read_u32(img, pc) { if let Some(d) = dec.decode(raw32) { lines.push(format!("{pc:#010x}: {}", fmt_decoded(&d))); } }
+                        if let Some(raw32) = fetch_insn(img, pc) { if let Some(d) = dec.decode(raw32) { lines.push(format!("{pc:#010x}: {}", fmt_decoded(&d))); } }
                     }
                     let out = lines.join("\n");
                     return Command::perform(async move {
                         tokio::task::spawn_blocking(move || std::fs::write("disasm.txt", out)).await.map_err(|e| e.to_string()).and_then(|r| r.map_err(|e| e.to_string()))
-                    }, |r| match r { Ok(()) => Msg::DisasmSaved(Ok(())), Err(e) => Msg::DisasmSaved(Err(e)) });
+                    }, |r| Msg::DisasmSaved(r.map(|()| "disasm.txt")));
+                }
+            }
+            Msg::SaveDisasmJsonl => {
+                if let (Some(img), Some(report)) = (&self.0.image, &self.0.report) {
+                    let labels = &self.0.labels;
+                    let out = disasm_jsonl(img, report, |a| labels.get(&a).cloned());
+                    return Command::perform(async move {
+                        tokio::task::spawn_blocking(move || std::fs::write("disasm.jsonl", out)).await.map_err(|e| e.to_string()).and_then(|r| r.map_err(|e| e.to_string()))
+                    }, |r| Msg::DisasmSaved(r.map(|()| "disasm.jsonl")));
                 }
             }
             Msg::DisasmSaved(r) => {
-                match r { Ok(()) => self.0.status = "Saved disasm.txt".into(), Err(e) => self.0.status = format!("Save failed: {}", e) }
+                match r { Ok(file) => self.0.status = format!("Saved {file}"), Err(e) => self.0.status = format!("Save failed: {}", e) }
                 self.push_log(self.0.status.clone());
             }
             Msg::SaveImageBin => {
                 if let Some(img) = &self.0.image {
-                    let data: Vec<u8> = if let Some(seg) = img.segments.first() { seg.bytes.clone() } else { Vec::new() };
+                    let (path, data) = image_file(img);
                     return Command::perform(async move {
-                        tokio::task::spawn_blocking(move || std::fs::write("image.bin", data)).await.map_err(|e| e.to_string()).and_then(|r| r.map_err(|e| e.to_string()))
-                    }, |r| match r { Ok(()) => Msg::ImageSaved(Ok(())), Err(e) => Msg::ImageSaved(Err(e)) });
+                        tokio::task::spawn_blocking(move || std::fs::write(path, data)).await.map_err(|e| e.to_string()).and_then(|r| r.map_err(|e| e.to_string()))
+                    }, move |r| Msg::ImageSaved(r.map(|()| path)));
                 }
             }
-            Msg::ImageSaved(r) => { match r { Ok(()) => self.0.status = "Saved image.bin".into(), Err(e) => self.0.status = format!("Save failed: {}", e) } self.push_log(self.0.status.clone()); }
+            Msg::ImageSaved(r) => { match r { Ok(path) => self.0.status = format!("Saved {path}"), Err(e) => self.0.status = format!("Save failed: {}", e) } self.push_log(self.0.status.clone()); }
+            Msg::SaveSession => {
+                let path = self.0.session_path.clone();
+                let session = self.0.to_session();
+                return Command::perform(async move {
+                    tokio::task::spawn_blocking(move || -> Result<(), String> {
+                        let s = serde_json::to_string_pretty(&session).map_err(|e| e.to_string())?;
+                        std::fs::write(&path, s).map_err(|e| e.to_string())
+                    }).await.map_err(|e| e.to_string()).and_then(|r| r)
+                }, Msg::SessionSaved);
+            }
+            Msg::SessionSaved(r) => {
+                match r { Ok(()) => self.0.status = format!("Session saved to {}", self.0.session_path), Err(e) => self.0.status = format!("Session save error: {}", e) }
+                self.push_log(self.0.status.clone());
+            }
+            Msg::OpenSession => {
+                let path = self.0.session_path.clone();
+                return Command::perform(async move {
+                    tokio::task::spawn_blocking(move || -> Result<Session, String> {
+                        let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+                        serde_json::from_str(&data).map_err(|e| e.to_string())
+                    }).await.map_err(|e| e.to_string()).and_then(|r| r)
+                }, Msg::SessionOpened);
+            }
+            Msg::SessionOpened(r) => {
+                let session = match r {
+                    Ok(s) => s,
+                    Err(e) => { self.0.status = format!("Session open error: {}", e); self.push_log(self.0.status.clone()); return Command::none(); }
+                };
+                self.0.apply_session(session);
+                if !Path::new(&self.0.path).exists() {
+                    // Keep the annotations so the user can point at the moved file and press Open
+                    self.0.status = format!("Session restored, but image not found: {}", self.0.path);
+                    self.push_log(self.0.status.clone());
+                    return Command::none();
+                }
+                self.push_log(format!("Session restored from {}", self.0.session_path));
+                return self.update(Msg::Load);
+            }
+            Msg::SegmentPicked(choice) => { self.0.selected_segment = choice.index; }
+            Msg::OpenRecent(r) => {
+                self.0.path = r.path;
+                self.0.format = None;
+                self.0.base = r.base;
+                self.0.skip = r.skip;
+                return self.update(Msg::Load);
+            }
         }
         Command::none()
     }
@@ -427,7 +659,9 @@ impl Application for App {
             button(text("Open")).on_press(Msg::Load),
             button(text("Analyze")).on_press(Msg::Analyze),
             button(text("Open Example")).on_press(Msg::OpenExample),
+            pick_list(self.0.recent.clone(), None::<RecentFile>, Msg::OpenRecent).placeholder("Recent…"),
             toggler(Some("Bytes".into()), self.0.show_bytes, Msg::ToggleBytes).spacing(10),
+            toggler(Some("Seg+off".into()), self.0.seg_relative, Msg::ToggleSegRelative).spacing(10),
             vertical_rule(1),
             button(if self.0.tab==Tab::Code { text("[Code]") } else { text("Code") }).on_press(Msg::SwitchTab(Tab::Code)),
             button(if self.0.tab==Tab::Disasm { text("[Disasm]") } else { text("Disasm") }).on_press(Msg::SwitchTab(Tab::Disasm)),
@@ -435,14 +669,17 @@ impl Application for App {
             button(if self.0.tab==Tab::Hex { text("[Hex]") } else { text("Hex") }).on_press(Msg::SwitchTab(Tab::Hex)),
             vertical_rule(1),
             text("Search:"),
-            text_input("text | 0xADDR | label", &self.0.search).on_input(Msg::SearchChanged).width(Length::Fixed(240.0)),
+            text_input("text | 0xADDR | label | reg:d4 op:ld.w mem:abs", &self.0.search).id(text_input::Id::new(SEARCH_INPUT)).on_input(Msg::SearchChanged).on_submit(Msg::SearchGo).width(Length::Fixed(240.0)),
             button("Go").on_press(Msg::SearchGo),
             vertical_rule(1),
             button("Copy").on_press(Msg::CopySelection),
             button("Paste").on_press(Msg::PasteToSearch),
             vertical_rule(1),
             button("Save Disasm").on_press(Msg::SaveDisasm),
-            button("Save Bin").on_press(Msg::SaveImageBin),
+            button("Save JSONL").on_press(Msg::SaveDisasmJsonl),
+            button("Save Image").on_press(Msg::SaveImageBin),
+            button("Save Session").on_press(Msg::SaveSession),
+            button("Open Session").on_press(Msg::OpenSession),
             vertical_rule(1),
             button(text(if self.0.show_settings { "Close Settings" } else { "Settings" })).on_press(Msg::ToggleSettings),
         ].spacing(10).align_items(iced::Alignment::Center);
@@ -468,10 +705,13 @@ impl Application for App {
                 else { ColorChoice::Default }
             })), Msg::CodeColorPicked);
 
+            let syntax_pick = pick_list(MnemonicStyle::ALL.to_vec(), Some(self.0.syntax), Msg::SyntaxPicked);
+
             row![
                 text("Theme:"), theme_pick,
                 text("Font size:"), font_pick,
                 text("Code color:"), color_pick,
+                text("Syntax:"), syntax_pick,
             ].spacing(10).align_items(iced::Alignment::Center).into()
         } else { container(column![]).into() };
 
@@ -517,29 +757,36 @@ impl Application for App {
                 col = col.push(text("No instructions to show yet. Analyzing or no code found.").size(self.0.font_size.saturating_sub(2)));
             }
             let mut pcs = self.0.visited.clone();
-            if !self.0.search.trim().is_empty() {
+            // `reg:`/`op:`/`mem:` terms filter on decoded fields; anything else is a text search
+            let query = Query::parse(&self.0.search);
+            if let Err(e) = &query {
+                col = col.push(text(format!("Search: {e}")).size(self.0.font_size.saturating_sub(2)));
+            }
+            if let Ok(Some(query)) = &query {
+                pcs.retain(|pc| fetch_insn(img, *pc).and_then(|raw32| dec.decode(raw32)).is_some_and(|d| query.matches(&d)));
+            } else if !self.0.search.trim().is_empty() {
                 let q = self.0.search.to_lowercase();
                 pcs.retain(|pc| {
                     // address match or mnemonic contains
                     if q.starts_with("0x") { if let Ok(addr) = u32::from_str_radix(q.trim_start_matches("0x"), 16) { return *pc == addr; } }
                     // label match
                     if let Some(name) = self.0.labels.get(pc) { if name.to_lowercase().contains(&q) { return true; } }
-                    if let Some(raw32) = read_u32(img, *pc) {
-                        if let Some(d) = dec.decode(raw32) { return fmt_decoded(&d).to_lowercase().contains(&q); }
+                    if let Some(raw32) = fetch_insn(img, *pc) {
+                        if let Some(d) = dec.decode(raw32) { return self.0.insn_text(img, &d, *pc).to_lowercase().contains(&q); }
                     }
                     false
                 });
             }
             for pc in pcs {
-                if let Some(raw32) = read_u32(img, pc) {
+                if let Some(raw32) = fetch_insn(img, pc) {
                     if let Some(d) = dec.decode(raw32) {
                         let label_prefix = self.0.labels.get(&pc).map(|s| format!("{}: ", s)).unwrap_or_default();
                         let line = if self.0.show_bytes {
                             let mut bytes = Vec::new();
                             for i in 0..(d.width as u32) { bytes.push(read_u8(img, pc + i).unwrap_or(0)); }
-                            format!("{label_prefix}{pc:#010x}: {:02x?}  {}", bytes, fmt_decoded(&d))
+                            format!("{label_prefix}{}: {:02x?}  {}", img.fmt_addr(pc, self.0.seg_relative), bytes, self.0.insn_text(img, &d, pc))
                         } else {
-                            format!("{label_prefix}{pc:#010x}: {}", fmt_decoded(&d))
+                            format!("{label_prefix}{}: {}", img.fmt_addr(pc, self.0.seg_relative), self.0.insn_text(img, &d, pc))
                         };
                         let mut t = text(line).size(self.0.font_size);
                         if let Some(c) = self.0.code_color { t = t.style(theme::Text::Color(c)); }
@@ -553,6 +800,20 @@ impl Application for App {
                                 button("Save").on_press(Msg::SaveLabel),
                             ].spacing(5);
                             col = col.push(edit);
+                            let patch = row![
+                                text("Patch:"),
+                                text_input("asm, e.g. j 0x80000040", &self.0.patch_edit).on_input(Msg::PatchEditChanged).on_submit(Msg::ApplyPatch).width(Length::Fixed(200.0)),
+                                button("Assemble").on_press(Msg::ApplyPatch),
+                            ].spacing(5);
+                            col = col.push(patch);
+                            // Who branches to or calls the selection; click to go there
+                            let refs = self.0.report.as_ref().map(|r| xrefs_to(r, pc)).unwrap_or_default();
+                            let mut xrefs = row![text(format!("Xrefs ({}):", refs.len())).size(self.0.font_size.saturating_sub(2))].spacing(5);
+                            for (from, kind) in refs {
+                                let name = self.0.labels.get(&from).cloned().unwrap_or_else(|| format!("{from:#010x}"));
+                                xrefs = xrefs.push(button(text(format!("{name} ({kind:?})")).size(self.0.font_size.saturating_sub(2))).on_press(Msg::SelectPc(from)));
+                            }
+                            col = col.push(xrefs);
                         }
                     }
                 }
@@ -561,39 +822,28 @@ impl Application for App {
         let code_view: Element<Msg> = match self.0.tab {
             Tab::Code => scrollable(col).height(Length::Fill).width(Length::Fill).into(),
             Tab::Disasm => {
-                // Sequential disassembly of the first segment (preview without analysis)
+                // Sequential disassembly of the picked segment (preview without analysis)
                 let mut lines = column![];
                 if let Some(img) = &self.0.image {
-                    if let Some(seg) = img.segments.first() {
-                        let mut pc = seg.base;
-                        let end = seg.base + seg.bytes.len() as u32;
-                        let dec = Tc16Decoder::new();
-                        let mut count = 0usize;
-                        while pc < end && count < 4000 { // cap to 4000 lines
-                            let raw32 = if let Some(x) = read_u32(img, pc) {
-                                x
-                            } else if let Some(h) = read_u16(img, pc) {
-                                h as u32
-                            } else { break };
-                            if let Some(d) = dec.decode(raw32) {
-                                let mut bytes = Vec::new();
-                                for i in 0..(d.width as u32) { bytes.push(read_u8(img, pc + i).unwrap_or(0)); }
-                                let line = if self.0.show_bytes {
-                                    format!("{pc:#010x}: {:02x?}  {}", bytes, fmt_decoded(&d))
+                    if let Some((index, base, end)) = disasm_range(img, self.0.selected_segment) {
+                        let choices = segment_choices(img);
+                        let picked = choices.get(index).cloned();
+                        lines = lines.push(row![text("Segment:").size(self.0.font_size), pick_list(choices, picked, Msg::SegmentPicked)].spacing(8).align_items(iced::Alignment::Center));
+                        for (pc, d) in iter_insns(img, base, end).take(DISASM_MAX_LINES) {
+                            let line = if let Some(d) = d {
+                                let bytes: Vec<u8> = (0..d.width as u32).map(|i| read_u8(img, pc + i).unwrap_or(0)).collect();
+                                if self.0.show_bytes {
+                                    format!("{}: {:02x?}  {}", img.fmt_addr(pc, self.0.seg_relative), bytes, self.0.insn_text(img, &d, pc))
                                 } else {
-                                    format!("{pc:#010x}: {}", fmt_decoded(&d))
-                                };
-                                lines = lines.push(text(line).size(16));
-                                pc = pc.saturating_add(d.width as u32);
+                                    format!("{}: {}", img.fmt_addr(pc, self.0.seg_relative), self.0.insn_text(img, &d, pc))
+                                }
                             } else {
-                                // Unknown encoding: show as .2byte and advance 2
+                                // Unknown encoding: show as .2byte (iter_insns steps 2)
                                 let b0 = read_u8(img, pc).unwrap_or(0);
                                 let b1 = read_u8(img, pc + 1).unwrap_or(0);
-                                let line = format!("{pc:#010x}: .2byte 0x{:02x}{:02x}", b1, b0);
-                                lines = lines.push(text(line).size(16));
-                                pc = pc.saturating_add(2);
-                            }
-                            count += 1;
+                                format!("{pc:#010x}: .2byte 0x{:02x}{:02x}", b1, b0)
+                            };
+                            lines = lines.push(text(line).size(16));
                         }
                     }
                 } else {
@@ -608,25 +858,61 @@ impl Application for App {
                     toggler(Some("BR".into()), self.0.show_br, Msg::ToggleEdgeBr).spacing(5),
                     toggler(Some("CBR".into()), self.0.show_cbr, Msg::ToggleEdgeCbr).spacing(5),
                     toggler(Some("CALL".into()), self.0.show_call, Msg::ToggleEdgeCall).spacing(5),
+                    toggler(Some("IND".into()), self.0.show_ind, Msg::ToggleEdgeInd).spacing(5),
+                    toggler(Some("Follow".into()), self.0.follow_selection, Msg::ToggleFollow).spacing(5),
+                    toggler(Some("Blocks".into()), self.0.graph_blocks, Msg::ToggleGraphBlocks).spacing(5),
+                    toggler(Some("Map".into()), self.0.show_minimap, Msg::ToggleMinimap).spacing(5),
                 ].spacing(10);
+                // Legend: color, name and number of edges currently drawn
+                let mut legend = row![].spacing(14);
+                for (kind, name, count) in self.0.shown_edge_counts() {
+                    let mut swatch = text("\u{2501}\u{2501}").size(self.0.font_size);
+                    if let Some(c) = edge_color(kind) { swatch = swatch.style(theme::Text::Color(c)); }
+                    legend = legend.push(row![swatch, text(format!("{name} {count}")).size(self.0.font_size)].spacing(4));
+                }
 
-                // Build node list from visited PCs; layout along X by order
-                let mut pcs = self.0.visited.clone();
-                pcs.sort_unstable();
-                let nodes: Vec<u32> = pcs;
+                // One node per visited PC, or one box per block once a report exists
+                let (nodes, edges, selection, boxes) = match (&self.0.image, &self.0.report) {
+                    (Some(img), Some(report)) if self.0.graph_blocks => {
+                        let boxes: std::collections::HashMap<u32, Vec<String>> = report.blocks.iter()
+                            .map(|b| (b.start, block_box_lines(img, b, &self.0.labels, BLOCK_BOX_LINES, |d, pc| self.0.insn_text(img, d, pc))))
+                            .collect();
+                        // Select (and follow) the block holding the selected instruction
+                        let selection = self.0.selection.map(|pc| report.blocks.iter().find(|b| b.start <= pc && pc < b.end).map_or(pc, |b| b.start));
+                        (report.blocks.iter().map(|b| b.start).collect(), block_edges(report), selection, boxes)
+                    }
+                    _ => {
+                        let mut pcs = self.0.visited.clone();
+                        pcs.sort_unstable();
+                        (pcs, self.0.edges.clone(), self.0.selection, Default::default())
+                    }
+                };
                 let graph = GraphCanvas::new(
                     nodes,
-                    self.0.edges.clone(),
+                    edges,
                     self.0.show_ft,
                     self.0.show_br,
                     self.0.show_cbr,
                     self.0.show_call,
-                    self.0.selection,
+                    self.0.show_ind,
+                    selection,
+                    self.0.follow_selection,
                     self.0.labels.clone(),
                     self.0.font_size as f32,
-                );
+                ).with_boxes(boxes).with_minimap(self.0.show_minimap);
                 let canvas = Canvas::new(graph).width(Length::Fill).height(Length::Fill);
-                column![toggles, canvas].spacing(6).into()
+                // Detail panel for the selected node
+                let mut detail = column![text("Selected node").size(self.0.font_size)].spacing(4);
+                match (&self.0.image, self.0.selection) {
+                    (Some(img), Some(pc)) => {
+                        for line in node_detail_lines(img, &self.0.edges, &self.0.labels, pc) {
+                            detail = detail.push(text(line).size(self.0.font_size.saturating_sub(2)));
+                        }
+                    }
+                    _ => { detail = detail.push(text("(click a node)").size(self.0.font_size.saturating_sub(2))); }
+                }
+                let panel = container(scrollable(detail)).width(Length::Fixed(280.0)).padding(6);
+                column![toggles, legend, row![canvas, vertical_rule(1), panel].height(Length::Fill)].spacing(6).into()
             }
             Tab::Hex => {
                 let mut lines = column![];
@@ -713,11 +999,103 @@ impl Application for App {
     }
 }
 
+/// How far back `containing_insn` scans for an instruction start when no
+/// analyzed instruction covers the byte.
+const HEX_BACKSCAN_WINDOW: u32 = 32;
+
+/// Start of the instruction covering `addr`: the nearest analyzed PC at or
+/// before it when that instruction reaches `addr`, else `find_instruction_start`.
+fn containing_insn(img: &Image, visited: &[u32], addr: u32) -> Option<u32> {
+    let dec = Tc16Decoder::new();
+    let i = visited.partition_point(|&pc| pc <= addr);
+    if let Some(&pc) = i.checked_sub(1).and_then(|i| visited.get(i)) {
+        let width = fetch_insn(img, pc).and_then(|w| dec.decode(w)).map_or(0, |d| d.width as u32);
+        if addr < pc.saturating_add(width) { return Some(pc); }
+    }
+    find_instruction_start(img, addr, HEX_BACKSCAN_WINDOW)
+}
+
+/// CRC-32 of the whole segment holding `addr`, to compare a patched image
+/// against a reference build.
+fn segment_crc32(img: &Image, addr: u32) -> Option<u32> {
+    let s = img.segments.iter().find(|s| addr >= s.base && addr - s.base < s.bytes.len() as u32)?;
+    checksum(img, s.base, s.base + s.bytes.len() as u32, ChecksumAlgo::Crc32, false).ok()
+}
+
 fn parse_hex(s: &str) -> Option<u32> {
     let t = s.trim();
     if let Some(h) = t.strip_prefix("0x").or_else(|| t.strip_prefix("0X")) { u32::from_str_radix(h, 16).ok() } else { t.parse().ok() }
 }
 
+/// Overwrite the byte at `addr`; false when no segment maps it.
+fn write_byte(img: &mut Image, addr: u32, v: u8) -> bool {
+    match img.segments.iter_mut().find(|s| addr >= s.base && ((addr - s.base) as usize) < s.bytes.len()) {
+        Some(s) => { s.bytes[(addr - s.base) as usize] = v; true }
+        None => false,
+    }
+}
+
+/// File name and contents for Save Image. A raw dump only round-trips as
+/// raw; anything with several segments or an entry keeps its layout as ELF.
+fn image_file(img: &Image) -> (&'static str, Vec<u8>) {
+    match img.segments.as_slice() {
+        [seg] if img.entry.is_none() => ("image.bin", seg.bytes.clone()),
+        _ => ("image.elf", write_elf(img)),
+    }
+}
+
+/// Assemble `line` over the instruction at `pc` and write it into the image.
+/// A shorter encoding is padded with 16-bit NOPs (0x0000); a wider one is
+/// refused rather than clobbering the next instruction. Returns the bytes
+/// written.
+fn patch_at(img: &mut Image, pc: u32, line: &str) -> Result<Vec<u8>, String> {
+    let old = fetch_insn(img, pc).and_then(|raw32| Tc16Decoder::new().decode(raw32))
+        .map(|d| d.width as usize)
+        .ok_or_else(|| format!("no decodable instruction at {pc:#010x}"))?;
+    let mut bytes = encode_one(line, pc).map_err(|e| e.to_string())?;
+    if bytes.len() > old {
+        return Err(format!("{} bytes do not fit over the {old}-byte instruction at {pc:#010x}", bytes.len()));
+    }
+    bytes.resize(old, 0);
+    let seg = img.segments.iter_mut()
+        .find(|s| pc >= s.base && (pc - s.base) as usize + old <= s.bytes.len())
+        .ok_or_else(|| format!("{pc:#010x} is not inside one segment"))?;
+    let off = (pc - seg.base) as usize;
+    seg.bytes[off..off + old].copy_from_slice(&bytes);
+    Ok(bytes)
+}
+
+/// Picker entries for every segment, labelled with name and range.
+fn segment_choices(img: &Image) -> Vec<SegmentChoice> {
+    img.segments.iter().enumerate().map(|(index, seg)| SegmentChoice {
+        index,
+        label: format!("{} [{:#010x}, {:#010x})", seg.name, seg.base, seg.base.saturating_add(seg.bytes.len() as u32)),
+    }).collect()
+}
+
+/// The segment the Disasm tab shows as `(index, base, end)`, falling back to
+/// the first segment when `selected` is out of range (e.g. a new image loaded).
+fn disasm_range(img: &Image, selected: usize) -> Option<(usize, u32, u32)> {
+    let index = if selected < img.segments.len() { selected } else { 0 };
+    let seg = img.segments.get(index)?;
+    Some((index, seg.base, seg.base.saturating_add(seg.bytes.len() as u32)))
+}
+
+/// The visited PC after (or before) `current` in the sorted `pcs`, stopping
+/// at either end; with nothing selected, the first (or last) one.
+fn step_pc(pcs: &[u32], current: Option<u32>, forward: bool) -> Option<u32> {
+    let last = pcs.len().checked_sub(1)?;
+    let i = match (current.map(|c| pcs.binary_search(&c)), forward) {
+        (None, true) => 0,
+        (None, false) => last,
+        (Some(Ok(i)), true) => (i + 1).min(last),
+        // Not a visited PC: `i` is the first one after it
+        (Some(Err(i)), true) => i.min(last),
+        (Some(Ok(i) | Err(i)), false) => i.saturating_sub(1),
+    };
+    Some(pcs[i])
+}
+
 fn parse_nav(s: &str, labels: &std::collections::HashMap<u32, String>) -> Option<u32> {
     let t = s.trim();
     if t.is_empty() { return None; }
@@ -733,20 +1111,220 @@ fn parse_nav(s: &str, labels: &std::collections::HashMap<u32, String>) -> Option
     None
 }
 
-async fn load_image_async(path: String, base: u32, skip: usize) -> Result<Image> {
-    tokio::task::spawn_blocking(move || load_raw_bin(std::path::Path::new(&path), base, skip, None)).await.unwrap()
+/// Instruction lines shown in a block box before it is cut off.
+const BLOCK_BOX_LINES: usize = 8;
+/// Padding inside a block box, in unscaled pixels.
+const BOX_PAD: f32 = 6.0;
+
+fn box_line_height(font_px: f32) -> f32 { font_px + 2.0 }
+
+/// Text of a block box: a header (label or address), then the block's
+/// instructions, cut to `max_lines` with a count of what was left out.
+fn block_box_lines(img: &Image, b: &Block, labels: &std::collections::HashMap<u32, String>, max_lines: usize, text: impl Fn(&Decoded, u32) -> String) -> Vec<String> {
+    let mut out = vec![labels.get(&b.start).cloned().unwrap_or_else(|| format!("{:#010x}", b.start))];
+    let mut insns = Vec::new();
+    for (pc, d) in iter_insns(img, b.start, b.end) {
+        let Some(d) = d else {
+            insns.push(format!("{pc:#010x}: ??"));
+            break;
+        };
+        insns.push(text(&d, pc));
+    }
+    let hidden = insns.len().saturating_sub(max_lines);
+    insns.truncate(max_lines);
+    out.extend(insns);
+    if hidden > 0 { out.push(format!("… +{hidden} more")); }
+    out
+}
+
+/// Unscaled size of a block box holding `lines` at `font_px`, assuming a
+/// monospace glyph about 0.6em wide.
+fn block_box_size(lines: &[String], font_px: f32) -> Size {
+    let chars = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    Size::new(chars as f32 * font_px * 0.6 + 2.0 * BOX_PAD, lines.len() as f32 * box_line_height(font_px) + 2.0 * BOX_PAD)
+}
+
+/// The report's block-level edges as graph edges.
+fn block_edges(report: &Report) -> Vec<Edge> {
+    report.edges.iter().filter_map(|e| {
+        let kind = match e.kind.as_str() {
+            "ft" => EdgeKind::Fallthrough,
+            "br" => EdgeKind::Branch,
+            "cbr" => EdgeKind::CondBranch,
+            "call" => EdgeKind::Call,
+            "ind" => EdgeKind::Indirect,
+            "ret" => EdgeKind::Return,
+            _ => return None,
+        };
+        Some(Edge { from: e.from, to: e.to, kind })
+    }).collect()
+}
+
+/// Describe one graph node: label, decoded text, raw encoding and outgoing edges.
+fn node_detail_lines(img: &Image, edges: &[Edge], labels: &std::collections::HashMap<u32, String>, pc: u32) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(name) = labels.get(&pc) { out.push(format!("{name}:")); }
+    let dec = Tc16Decoder::new();
+    match fetch_insn(img, pc) {
+        Some(raw32) => match dec.decode_full(raw32) {
+            Ok(d) => {
+                out.push(format!("{pc:#010x}: {}", fmt_decoded(&d)));
+                let raw = if d.width == 2 { format!("{:#06x}", raw32 & 0xFFFF) } else { format!("{raw32:#010x}") };
+                out.push(format!("raw: {raw} ({} bytes)", d.width));
+                out.push(format!("op: {:?}  rd={} rs1={} rs2={} imm={:#x}", d.op, d.rd, d.rs1, d.rs2, d.imm));
+            }
+            Err(e) => out.push(format!("{pc:#010x}: .word {raw32:#010x} (undecoded: {e})")),
+        },
+        None => out.push(format!("{pc:#010x}: <unmapped>")),
+    }
+    out.push("Outgoing:".into());
+    let mut any = false;
+    for e in edges.iter().filter(|e| e.from == pc) {
+        let kind = match e.kind { EdgeKind::Fallthrough => "ft", EdgeKind::Branch => "br", EdgeKind::CondBranch => "cbr", EdgeKind::Call => "call", EdgeKind::Indirect => "ind", EdgeKind::Return => "ret" };
+        let tgt = labels.get(&e.to).cloned().unwrap_or_else(|| format!("{:#010x}", e.to));
+        out.push(format!("  -> {tgt} ({kind})"));
+        any = true;
+    }
+    if !any { out.push("  (none)".into()); }
+    out
+}
+
+/// Read settings; a missing or malformed file yields defaults.
+fn load_settings(path: &str) -> Settings {
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn save_settings(path: &str, settings: &Settings) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
 }
 
-async fn analyze_async(img: Image, seeds: Vec<u32>) -> Result<(Vec<u32>, Vec<Edge>)> {
-    tokio::task::spawn_blocking(move || {
-        let (visited, _w, edges, _r) = analyze_entries(&img, &seeds, 100_000);
-        Ok::<_, anyhow::Error>((visited.into_iter().collect(), edges))
-    }).await.unwrap()
+/// Load `path` as `format`, or in whatever container it is like the CLI,
+/// and report the container used; base and skip only apply to raw dumps.
+fn load_image(path: &str, format: Option<InputFormat>, base: u32, skip: usize) -> Result<(Image, InputFormat)> {
+    let path = Path::new(path);
+    let format = match format { Some(f) => f, None => detect_format(path)? };
+    Ok((load_input(path, Some(format), base, skip, None, Endian::Le)?, format))
+}
+
+async fn load_image_async(path: String, format: Option<InputFormat>, base: u32, skip: usize) -> Result<(Image, InputFormat)> {
+    tokio::task::spawn_blocking(move || load_image(&path, format, base, skip)).await.unwrap()
+}
+
+async fn analyze_async(img: Image, seeds: Vec<u32>) -> Result<Report> {
+    // Same report as the CLI; the graph view draws the instruction-level edges
+    tokio::task::spawn_blocking(move || Ok::<_, anyhow::Error>(build_report(&img, &seeds, 100_000))).await.unwrap()
+}
+
+/// Incremental re-analysis around a one-byte edit at `changed`; without a
+/// previous report this is a full analysis.
+async fn reanalyze_async(img: Image, seeds: Vec<u32>, prev: Option<Report>, changed: u32) -> Result<Report> {
+    tokio::task::spawn_blocking(move || Ok::<_, anyhow::Error>(match prev {
+        Some(prev) => reanalyze_around(&img, &prev, changed, 100_000),
+        None => build_report(&img, &seeds, 100_000),
+    })).await.unwrap()
 }
 
 fn main() -> iced::Result { App::run(iced::Settings::default()) }
 
+impl AppState {
+    /// Clipboard text for the selection: the instruction line in the Code
+    /// tab, the selected byte elsewhere; empty when nothing is selected.
+    fn copy_text(&self) -> String {
+        let Some(img) = &self.image else { return String::new() };
+        match self.tab {
+            Tab::Code => {
+                let Some(pc) = self.selection else { return String::new() };
+                match fetch_insn(img, pc).and_then(|raw32| Tc16Decoder::new().decode(raw32)) {
+                    Some(d) => format!("{pc:#010x}: {}", fmt_decoded(&d)),
+                    None => format!("{pc:#010x}"),
+                }
+            }
+            Tab::Hex | Tab::Disasm | Tab::Graph => match self.selected_addr {
+                Some(addr) => format!("{addr:#010x}: {:#04x}", read_u8(img, addr).unwrap_or(0)),
+                None => String::new(),
+            },
+        }
+    }
+
+    /// Instruction text for the code views: labels first, then targets in the
+    /// address column's style (`segment+offset` or absolute), in the picked syntax.
+    fn insn_text(&self, img: &Image, d: &Decoded, pc: u32) -> String {
+        fmt_decoded_with_style(d, pc, self.syntax, |a| self.labels.get(&a).cloned().or_else(|| self.seg_relative.then(|| img.seg_relative(a)).flatten()))
+    }
+
+    fn to_session(&self) -> Session {
+        Session {
+            path: self.path.clone(),
+            base: self.base.clone(),
+            skip: self.skip.clone(),
+            format: self.format,
+            labels: self.labels.clone(),
+            tab: self.tab,
+            selection: self.selection,
+            selected_addr: self.selected_addr,
+        }
+    }
+
+    /// Whether the graph filters currently draw edges of `kind`.
+    fn edge_shown(&self, kind: EdgeKind) -> bool {
+        match kind {
+            EdgeKind::Fallthrough => self.show_ft,
+            EdgeKind::Branch => self.show_br,
+            EdgeKind::CondBranch => self.show_cbr,
+            EdgeKind::Call => self.show_call,
+            EdgeKind::Indirect => self.show_ind,
+            EdgeKind::Return => false,
+        }
+    }
+
+    /// Per-kind counts of the edges the graph draws; hidden kinds count 0.
+    fn shown_edge_counts(&self) -> Vec<(EdgeKind, &'static str, usize)> {
+        GRAPH_EDGE_KINDS
+            .iter()
+            .map(|&(kind, name)| {
+                let n = if self.edge_shown(kind) { self.edges.iter().filter(|e| e.kind == kind).count() } else { 0 };
+                (kind, name, n)
+            })
+            .collect()
+    }
+
+    /// Move the current path/base/skip to the front of the recent list.
+    fn note_recent(&mut self) {
+        let entry = RecentFile { path: self.path.clone(), base: self.base.clone(), skip: self.skip.clone() };
+        self.recent.retain(|r| r.path != entry.path);
+        self.recent.insert(0, entry);
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// Restore annotations and view state; the caller reloads the image.
+    fn apply_session(&mut self, s: Session) {
+        self.path = s.path;
+        self.base = s.base;
+        self.skip = s.skip;
+        self.format = s.format;
+        self.labels = s.labels;
+        self.tab = s.tab;
+        self.selection = s.selection;
+        self.selected_addr = s.selected_addr;
+        self.label_edit = s.selection.and_then(|pc| self.labels.get(&pc).cloned()).unwrap_or_default();
+    }
+}
+
 impl App {
+    /// Re-analyze after the byte at `addr` was written, reusing the last report.
+    fn reanalyze_after_edit(&mut self, addr: u32) -> Command<Msg> {
+        let Some(img) = self.0.image.clone() else { return Command::none() };
+        let seeds = find_seeds(&img);
+        self.0.status = "Analyzing after hex edit…".into();
+        self.0.analyze_started = Some(Instant::now());
+        self.push_log(self.0.status.clone());
+        Command::perform(reanalyze_async(img, seeds, self.0.report.clone(), addr), |res| match res {
+            Ok(r) => Msg::AnalyzedOk(Box::new(r)),
+            Err(e) => Msg::AnalyzedErr(e.to_string()),
+        })
+    }
+
     fn push_log(&mut self, line: impl Into<String>) {
         let s = line.into();
         eprintln!("[LOG] {}", s);
@@ -763,9 +1341,14 @@ struct GraphCanvas {
     show_br: bool,
     show_cbr: bool,
     show_call: bool,
+    show_ind: bool,
     selection: Option<u32>,
+    follow: bool,
     labels: std::collections::HashMap<u32, String>,
     font_px: f32,
+    // Block mode: text of each block's box, keyed by block start; empty draws instruction nodes
+    boxes: std::collections::HashMap<u32, Vec<String>>,
+    minimap: bool,
 }
 
 impl GraphCanvas {
@@ -776,22 +1359,247 @@ impl GraphCanvas {
         show_br: bool,
         show_cbr: bool,
         show_call: bool,
+        show_ind: bool,
         selection: Option<u32>,
+        follow: bool,
         labels: std::collections::HashMap<u32, String>,
         font_px: f32,
     ) -> Self {
-        Self { nodes, edges, show_ft, show_br, show_cbr, show_call, selection, labels, font_px }
+        Self { nodes, edges, show_ft, show_br, show_cbr, show_call, show_ind, selection, follow, labels, font_px, boxes: Default::default(), minimap: false }
     }
 
-    fn node_pos(&self, pc: u32, bounds: Rectangle) -> Point {
-        // Fallback position (center) — not used by new layout
-        Point::new(bounds.width/2.0, bounds.height/2.0)
+    fn with_boxes(mut self, boxes: std::collections::HashMap<u32, Vec<String>>) -> Self {
+        self.boxes = boxes;
+        self
+    }
+
+    fn with_minimap(mut self, minimap: bool) -> Self {
+        self.minimap = minimap;
+        self
+    }
+
+    /// The minimap for this layout, if it is shown and there is anything to map.
+    fn minimap_for(&self, pos: &std::collections::HashMap<u32, Point>, bounds: Rectangle) -> Option<Minimap> {
+        if !self.minimap { return None; }
+        world_bounds(pos, &self.box_sizes()).map(|world| Minimap::new(bounds.size(), world))
+    }
+
+    /// Unscaled size of each block box.
+    fn box_sizes(&self) -> std::collections::HashMap<u32, Size> {
+        self.boxes.iter().map(|(&pc, lines)| (pc, block_box_size(lines, self.font_px))).collect()
+    }
+
+    /// Where edges leave (`out`) or enter a node, in unscaled coordinates:
+    /// a node's center, or the bottom/top middle of a block box.
+    fn anchor(&self, pos: Point, size: Option<Size>, out: bool) -> Point {
+        match size {
+            Some(sz) => Point::new(pos.x + sz.width / 2.0, if out { pos.y + sz.height } else { pos.y }),
+            None => pos,
+        }
     }
+
+    /// Node positions from `layered_layout` over the edges currently shown.
+    /// Points are node centers, or the top-left corner of block boxes.
+    fn layout(&self, bounds: Rectangle) -> std::collections::HashMap<u32, Point> {
+        let edges: Vec<(u32, u32)> = self.edges.iter()
+            .filter(|e| match e.kind { EdgeKind::Fallthrough => self.show_ft, EdgeKind::Branch => self.show_br, EdgeKind::CondBranch => self.show_cbr, EdgeKind::Call => self.show_call, EdgeKind::Indirect => self.show_ind, EdgeKind::Return => false })
+            .map(|e| (e.from, e.to))
+            .collect();
+        // Room for a node and its caption; block boxes need room for the tallest and widest box
+        let node_r = 6.0_f32;
+        let font = self.font_px.max(12.0);
+        let mut min_gap = Size::new(3.0 * font, (font + 2.0 * node_r + 16.0).max(48.0));
+        if !self.boxes.is_empty() {
+            let (box_w, box_h) = self.box_sizes().values().fold((0.0_f32, 0.0_f32), |(w, h), sz| (w.max(sz.width), h.max(sz.height)));
+            min_gap = Size::new(box_w + 16.0, min_gap.height.max(box_h + 24.0));
+        }
+        layered_layout(&self.nodes, &edges, bounds.size(), min_gap)
+    }
+}
+
+struct GraphState {
+    offset: (f32,f32),
+    scale: f32,
+    dragging: Option<Point>,
+    // Selection the offset was last centered on (or picked on the canvas)
+    centered_on: Option<u32>,
+}
+
+impl Default for GraphState { fn default() -> Self { Self { offset: (40.0, 40.0), scale: 1.0, dragging: None, centered_on: None } } }
+
+impl GraphState {
+    /// Offset to draw with: centered on a newly selected node when following,
+    /// otherwise the panned offset.
+    fn view_offset(&self, g: &GraphCanvas, pos: &std::collections::HashMap<u32, Point>, bounds: Rectangle) -> (f32, f32) {
+        match g.selection {
+            Some(pc) if g.follow && self.centered_on != Some(pc) => {
+                // Block boxes are placed by their corner; center on their middle
+                let mid = |p: Point| g.boxes.get(&pc).map_or(p, |lines| {
+                    let sz = block_box_size(lines, g.font_px);
+                    Point::new(p.x + sz.width / 2.0, p.y + sz.height / 2.0)
+                });
+                pos.get(&pc).map_or(self.offset, |&p| center_offset(mid(p), self.scale, bounds.size()))
+            }
+            _ => self.offset,
+        }
+    }
+}
+
+/// The node drawn closest to `click` (viewport coordinates), if any lies
+/// within the 10px hit radius.
+fn node_at(pos: &std::collections::HashMap<u32, Point>, offset: (f32, f32), scale: f32, click: Point) -> Option<u32> {
+    let mut best: Option<(f32, u32)> = None;
+    for (&pc, p) in pos {
+        let dx = p.x * scale + offset.0 - click.x;
+        let dy = p.y * scale + offset.1 - click.y;
+        let d2 = dx*dx + dy*dy;
+        // Ties go to the lower address so the pick doesn't depend on map order
+        if d2 <= 10.0*10.0 && best.is_none_or(|(bd, bpc)| d2 < bd || (d2 == bd && pc < bpc)) { best = Some((d2, pc)); }
+    }
+    best.map(|(_, pc)| pc)
+}
+
+/// The block box drawn under `click` (viewport coordinates), if any.
+fn box_at(pos: &std::collections::HashMap<u32, Point>, sizes: &std::collections::HashMap<u32, Size>, offset: (f32, f32), scale: f32, click: Point) -> Option<u32> {
+    let mut hits: Vec<u32> = pos.iter().filter(|(pc, p)| {
+        let Some(sz) = sizes.get(*pc) else { return false };
+        let r = Rectangle::new(Point::new(p.x * scale + offset.0, p.y * scale + offset.1), Size::new(sz.width * scale, sz.height * scale));
+        r.contains(click)
+    }).map(|(&pc, _)| pc).collect();
+    hits.sort_unstable();
+    hits.first().copied()
 }
 
-struct GraphState { offset: (f32,f32), scale: f32, dragging: Option<Point> }
+/// Gap between the graph layout and the canvas edge.
+const GRAPH_MARGIN: f32 = 40.0;
 
-impl Default for GraphState { fn default() -> Self { Self { offset: (40.0, 40.0), scale: 1.0, dragging: None } } }
+/// Layered (Sugiyama-style) layout in unscaled canvas coordinates. A node's
+/// level is its BFS depth from the roots (nodes without predecessors); nodes
+/// the walk misses get levels of their own. Levels start in address order,
+/// then alternating barycenter sweeps reorder them to cut edge crossings.
+/// Each level is centered on the widest one, with neighbours and levels at
+/// least `min_gap` apart. The same input always gives the same layout.
+fn layered_layout(nodes: &[u32], edges: &[(u32, u32)], area: Size, min_gap: Size) -> std::collections::HashMap<u32, Point> {
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    let mut sorted = nodes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let known: HashSet<u32> = sorted.iter().copied().collect();
+    let mut succ: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut pred: HashMap<u32, Vec<u32>> = HashMap::new();
+    for &(from, to) in edges {
+        if from == to || !known.contains(&from) || !known.contains(&to) { continue; }
+        succ.entry(from).or_default().push(to);
+        pred.entry(to).or_default().push(from);
+    }
+
+    // BFS levels from the roots; if every node has a predecessor, start at the lowest address
+    let mut level: HashMap<u32, usize> = HashMap::new();
+    let mut q: VecDeque<u32> = sorted.iter().copied().filter(|pc| !pred.contains_key(pc)).collect();
+    if q.is_empty() { q.extend(sorted.first()); }
+    for &r in &q { level.insert(r, 0); }
+    while let Some(u) = q.pop_front() {
+        let lu = level[&u];
+        for &v in succ.get(&u).into_iter().flatten() {
+            if level.get(&v).is_none_or(|&x| lu + 1 < x) { level.insert(v, lu + 1); q.push_back(v); }
+        }
+    }
+    // Unreached nodes: one level each, by address, after the deepest
+    let mut next_level = level.values().copied().max().map_or(0, |m| m + 1);
+    for &pc in &sorted {
+        level.entry(pc).or_insert_with(|| { next_level += 1; next_level - 1 });
+    }
+    let levels = level.values().copied().max().map_or(0, |m| m + 1);
+    let mut rows: Vec<Vec<u32>> = vec![Vec::new(); levels];
+    for &pc in &sorted { rows[level[&pc]].push(pc); }
+
+    // Crossing reduction: order each level by the mean index of its neighbours
+    // on the adjacent level, sweeping down (predecessors) then up (successors)
+    let mut index: HashMap<u32, f32> = HashMap::new();
+    for row in &rows { for (i, &pc) in row.iter().enumerate() { index.insert(pc, i as f32); } }
+    let sweep = |row: &mut Vec<u32>, adjacent: usize, neighbours: &HashMap<u32, Vec<u32>>, index: &mut HashMap<u32, f32>| {
+        let barycenter = |pc: u32| {
+            let ns: Vec<f32> = neighbours.get(&pc).into_iter().flatten().filter(|n| level[*n] == adjacent).map(|n| index[n]).collect();
+            // No neighbours on that level: keep the current place
+            if ns.is_empty() { index[&pc] } else { ns.iter().sum::<f32>() / ns.len() as f32 }
+        };
+        let mut keyed: Vec<(f32, u32)> = row.iter().map(|&pc| (barycenter(pc), pc)).collect();
+        // Stable sort, so ties keep their current order
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (i, (_, pc)) in keyed.into_iter().enumerate() { row[i] = pc; index.insert(pc, i as f32); }
+    };
+    for _ in 0..4 {
+        for (l, row) in rows.iter_mut().enumerate().skip(1) { sweep(row, l - 1, &pred, &mut index); }
+        for (l, row) in rows.iter_mut().enumerate().take(levels.saturating_sub(1)).rev() { sweep(row, l + 1, &succ, &mut index); }
+    }
+
+    let widest = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let avail_w = (area.width - 2.0 * GRAPH_MARGIN).max(1.0);
+    let avail_h = (area.height - 2.0 * GRAPH_MARGIN).max(1.0);
+    let x_step = if widest > 1 { (avail_w / (widest - 1) as f32).max(min_gap.width) } else { 0.0 };
+    let y_step = if levels > 1 { (avail_h / (levels - 1) as f32).max(min_gap.height) } else { 0.0 };
+    let mut pos = HashMap::new();
+    for (l, row) in rows.iter().enumerate() {
+        let x0 = GRAPH_MARGIN + (widest - row.len()) as f32 * x_step / 2.0;
+        for (i, &pc) in row.iter().enumerate() {
+            pos.insert(pc, Point::new(x0 + i as f32 * x_step, GRAPH_MARGIN + l as f32 * y_step));
+        }
+    }
+    pos
+}
+
+/// Size of the minimap overlay and its gap from the canvas corner.
+const MINIMAP_SIZE: Size = Size { width: 160.0, height: 120.0 };
+const MINIMAP_MARGIN: f32 = 10.0;
+
+/// Smallest rectangle (unscaled coordinates) holding every node position and
+/// block box; `None` for an empty graph.
+fn world_bounds(pos: &std::collections::HashMap<u32, Point>, sizes: &std::collections::HashMap<u32, Size>) -> Option<Rectangle> {
+    let mut it = pos.iter().map(|(pc, p)| {
+        let sz = sizes.get(pc).copied().unwrap_or(Size::ZERO);
+        (p.x, p.y, p.x + sz.width, p.y + sz.height)
+    });
+    let first = it.next()?;
+    let (x0, y0, x1, y1) = it.fold(first, |(a, b, c, d), (x0, y0, x1, y1)| (a.min(x0), b.min(y0), c.max(x1), d.max(y1)));
+    Some(Rectangle::new(Point::new(x0, y0), Size::new(x1 - x0, y1 - y0)))
+}
+
+/// Maps between graph coordinates and the minimap in the bottom-right corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Minimap {
+    /// Minimap on screen
+    area: Rectangle,
+    /// Graph extent it shows
+    world: Rectangle,
+    /// Minimap pixels per graph unit, the same on both axes
+    k: f32,
+}
+
+impl Minimap {
+    fn new(viewport: Size, world: Rectangle) -> Self {
+        let area = Rectangle::new(
+            Point::new(viewport.width - MINIMAP_SIZE.width - MINIMAP_MARGIN, viewport.height - MINIMAP_SIZE.height - MINIMAP_MARGIN),
+            MINIMAP_SIZE,
+        );
+        let k = (area.width / world.width.max(1.0)).min(area.height / world.height.max(1.0));
+        Self { area, world, k }
+    }
+
+    fn to_map(self, p: Point) -> Point {
+        Point::new(self.area.x + (p.x - self.world.x) * self.k, self.area.y + (p.y - self.world.y) * self.k)
+    }
+
+    fn to_world(self, p: Point) -> Point {
+        Point::new(self.world.x + (p.x - self.area.x) / self.k, self.world.y + (p.y - self.area.y) / self.k)
+    }
+}
+
+/// Offset that puts `node` (unscaled canvas coordinates) at the middle of a
+/// `viewport` drawn at `scale`.
+fn center_offset(node: Point, scale: f32, viewport: Size) -> (f32, f32) {
+    (viewport.width / 2.0 - node.x * scale, viewport.height / 2.0 - node.y * scale)
+}
 
 impl Program<Msg> for GraphCanvas {
     type State = GraphState;
@@ -805,70 +1613,11 @@ impl Program<Msg> for GraphCanvas {
         _cursor: mouse::Cursor,
     ) -> Vec<<iced::Renderer as CanvasRenderer>::Geometry> {
         let mut frame = Frame::new(renderer, Size::new(bounds.width, bounds.height));
-        let (ox, oy) = state.offset;
         let sc = state.scale;
 
-        use std::collections::HashMap;
-
-        // Compute layered layout from edges: level per node (BFS from roots), then X per level
-        let margin = 40.0;
-        let node_r = 6.0_f32;
-        let avail_w = (bounds.width - 2.0 * margin).max(1.0);
-        let avail_h = (bounds.height - 2.0 * margin).max(1.0);
-
-        // Build filtered adjacency and indegree for layout
-        let mut adj: HashMap<u32, Vec<u32>> = HashMap::new();
-        let mut indeg: HashMap<u32, usize> = HashMap::new();
-        for &pc in &self.nodes { indeg.entry(pc).or_insert(0); adj.entry(pc).or_insert_with(Vec::new); }
-        for e in &self.edges {
-            let show = match e.kind { EdgeKind::Fallthrough => self.show_ft, EdgeKind::Branch => self.show_br, EdgeKind::CondBranch => self.show_cbr, EdgeKind::Call => self.show_call };
-            if !show { continue; }
-            adj.entry(e.from).or_default().push(e.to);
-            *indeg.entry(e.to).or_insert(0) += 1;
-            indeg.entry(e.from).or_insert(0);
-        }
-        // Roots: zero indegree; if none, pick first node
-        let mut roots: Vec<u32> = self.nodes.iter().copied().filter(|pc| indeg.get(pc).copied().unwrap_or(0) == 0).collect();
-        if roots.is_empty() { if let Some(&first) = self.nodes.first() { roots.push(first); } }
-        // BFS levels
-        let mut level: HashMap<u32, usize> = HashMap::new();
-        use std::collections::VecDeque;
-        let mut q = VecDeque::new();
-        for r in roots { level.insert(r, 0); q.push_back(r); }
-        while let Some(u) = q.pop_front() {
-            let lu = level[&u];
-            if let Some(vs) = adj.get(&u) {
-                for &v in vs {
-                    let lv = level.get(&v).copied();
-                    if lv.map_or(true, |x| lu + 1 < x) { level.insert(v, lu + 1); q.push_back(v); }
-                }
-            }
-        }
-        // Assign any missing nodes: order by address, append after max level
-        let max_level = level.values().copied().max().unwrap_or(0);
-        let mut next_level = max_level + 1;
-        for &pc in &self.nodes {
-            if !level.contains_key(&pc) { level.insert(pc, next_level); next_level += 1; }
-        }
-        let levels_total = level.values().copied().max().unwrap_or(0) + 1;
-        let min_v_spacing = (self.font_px.max(12.0) + 2.0 * node_r + 16.0).max(48.0);
-        let mut y_step = if levels_total > 1 { avail_h / (levels_total as f32 - 1.0) } else { 0.0 };
-        if y_step < min_v_spacing && levels_total > 1 { y_step = min_v_spacing; }
-        // Group nodes per level ordered by address
-        let mut by_level: HashMap<usize, Vec<u32>> = HashMap::new();
-        for &pc in &self.nodes { by_level.entry(level[&pc]).or_default().push(pc); }
-        for vs in by_level.values_mut() { vs.sort_unstable(); }
-        // Compute positions
-        let mut pos: HashMap<u32, Point> = HashMap::new();
-        for (lev, vs) in by_level.iter() {
-            let k = vs.len();
-            let y = margin + (*lev as f32) * y_step;
-            let x_step = if k > 1 { avail_w / (k as f32 - 1.0) } else { 0.0 };
-            for (i, pc) in vs.iter().enumerate() {
-                let x = margin + (i as f32) * x_step;
-                pos.insert(*pc, Point::new(x, y));
-            }
-        }
+        let pos = self.layout(bounds);
+        let sizes = self.box_sizes();
+        let (ox, oy) = state.view_offset(self, &pos, bounds);
 
         // Draw edges with arrowheads
         for e in &self.edges {
@@ -877,18 +1626,16 @@ impl Program<Msg> for GraphCanvas {
                 EdgeKind::Branch => self.show_br,
                 EdgeKind::CondBranch => self.show_cbr,
                 EdgeKind::Call => self.show_call,
+                EdgeKind::Indirect => self.show_ind,
+                // Drawn as a node style, not a line
+                EdgeKind::Return => false,
             };
             if !show { continue; }
-            let p0w = pos.get(&e.from).copied().unwrap_or(Point::new(bounds.width/2.0, bounds.height/2.0));
-            let p1w = pos.get(&e.to).copied().unwrap_or(Point::new(bounds.width/2.0, bounds.height/2.0));
+            let p0w = self.anchor(pos.get(&e.from).copied().unwrap_or(Point::new(bounds.width/2.0, bounds.height/2.0)), sizes.get(&e.from).copied(), true);
+            let p1w = self.anchor(pos.get(&e.to).copied().unwrap_or(Point::new(bounds.width/2.0, bounds.height/2.0)), sizes.get(&e.to).copied(), false);
             let p0 = Point::new(p0w.x * sc + ox, p0w.y * sc + oy);
             let p1 = Point::new(p1w.x * sc + ox, p1w.y * sc + oy);
-            let color = match e.kind {
-                EdgeKind::Fallthrough => Color::from_rgb(0.6,0.6,0.6),
-                EdgeKind::Branch => Color::from_rgb(0.9,0.7,0.2),
-                EdgeKind::CondBranch => Color::from_rgb(0.2,0.7,0.9),
-                EdgeKind::Call => Color::from_rgb(0.4,0.95,0.4),
-            };
+            let Some(color) = edge_color(e.kind) else { continue };
             let stroke = Stroke { width: 2.0, style: CanvasStyle::Solid(color), ..Default::default() };
             let path = CanvasPath::line(p0, p1);
             frame.stroke(&path, stroke);
@@ -912,10 +1659,28 @@ impl Program<Msg> for GraphCanvas {
             }
         }
 
-        // Draw nodes + captions
+        // Draw nodes + captions; RET nodes get a second outline so returns stand apart from tail calls
+        let returning: std::collections::HashSet<u32> = self.edges.iter().filter(|e| matches!(e.kind, EdgeKind::Return)).map(|e| e.from).collect();
         for &pc in &self.nodes {
             let pw = pos.get(&pc).copied().unwrap_or(Point::new(bounds.width/2.0, bounds.height/2.0));
             let p = Point::new(pw.x * sc + ox, pw.y * sc + oy);
+            if let (Some(lines), Some(sz)) = (self.boxes.get(&pc), sizes.get(&pc)) {
+                // Block box: outline plus its lines, all scaled with the view
+                let selected = Some(pc) == self.selection;
+                let rect = CanvasPath::rectangle(p, Size::new(sz.width * sc, sz.height * sc));
+                let outline = if returning.contains(&pc) { Color::from_rgb(0.9, 0.35, 0.35) } else if selected { Color::from_rgb(1.0, 1.0, 1.0) } else { Color::from_rgb(0.6, 0.6, 0.6) };
+                frame.stroke(&rect, Stroke { width: if selected { 3.0 } else { 1.5 }, style: CanvasStyle::Solid(outline), ..Default::default() });
+                for (i, line) in lines.iter().enumerate() {
+                    frame.fill_text(CanvasText {
+                        content: line.clone(),
+                        position: Point::new(p.x + BOX_PAD * sc, p.y + (BOX_PAD + i as f32 * box_line_height(self.font_px)) * sc),
+                        color: if i == 0 { Color::from_rgb(1.0, 1.0, 0.6) } else { Color::from_rgb(0.85, 0.85, 0.85) },
+                        size: self.font_px * sc,
+                        ..Default::default()
+                    });
+                }
+                continue;
+            }
             let circle = CanvasPath::circle(p, 6.0);
             let stroke = Stroke {
                 width: if Some(pc) == self.selection { 3.0 } else { 1.5 },
@@ -923,6 +1688,10 @@ impl Program<Msg> for GraphCanvas {
                 ..Default::default()
             };
             frame.stroke(&circle, stroke);
+            if returning.contains(&pc) {
+                let ring = Stroke { width: 1.5, style: CanvasStyle::Solid(Color::from_rgb(0.9, 0.35, 0.35)), ..Default::default() };
+                frame.stroke(&CanvasPath::circle(p, 9.0), ring);
+            }
 
             // Caption: label if present, else short address
             let caption = self.labels.get(&pc).cloned().unwrap_or_else(|| format!("{pc:#06x}"));
@@ -938,31 +1707,48 @@ impl Program<Msg> for GraphCanvas {
             text.vertical_alignment = iced::alignment::Vertical::Top;
             frame.fill_text(text);
         }
+        // Minimap: every node as a dot, plus the part of the graph in view
+        if let Some(map) = self.minimap_for(&pos, bounds) {
+            frame.fill_rectangle(map.area.position(), map.area.size(), Color::from_rgba(0.1, 0.1, 0.1, 0.85));
+            frame.stroke(&CanvasPath::rectangle(map.area.position(), map.area.size()), Stroke { width: 1.0, style: CanvasStyle::Solid(Color::from_rgb(0.5, 0.5, 0.5)), ..Default::default() });
+            for &pc in &self.nodes {
+                let Some(&p) = pos.get(&pc) else { continue };
+                let p = sizes.get(&pc).map_or(p, |sz| Point::new(p.x + sz.width / 2.0, p.y + sz.height / 2.0));
+                let color = if Some(pc) == self.selection { Color::from_rgb(1.0, 1.0, 1.0) } else { Color::from_rgb(0.7, 0.7, 0.7) };
+                frame.fill(&CanvasPath::circle(map.to_map(p), 1.5), color);
+            }
+            let view = Rectangle::new(Point::new(-ox / sc, -oy / sc), Size::new(bounds.width / sc, bounds.height / sc));
+            let tl = map.to_map(view.position());
+            let br = map.to_map(Point::new(view.x + view.width, view.y + view.height));
+            frame.stroke(&CanvasPath::rectangle(tl, Size::new(br.x - tl.x, br.y - tl.y)), Stroke { width: 1.5, style: CanvasStyle::Solid(Color::from_rgb(1.0, 1.0, 0.4)), ..Default::default() });
+        }
         vec![frame.into_geometry()]
     }
 
     fn update(&self, state: &mut Self::State, event: canvas::Event, bounds: Rectangle, cursor: mouse::Cursor) -> (canvas::event::Status, Option<Msg>) {
         use canvas::event::Status;
+        // Commit a follow recenter so later panning and zooming start from it
+        if self.follow && self.selection != state.centered_on {
+            state.offset = state.view_offset(self, &self.layout(bounds), bounds);
+            state.centered_on = self.selection;
+        }
         match event {
             canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
                 if let Some(pos) = cursor.position_in(bounds) {
-                    // Hit-test nodes (apply inverse transform)
-                    let (ox, oy) = state.offset;
-                    let sc = state.scale;
-                    let inv = |p: Point| Point::new((p.x - ox)/sc, (p.y - oy)/sc);
+                    // Same layout and pan/zoom transform as `draw`
+                    let layout = self.layout(bounds);
+                    // A click in the minimap centers the main view on that spot
+                    if let Some(map) = self.minimap_for(&layout, bounds).filter(|m| m.area.contains(pos)) {
+                        state.offset = center_offset(map.to_world(pos), state.scale, bounds.size());
+                        return (Status::Captured, None);
+                    }
                     state.dragging = Some(pos);
-                    let posw = inv(pos);
-                    let mut best: Option<(f32, u32)> = None;
-                    for &pc in &self.nodes {
-                        let p = self.node_pos(pc, bounds);
-                        let dx = p.x - pos.x;
-                        let dy = p.y - pos.y;
-                        let d2 = dx*dx + dy*dy;
-                        if d2 <= 10.0*10.0 {
-                            if best.map_or(true, |(bd, _)| d2 < bd) { best = Some((d2, pc)); }
-                        }
+                    let hit = if self.boxes.is_empty() { node_at(&layout, state.offset, state.scale, pos) } else { box_at(&layout, &self.box_sizes(), state.offset, state.scale, pos) };
+                    if let Some(pc) = hit {
+                        // Clicked nodes are already in view; don't recenter on them
+                        state.centered_on = Some(pc);
+                        return (Status::Captured, Some(Msg::SelectPc(pc)));
                     }
-                    if let Some((_, pc)) = best { return (Status::Captured, Some(Msg::SelectPc(pc))); }
                 }
                 (Status::Ignored, None)
             }
@@ -988,3 +1774,234 @@ impl Program<Msg> for GraphCanvas {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_round_trip_restores_labels_and_selection() {
+        let mut st = AppState { path: "fw.s19".into(), base: "0x80000000".into(), format: Some(InputFormat::Srec), tab: Tab::Graph, selection: Some(0x8000_0010), ..Default::default() };
+        st.labels.insert(0x8000_0010, "main".into());
+        st.labels.insert(0x8000_0040, "helper".into());
+        let json = serde_json::to_string(&st.to_session()).unwrap();
+
+        let mut restored = AppState::default();
+        restored.apply_session(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.labels, st.labels);
+        assert_eq!(restored.selection, Some(0x8000_0010));
+        assert_eq!(restored.tab, Tab::Graph);
+        assert_eq!(restored.path, "fw.s19");
+        assert_eq!(restored.format, Some(InputFormat::Srec));
+        assert_eq!(restored.label_edit, "main");
+        assert_eq!(restored.to_session(), st.to_session());
+
+        // Sessions written before the format was recorded said "raw"
+        let old = json.replace("\"srec\"", "\"raw\"");
+        assert_eq!(serde_json::from_str::<Session>(&old).unwrap().format, Some(InputFormat::Bin));
+    }
+
+    #[test]
+    fn opening_a_file_adds_it_to_recent_without_duplicates() {
+        let mut st = AppState { path: "a.bin".into(), base: "0x0".into(), ..Default::default() };
+        st.note_recent();
+        st.path = "b.bin".into();
+        st.note_recent();
+        st.path = "a.bin".into();
+        st.base = "0x80000000".into();
+        st.note_recent();
+        let paths: Vec<&str> = st.recent.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["a.bin", "b.bin"]);
+        assert_eq!(st.recent[0].base, "0x80000000", "reopening keeps the latest base");
+
+        for i in 0..MAX_RECENT + 3 { st.path = format!("f{i}.bin"); st.note_recent(); }
+        assert_eq!(st.recent.len(), MAX_RECENT);
+    }
+
+    #[test]
+    fn legend_counts_follow_edge_filters() {
+        let e = |from, to, kind| Edge { from, to, kind };
+        let mut st = AppState {
+            edges: vec![e(0, 2, EdgeKind::Fallthrough), e(2, 8, EdgeKind::Call), e(4, 8, EdgeKind::Call), e(8, 8, EdgeKind::Return)],
+            show_ft: true, show_br: true, show_cbr: true, show_call: true, show_ind: true,
+            ..Default::default()
+        };
+        let counts = |st: &AppState| st.shown_edge_counts().into_iter().map(|(_, name, n)| (name, n)).collect::<Vec<_>>();
+        assert_eq!(counts(&st), [("FT", 1), ("BR", 0), ("CBR", 0), ("CALL", 2), ("IND", 0)]);
+        st.show_call = false;
+        assert_eq!(counts(&st)[3], ("CALL", 0));
+    }
+
+    #[test]
+    fn step_pc_moves_between_visited_pcs_and_stops_at_the_ends() {
+        let pcs = [0x100, 0x102, 0x106];
+        assert_eq!(step_pc(&pcs, None, true), Some(0x100));
+        assert_eq!(step_pc(&pcs, None, false), Some(0x106));
+        assert_eq!(step_pc(&pcs, Some(0x100), true), Some(0x102));
+        assert_eq!(step_pc(&pcs, Some(0x102), false), Some(0x100));
+        assert_eq!(step_pc(&pcs, Some(0x106), true), Some(0x106));
+        assert_eq!(step_pc(&pcs, Some(0x100), false), Some(0x100));
+        // A selection between instructions moves to its neighbours
+        assert_eq!(step_pc(&pcs, Some(0x104), true), Some(0x106));
+        assert_eq!(step_pc(&pcs, Some(0x104), false), Some(0x102));
+        assert_eq!(step_pc(&[], None, true), None);
+    }
+
+    #[test]
+    fn copy_text_formats_the_selected_instruction_or_byte() {
+        let img = Image { segments: vec![tricore_disasm::model::Segment { name: "s".into(), base: 0x100, bytes: vec![0x82, 0x10, 0xAB, 0x00], perms: "r-x", kind: "raw" }], entry: None };
+        let mut st = AppState { image: Some(img), tab: Tab::Code, ..Default::default() };
+        assert_eq!(st.copy_text(), "");
+        st.selection = Some(0x100);
+        assert_eq!(st.copy_text(), "0x00000100: mov d0, #0x1");
+        st.tab = Tab::Hex;
+        st.selected_addr = Some(0x102);
+        assert_eq!(st.copy_text(), "0x00000102: 0xab");
+    }
+
+    #[test]
+    fn disasm_range_follows_the_picked_segment() {
+        let seg = |name: &str, base, len| tricore_disasm::model::Segment { name: name.into(), base, bytes: vec![0; len], perms: "r-x", kind: "elf" };
+        let img = Image { segments: vec![seg(".text", 0x8000_0000, 0x20), seg(".rodata", 0x8000_1000, 6)], entry: None };
+        assert_eq!(disasm_range(&img, 0), Some((0, 0x8000_0000, 0x8000_0020)));
+        assert_eq!(disasm_range(&img, 1), Some((1, 0x8000_1000, 0x8000_1006)));
+        assert_eq!(disasm_range(&img, 5), Some((0, 0x8000_0000, 0x8000_0020)), "a stale index shows the first segment");
+        assert_eq!(disasm_range(&Image { segments: vec![], entry: None }, 0), None);
+        assert_eq!(segment_choices(&img)[1].to_string(), ".rodata [0x80001000, 0x80001006)");
+    }
+
+    #[test]
+    fn center_offset_puts_the_node_mid_viewport_at_any_scale() {
+        let viewport = Size::new(800.0, 600.0);
+        let node = Point::new(100.0, 50.0);
+        for scale in [0.5, 1.0, 2.5] {
+            let (ox, oy) = center_offset(node, scale, viewport);
+            assert_eq!((node.x * scale + ox, node.y * scale + oy), (400.0, 300.0));
+        }
+        assert_eq!(center_offset(node, 2.0, viewport), (200.0, 200.0));
+    }
+
+    #[test]
+    fn layout_places_nodes_by_level_and_hit_testing_finds_them() {
+        let e = |from, to, kind| Edge { from, to, kind };
+        // 0x100 branches to 0x110 and calls 0x200; 0x300 is unreachable
+        let edges = vec![e(0x100, 0x110, EdgeKind::CondBranch), e(0x100, 0x200, EdgeKind::Call), e(0x110, 0x200, EdgeKind::Branch)];
+        let g = GraphCanvas::new(vec![0x100, 0x110, 0x200, 0x300], edges, true, true, true, true, true, None, false, Default::default(), 16.0);
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(400.0, 300.0));
+        let pos = g.layout(bounds);
+        assert_eq!(pos, g.layout(bounds), "layout is deterministic");
+        // Level 0: both roots (0x300 has no predecessor); level 1: the successors.
+        // Both levels are equally wide, so each spans the 40px margins.
+        assert_eq!(pos[&0x100], Point::new(40.0, 40.0));
+        assert_eq!(pos[&0x300], Point::new(360.0, 40.0));
+        assert_eq!(pos[&0x110], Point::new(40.0, 260.0));
+        assert_eq!(pos[&0x200], Point::new(360.0, 260.0));
+
+        let (offset, scale) = ((10.0, 20.0), 2.0);
+        assert_eq!(node_at(&pos, offset, scale, Point::new(730.0, 540.0)), Some(0x200));
+        assert_eq!(node_at(&pos, offset, scale, Point::new(95.0, 105.0)), Some(0x100));
+        assert_eq!(node_at(&pos, offset, scale, Point::new(200.0, 200.0)), None);
+    }
+
+    #[test]
+    fn block_boxes_list_their_instructions_and_size_to_fit() {
+        // 0x100: mov d0, #1; mov d1, #2; mov d2, #3 (16-bit each)
+        let img = Image { segments: vec![tricore_disasm::model::Segment { name: "s".into(), base: 0x100, bytes: vec![0x82, 0x10, 0x82, 0x21, 0x82, 0x32], perms: "r-x", kind: "raw" }], entry: None };
+        let b = Block { start: 0x100, end: 0x106 };
+        let text = |d: &Decoded, _pc| fmt_decoded(d);
+        let mut labels = std::collections::HashMap::new();
+        assert_eq!(block_box_lines(&img, &b, &labels, 8, text), ["0x00000100", "mov d0, #0x1", "mov d1, #0x2", "mov d2, #0x3"]);
+        labels.insert(0x100, "main".to_string());
+        assert_eq!(block_box_lines(&img, &b, &labels, 2, text), ["main", "mov d0, #0x1", "mov d1, #0x2", "… +1 more"]);
+
+        // Widest line is 12 chars: 12 * 10 * 0.6 + 2 * pad; 4 lines of 12px + 2 * pad
+        let size = block_box_size(&block_box_lines(&img, &b, &labels, 2, text), 10.0);
+        assert_eq!(size, Size::new(72.0 + 2.0 * BOX_PAD, 48.0 + 2.0 * BOX_PAD));
+
+        let sizes: std::collections::HashMap<u32, Size> = [(0x100, size)].into();
+        let pos: std::collections::HashMap<u32, Point> = [(0x100, Point::new(40.0, 40.0))].into();
+        assert_eq!(box_at(&pos, &sizes, (0.0, 0.0), 1.0, Point::new(100.0, 90.0)), Some(0x100));
+        assert_eq!(box_at(&pos, &sizes, (0.0, 0.0), 1.0, Point::new(30.0, 90.0)), None);
+    }
+
+    #[test]
+    fn world_bounds_cover_nodes_and_boxes_and_map_to_the_minimap() {
+        let pos: std::collections::HashMap<u32, Point> = [(0, Point::new(40.0, 40.0)), (4, Point::new(360.0, 40.0)), (8, Point::new(120.0, 400.0))].into();
+        assert_eq!(world_bounds(&pos, &Default::default()), Some(Rectangle::new(Point::new(40.0, 40.0), Size::new(320.0, 360.0))));
+        // A box at 0x8 extends the bounds by its size
+        let sizes: std::collections::HashMap<u32, Size> = [(8, Size::new(300.0, 80.0))].into();
+        let world = world_bounds(&pos, &sizes).unwrap();
+        assert_eq!(world, Rectangle::new(Point::new(40.0, 40.0), Size::new(380.0, 440.0)));
+        assert_eq!(world_bounds(&Default::default(), &sizes), None);
+
+        // 440 tall into 120 px: the taller axis decides the scale
+        let map = Minimap::new(Size::new(800.0, 600.0), world);
+        assert_eq!(map.area, Rectangle::new(Point::new(630.0, 470.0), MINIMAP_SIZE));
+        assert_eq!(map.to_map(Point::new(40.0, 40.0)), Point::new(630.0, 470.0));
+        assert_eq!(map.to_map(Point::new(40.0, 480.0)), Point::new(630.0, 590.0));
+        let p = Point::new(200.0, 260.0);
+        let back = map.to_world(map.to_map(p));
+        assert!((back.x - p.x).abs() < 1e-3 && (back.y - p.y).abs() < 1e-3, "{back:?}");
+    }
+
+    #[test]
+    fn layered_layout_puts_a_diamond_side_by_side_and_uncrosses_edges() {
+        let area = Size::new(400.0, 300.0);
+        let gap = Size::new(48.0, 48.0);
+        // Diamond 0 -> {4, 8} -> 12
+        let edges = [(0, 4), (0, 8), (4, 12), (8, 12)];
+        let pos = layered_layout(&[12, 8, 4, 0], &edges, area, gap);
+        assert_eq!(pos, layered_layout(&[0, 4, 8, 12], &edges, area, gap), "input order does not matter");
+        assert_eq!(pos[&4].y, pos[&8].y);
+        assert!(pos[&8].x - pos[&4].x >= gap.width, "{pos:?}");
+        assert!(pos[&0].y < pos[&4].y && pos[&4].y < pos[&12].y);
+        // Single-node levels sit centered between the two branches
+        assert_eq!(pos[&0].x, (pos[&4].x + pos[&8].x) / 2.0);
+        assert_eq!(pos[&12].x, pos[&0].x);
+
+        // Address order would cross 0x0 -> 0x30 with 0x10 -> 0x20; the sweep swaps the lower level
+        let pos = layered_layout(&[0x0, 0x10, 0x20, 0x30], &[(0x0, 0x30), (0x10, 0x20)], area, gap);
+        assert!(pos[&0x0].x < pos[&0x10].x);
+        assert!(pos[&0x30].x < pos[&0x20].x, "{pos:?}");
+    }
+
+    #[test]
+    fn patch_pads_a_shorter_encoding_and_refuses_a_wider_one() {
+        // 0x100: mov.u d0, #0x1234 (32-bit); 0x104: mov d1, #2 (16-bit)
+        let mut bytes = ((0x1234u32 << 12) | 0xBB).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0x82, 0x21]);
+        let mut img = Image { segments: vec![tricore_disasm::model::Segment { name: "s".into(), base: 0x100, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        assert_eq!(patch_at(&mut img, 0x100, "mov d0, #1").unwrap(), [0x82, 0x10, 0x00, 0x00]);
+        assert_eq!(img.segments[0].bytes[..4], [0x82, 0x10, 0x00, 0x00]);
+        assert!(patch_at(&mut img, 0x104, "mov d1, #0x1234").unwrap_err().contains("do not fit"));
+        assert_eq!(img.segments[0].bytes[4..], [0x82, 0x21]);
+    }
+
+    #[test]
+    fn an_edited_elf_saves_back_as_elf() {
+        let seg = |name: &str, base, bytes| tricore_disasm::model::Segment { name: name.into(), base, bytes, perms: "r-x", kind: "code" };
+        let src = Image::new(vec![seg("load0", 0x8000_0000, vec![0x82, 0x10, 0x00, 0x90]), seg("load1", 0xD000_0000, vec![1, 2, 3, 4])], Some(0x8000_0000)).unwrap();
+        let path = std::env::temp_dir().join(format!("tricore_gui_save_{}.elf", std::process::id()));
+        std::fs::write(&path, write_elf(&src)).unwrap();
+        let (mut img, format) = load_image(path.to_str().unwrap(), None, 0, 0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(format, InputFormat::Elf);
+        assert_eq!(img.segments.len(), 2);
+
+        assert!(write_byte(&mut img, 0xD000_0002, 0xAB));
+        assert!(!write_byte(&mut img, 0xD000_0004, 0xAB), "past the end of load1");
+        let (name, data) = image_file(&img);
+        assert_eq!(name, "image.elf");
+        let saved = tricore_disasm::model::parse_elf(&data).unwrap();
+        assert_eq!(saved.entry, Some(0x8000_0000));
+        assert_eq!(saved.segments.iter().map(|s| s.base).collect::<Vec<_>>(), [0x8000_0000, 0xD000_0000]);
+        assert_eq!(saved.segments[0].bytes, [0x82, 0x10, 0x00, 0x90]);
+        assert_eq!(saved.segments[1].bytes, [1, 2, 0xAB, 4]);
+
+        // A recorded format wins over sniffing
+        std::fs::write(&path, &data).unwrap();
+        let (raw, format) = load_image(path.to_str().unwrap(), Some(InputFormat::Bin), 0, 0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((raw.segments.len(), format), (1, InputFormat::Bin));
+    }
+}
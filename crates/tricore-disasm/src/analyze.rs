@@ -1,18 +1,29 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use serde::Serialize;
 
-use tricore_rs::decoder::Decoder;
+use tricore_rs::decoder::{DecodeReason, Decoded, Decoder};
 use tricore_rs::isa::tc16::Tc16Decoder;
 
-use crate::model::{Image, is_mapped, read_u32};
+use tricore_rs::disasm::{abs_target, fmt_decoded_parts, fmt_decoded_with_style, MnemonicStyle, Operand};
 
-#[derive(Debug, Clone, Copy)]
-pub enum EdgeKind { Fallthrough, Branch, CondBranch, Call }
+use crate::model::{fetch_insn, Image, is_mapped, read_u8, read_u16, read_u32};
+
+/// `Return` is a synthetic marker on a RET: `to` equals `from`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind { Fallthrough, Branch, CondBranch, Call, Indirect, Return }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Edge { pub from: u32, pub to: u32, pub kind: EdgeKind }
 
+/// Jump table entries followed per `ji`/`calli` dispatch unless overridden.
+pub const DEFAULT_MAX_JUMP_TABLE: usize = 256;
+
 pub fn analyze_entries(img: &Image, entries: &[u32], max_instr: usize) -> (HashSet<u32>, HashMap<u32, u8>, Vec<Edge>, HashSet<u32>) {
+    analyze_entries_with(img, entries, max_instr, DEFAULT_MAX_JUMP_TABLE)
+}
+
+/// `analyze_entries` reading at most `max_table` entries per recognized jump table.
+pub fn analyze_entries_with(img: &Image, entries: &[u32], max_instr: usize, max_table: usize) -> (HashSet<u32>, HashMap<u32, u8>, Vec<Edge>, HashSet<u32>) {
     let dec = Tc16Decoder::new();
     let mut queue: VecDeque<u32> = VecDeque::new();
     let mut visited: HashSet<u32> = HashSet::new();
@@ -24,7 +35,8 @@ pub fn analyze_entries(img: &Image, entries: &[u32], max_instr: usize) -> (HashS
     while let Some(pc) = queue.pop_front() {
         if steps >= max_instr { break; }
         if !visited.insert(pc) { continue; }
-        let Some(raw32) = read_u32(img, pc) else { continue; };
+        // Instructions may straddle adjacent segments; a real gap just ends this path
+        let Some(raw32) = fetch_insn(img, pc) else { continue; };
         if let Some(d) = dec.decode(raw32) {
             steps += 1;
             widths.insert(pc, d.width);
@@ -32,13 +44,14 @@ pub fn analyze_entries(img: &Image, entries: &[u32], max_instr: usize) -> (HashS
             // Branch classification
             use tricore_rs::decoder::Op::*;
             match d.op {
-                J => {
+                J | LoopU => {
                     let tgt = ft.wrapping_add(d.imm as u32);
                     edges.push(Edge { from: pc, to: tgt, kind: EdgeKind::Branch });
                     if is_mapped(img, tgt) && !visited.contains(&tgt) { queue.push_back(tgt); }
                 }
                 Jeq | Jne | JeqImm | JneImm | Jge | JgeU | JgeImm | JgeUImm |
-                Jlt | JltU | JltImm | JltUImm | JeqA | JneA | Bne | JzA | JnzA => {
+                Jlt | JltU | JltImm | JltUImm | JeqA | JneA | Bne | JzA | JnzA | Loop |
+                BeqF | BneF | BgeF | BltF | BgeUF | BltUF => {
                     let tgt = ft.wrapping_add(d.imm as u32);
                     edges.push(Edge { from: pc, to: tgt, kind: EdgeKind::CondBranch });
                     if is_mapped(img, tgt) && !visited.contains(&tgt) { queue.push_back(tgt); }
@@ -57,12 +70,20 @@ pub fn analyze_entries(img: &Image, entries: &[u32], max_instr: usize) -> (HashS
                     if is_mapped(img, ft) { edges.push(Edge { from: pc, to: ft, kind: EdgeKind::Fallthrough }); queue.push_back(ft); }
                     if is_mapped(img, tgt) { queue.push_back(tgt); }
                 }
-                CallI => {
-                    // Unknown target; still add fallthrough
-                    if is_mapped(img, ft) { edges.push(Edge { from: pc, to: ft, kind: EdgeKind::Fallthrough }); queue.push_back(ft); }
+                Ji | Jli | CallI => {
+                    // Targets are only known when the dispatch goes through a recognizable table
+                    if let Some(table) = jump_table_base(img, &widths, pc, d.rs1) {
+                        for tgt in read_jump_table(img, table, max_table) {
+                            edges.push(Edge { from: pc, to: tgt, kind: EdgeKind::Indirect });
+                            if !visited.contains(&tgt) { queue.push_back(tgt); }
+                        }
+                    }
+                    // CALLI and JLI return here; JI does not
+                    if matches!(d.op, CallI | Jli) && is_mapped(img, ft) { edges.push(Edge { from: pc, to: ft, kind: EdgeKind::Fallthrough }); queue.push_back(ft); }
                 }
                 Ret => {
                     rets.insert(pc);
+                    edges.push(Edge { from: pc, to: pc, kind: EdgeKind::Return });
                 }
                 _ => {
                     // Fallthrough by default
@@ -74,14 +95,435 @@ pub fn analyze_entries(img: &Image, entries: &[u32], max_instr: usize) -> (HashS
     (visited, widths, edges, rets)
 }
 
+/// Recover the table base of a `ji`/`calli` through A[`reg`] at `pc` from the
+/// switch idiom on the straight-line path leading to it:
+/// `addsc.a aT, aB, dI, #2; ld.w dX, [aT]off; mov.a aJ, dX; ji aJ`, where aB
+/// is built from constants (movh.a/lea/addih.a/mov.aa).
+fn jump_table_base(img: &Image, widths: &HashMap<u32, u8>, pc: u32, reg: u8) -> Option<u32> {
+    use tricore_rs::decoder::Op::*;
+    const WINDOW: usize = 16;
+    let dec = Tc16Decoder::new();
+    // Walk back over already-decoded fallthrough predecessors
+    let mut path = Vec::new();
+    let mut cur = pc;
+    while path.len() < WINDOW {
+        let Some(prev) = [4u32, 2].into_iter().map(|w| cur.wrapping_sub(w)).find(|p| widths.get(p).is_some_and(|&w| p.wrapping_add(w as u32) == cur)) else { break };
+        let Some(d) = fetch_insn(img, prev).and_then(|raw| dec.decode(raw)) else { break };
+        if d.is_unconditional() { break; }
+        path.push(d);
+        cur = prev;
+    }
+    // Forward over the window: constant A regs, A regs scaled off a known base,
+    // D regs loaded from that base, and A regs holding such a loaded value
+    let mut konst: [Option<u32>; 16] = [None; 16];
+    let mut scaled: [Option<u32>; 16] = [None; 16];
+    let mut loaded: [Option<u32>; 16] = [None; 16];
+    let mut target: [Option<u32>; 16] = [None; 16];
+    for d in path.iter().rev() {
+        let (rd, rs1) = (d.rd as usize, d.rs1 as usize);
+        match d.op {
+            MovHA => { konst[rd] = Some(d.imm); scaled[rd] = None; target[rd] = None; }
+            Lea => {
+                konst[rd] = if d.abs { Some(d.imm) } else { konst[rs1].map(|b| b.wrapping_add(d.imm)) };
+                scaled[rd] = None; target[rd] = None;
+            }
+            MovAA => { konst[rd] = konst[rs1]; scaled[rd] = scaled[rs1]; target[rd] = target[rs1]; }
+            AddscA => {
+                scaled[rd] = if d.imm == 2 { konst[rs1] } else { None };
+                konst[rd] = None; target[rd] = None;
+            }
+            LdW => loaded[rd] = if d.wb || d.abs { None } else { scaled[rs1].map(|b| b.wrapping_add(d.imm)) },
+            MovA => { target[rd] = loaded[rs1]; konst[rd] = None; scaled[rd] = None; }
+            AddA | AddAI | SubA => { konst[rd] = None; scaled[rd] = None; target[rd] = None; }
+            // Anything else may clobber a data register
+            _ => loaded[rd] = None,
+        }
+    }
+    target[reg as usize]
+}
+
+/// Read up to `max` consecutive table words that point at code.
+fn read_jump_table(img: &Image, base: u32, max: usize) -> Vec<u32> {
+    (0..max as u32)
+        .map_while(|i| read_u32(img, base.wrapping_add(i * 4)))
+        .take_while(|&t| is_code(img, t))
+        .collect()
+}
+
+/// An even address inside an executable segment.
+fn is_code(img: &Image, addr: u32) -> bool {
+    addr & 1 == 0 && img.segments.iter().any(|s| {
+        s.perms.contains('x') && addr >= s.base && ((addr - s.base) as usize) < s.bytes.len()
+    })
+}
+
+/// Boot Mode Header ID in the upper halfword of a BMHD's first word.
+const BMHD_ID: u16 = 0xB359;
+
+/// Vector table slots scanned by `find_seeds` when a table base is given.
+pub const DEFAULT_VECTOR_SLOTS: usize = 32;
+
+/// Default analysis seeds: the image entry, the start address of any Boot
+/// Mode Header at a segment base, falling back to the first executable segment.
+pub fn find_seeds(img: &Image) -> Vec<u32> {
+    find_seeds_with(img, None, DEFAULT_VECTOR_SLOTS)
+}
+
+/// `find_seeds` plus `slots` code pointers read from a vector table at
+/// `vector_base`. Only targets inside an executable segment count.
+pub fn find_seeds_with(img: &Image, vector_base: Option<u32>, slots: usize) -> Vec<u32> {
+    let is_code = |addr: u32| is_code(img, addr);
+    let mut seeds: Vec<u32> = Vec::new();
+    seeds.extend(img.entry.filter(|&e| is_code(e)));
+    for s in &img.segments {
+        if read_u16(img, s.base.wrapping_add(2)) == Some(BMHD_ID) {
+            seeds.extend(read_u32(img, s.base.wrapping_add(4)).filter(|&a| is_code(a)));
+        }
+    }
+    if let Some(vb) = vector_base {
+        for i in 0..slots as u32 {
+            let Some(p) = read_u32(img, vb.wrapping_add(i * 4)) else { break };
+            if is_code(p) { seeds.push(p); }
+        }
+    }
+    if seeds.is_empty() {
+        let first = img.segments.iter().find(|s| s.perms.contains('x')).or(img.segments.first());
+        seeds.extend(first.map(|s| s.base));
+    }
+    seeds.sort_unstable();
+    seeds.dedup();
+    seeds
+}
+
+/// Linear sweep over [start, end): each instruction address with its
+/// decoding, advancing by the decoded width, or by 2 past an undecodable
+/// half-word. Words come from `fetch_insn`, so a 16-bit instruction in a
+/// segment's last two bytes decodes; the sweep stops early at the first
+/// address with nothing to read.
+pub fn iter_insns(img: &Image, start: u32, end: u32) -> impl Iterator<Item = (u32, Option<Decoded>)> + '_ {
+    let dec = Tc16Decoder::new();
+    let mut pc = start;
+    std::iter::from_fn(move || {
+        if pc >= end { return None; }
+        let raw = fetch_insn(img, pc)?;
+        let d = dec.decode(raw);
+        let at = pc;
+        pc = pc.saturating_add(d.as_ref().map_or(2, |d| d.width as u32));
+        Some((at, d))
+    })
+}
+
+/// Start of the instruction containing `addr`, found without analysis: try
+/// each half-word aligned start from `window` bytes back up to `addr`,
+/// decode forward, and take the first stream that decodes without a gap up
+/// to an instruction covering `addr`. Starting as far back as possible lets
+/// misaligned streams resynchronize before they reach `addr`.
+pub fn find_instruction_start(img: &Image, addr: u32, window: u32) -> Option<u32> {
+    let dec = Tc16Decoder::new();
+    let mut cand = addr.saturating_sub(window) & !1;
+    while cand <= addr {
+        let mut pc = cand;
+        while let Some(d) = fetch_insn(img, pc).and_then(|w| dec.decode(w)) {
+            let next = pc.saturating_add(d.width as u32);
+            if addr < next { return Some(pc); }
+            pc = next;
+        }
+        cand += 2;
+    }
+    None
+}
+
+/// Options for `disassemble_range`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisasmOptions<'a> {
+    /// Fill `DisasmLine::bytes` with the instruction bytes
+    pub show_bytes: bool,
+    /// Names for instruction addresses and branch/call targets
+    pub labels: Option<&'a HashMap<u32, String>>,
+    /// Print unlabeled targets as `segment+offset` instead of absolute
+    pub seg_relative: bool,
+    /// Instruction syntax
+    pub style: MnemonicStyle,
+}
+
+/// One line of `disassemble_range`: a decoded instruction or a `.2byte`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DisasmLine {
+    pub addr: u32,
+    /// Bytes the line covers (2 for an unknown half-word)
+    pub len: u8,
+    /// Those bytes in memory order, with `show_bytes` only
+    pub bytes: Vec<u8>,
+    /// Instruction text with absolute (or labelled) targets
+    pub text: String,
+    /// Label of `addr` from `DisasmOptions::labels`
+    pub label: Option<String>,
+}
+
+/// Disassemble [start, end) through `iter_insns` into structured lines,
+/// the one-call equivalent of the CLI `range` listing. Stops early where the
+/// sweep does (unmapped bytes); the last line's `addr + len` tells where.
+///
+/// ```
+/// use tricore_disasm::{disassemble_range, DisasmOptions, Image};
+/// use tricore_disasm::model::Segment;
+///
+/// // mov d0,#1; an unknown half-word; ret
+/// let bytes = vec![0x82, 0x10, 0x0A, 0x00, 0x0D, 0x00, 0x90, 0x00];
+/// let img = Image::new(vec![Segment { name: "flash".into(), base: 0x8000_0000, bytes, perms: "r-x", kind: "raw" }], None).unwrap();
+/// let labels = [(0x8000_0004, "done".to_string())].into_iter().collect();
+/// let opts = DisasmOptions { show_bytes: true, labels: Some(&labels), ..Default::default() };
+/// let lines = disassemble_range(&img, 0x8000_0000, 0x8000_0008, opts);
+/// let text: Vec<String> = lines.iter().map(|l| format!("{:#010x}: {}", l.addr, l.text)).collect();
+/// assert_eq!(text, ["0x80000000: mov d0, #0x1", "0x80000002: .2byte 0x000a", "0x80000004: ret"]);
+/// assert_eq!(lines[2].bytes, [0x0D, 0x00, 0x90, 0x00]);
+/// assert_eq!(lines[2].label.as_deref(), Some("done"));
+/// ```
+pub fn disassemble_range(img: &Image, start: u32, end: u32, opts: DisasmOptions) -> Vec<DisasmLine> {
+    let name = |a: u32| {
+        opts.labels.and_then(|l| l.get(&a).cloned()).or_else(|| opts.seg_relative.then(|| img.seg_relative(a)).flatten())
+    };
+    iter_insns(img, start, end).map(|(pc, d)| {
+        let (len, text) = match d {
+            Some(d) => (d.width, fmt_decoded_with_style(&d, pc, opts.style, name)),
+            None => (2, format!(".2byte {:#06x}", read_u16(img, pc).unwrap_or(0))),
+        };
+        let bytes = if opts.show_bytes { (0..len as u32).filter_map(|i| read_u8(img, pc + i)).collect() } else { Vec::new() };
+        DisasmLine { addr: pc, len, bytes, text, label: opts.labels.and_then(|l| l.get(&pc).cloned()) }
+    }).collect()
+}
+
+/// Decoded vs undecoded byte tally for a code range, from `coverage`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Bytes covered by a decoded instruction
+    pub decoded: u32,
+    /// Bytes the sweep stepped over as `.2byte` unknowns
+    pub unknown: u32,
+    /// Bytes in the range the sweep never reached (unmapped)
+    pub unreached: u32,
+    /// Unknown half-words seen per `op1` (the low byte)
+    pub unknown_op1: BTreeMap<u8, usize>,
+    /// Unknown half-words per `Decoder::decode_bytes` failure reason
+    pub unknown_reasons: BTreeMap<DecodeReason, usize>,
+}
+
+impl Coverage {
+    /// Unknown `op1` bytes, most frequent first (ties by ascending `op1`).
+    pub fn top_unknown_op1(&self, n: usize) -> Vec<(u8, usize)> {
+        let mut v: Vec<(u8, usize)> = self.unknown_op1.iter().map(|(&op1, &c)| (op1, c)).collect();
+        v.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        v.truncate(n);
+        v
+    }
+}
+
+/// Tally how much of [start, end) a linear sweep like `iter_insns` decodes.
+/// An instruction that straddles `end` counts only its bytes inside the
+/// range; a 32-bit encoding cut off by the image end is an unknown
+/// (`Truncated`) rather than where the sweep stops.
+pub fn coverage(img: &Image, start: u32, end: u32) -> Coverage {
+    let dec = Tc16Decoder::new();
+    let mut cov = Coverage::default();
+    let mut pc = start;
+    while pc < end {
+        // Whatever bytes remain, so a cut-off 32-bit encoding reads as truncated
+        let bytes: Vec<u8> = (0..4).map_while(|i| read_u8(img, pc.wrapping_add(i))).collect();
+        if bytes.is_empty() { break; }
+        let width = match dec.decode_bytes(&bytes) {
+            Ok(d) => {
+                let width = (d.width as u32).min(end - pc);
+                cov.decoded += width;
+                width
+            }
+            Err(e) => {
+                let width = (bytes.len() as u32).min(2).min(end - pc);
+                cov.unknown += width;
+                *cov.unknown_op1.entry(bytes[0]).or_default() += 1;
+                *cov.unknown_reasons.entry(e.reason).or_default() += 1;
+                width
+            }
+        };
+        pc += width;
+    }
+    cov.unreached = end.saturating_sub(pc);
+    cov
+}
+
+/// One valid decoding found by `overlapping_decodes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlapInsn {
+    pub addr: u32,
+    pub width: u8,
+    /// On the stream decoded from the range start
+    pub primary: bool,
+    /// How many distinct decoding streams reach this address
+    pub streams: usize,
+}
+
+/// Decode [start, end) from every byte offset, not just the primary stream.
+/// Each offset not already covered starts a linear sweep that stops at the
+/// first undecodable word or the range end; every valid decoding is reported
+/// once, ascending, with the number of sweeps that pass through it. Addresses
+/// with `streams > 1` are where differently aligned streams re-synchronize.
+pub fn overlapping_decodes(img: &Image, start: u32, end: u32) -> Vec<OverlapInsn> {
+    let dec = Tc16Decoder::new();
+    let mut width_at: HashMap<u32, u8> = HashMap::new();
+    for addr in start..end {
+        if let Some(d) = fetch_insn(img, addr).and_then(|raw| dec.decode(raw)) { width_at.insert(addr, d.width); }
+    }
+    let sweep = |from: u32| {
+        let mut addrs = Vec::new();
+        let mut pc = from;
+        while pc < end {
+            let Some(&w) = width_at.get(&pc) else { break };
+            addrs.push(pc);
+            pc += w as u32;
+        }
+        addrs
+    };
+    let primary: HashSet<u32> = sweep(start).into_iter().collect();
+    // A stream starts at each decodable offset no earlier stream passed through
+    let mut streams: HashMap<u32, usize> = HashMap::new();
+    for from in start..end {
+        if streams.contains_key(&from) { continue; }
+        for addr in sweep(from) { *streams.entry(addr).or_default() += 1; }
+    }
+    let mut out: Vec<OverlapInsn> = width_at
+        .iter()
+        .map(|(&addr, &width)| OverlapInsn { addr, width, primary: primary.contains(&addr), streams: streams[&addr] })
+        .collect();
+    out.sort_unstable_by_key(|i| i.addr);
+    out
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Block { pub start: u32, pub end: u32 }
 
+/// An edge target that lands inside another decoded instruction: data
+/// decoded as code, or a decoder width bug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Overlap {
+    /// The misaligned edge target
+    pub addr: u32,
+    /// Start of the instruction it falls inside
+    pub insn: u32,
+    /// Block holding that instruction (`insn` itself when no block covers it)
+    pub inside_block: u32,
+}
+
+/// Flag edge targets that fall strictly inside another decoded instruction.
+/// `widths` is the decoded-instruction map from `analyze_entries`; `blocks`
+/// may be empty when only the instruction-level result is wanted.
+pub fn find_overlaps(edges: &[Edge], widths: &HashMap<u32, u8>, blocks: &[Block]) -> Vec<Overlap> {
+    let mut out: Vec<Overlap> = Vec::new();
+    for e in edges.iter().filter(|e| e.kind != EdgeKind::Return) {
+        let covering = (1..4u32).filter_map(|back| e.to.checked_sub(back)).find(|&p| widths.get(&p).is_some_and(|&w| e.to < p + w as u32));
+        if let Some(insn) = covering {
+            let inside_block = blocks.iter().find(|b| insn >= b.start && insn < b.end).map_or(insn, |b| b.start);
+            out.push(Overlap { addr: e.to, insn, inside_block });
+        }
+    }
+    out.sort_unstable_by_key(|o| (o.addr, o.insn));
+    out.dedup();
+    out
+}
+
+/// A visited address the walk could not decode, reported instead of dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub addr: u32,
+    pub message: String,
+}
+
+/// Visited addresses left undecoded because the instruction runs past the
+/// image end: a 32-bit encoding in a segment's last 2-3 bytes, or any seed in
+/// its last byte. A 16-bit instruction in the last two bytes decodes fine.
+pub fn boundary_diagnostics(img: &Image, visited: &HashSet<u32>, widths: &HashMap<u32, u8>) -> Vec<Diagnostic> {
+    let mut out: Vec<Diagnostic> = visited
+        .iter()
+        .filter(|pc| !widths.contains_key(pc) && fetch_insn(img, **pc).is_none())
+        .filter_map(|&pc| {
+            let s = img.segment_containing(pc)?;
+            let left = s.base as u64 + s.bytes.len() as u64 - pc as u64;
+            Some(Diagnostic { addr: pc, message: format!("undecodable at boundary: {left} byte(s) left in {}", s.name) })
+        })
+        .collect();
+    out.sort_unstable_by_key(|d| d.addr);
+    out
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct EdgeOut { pub from: u32, pub to: u32, pub kind: String }
 
 #[derive(Debug, Clone, Serialize)]
-pub struct FunctionOut { pub entry: u32, pub blocks: Vec<u32> }
+pub struct FunctionOut {
+    pub entry: u32,
+    pub blocks: Vec<u32>,
+    /// Blocks of this function that end in RET
+    pub exits: Vec<u32>,
+    /// Lowest block start
+    pub start: u32,
+    /// Highest block end (exclusive)
+    pub end: u32,
+    /// Bytes covered by the function's blocks (gaps between blocks excluded)
+    pub size: u32,
+    pub num_blocks: usize,
+    /// Entries of functions with a call edge into this one
+    pub callers: Vec<u32>,
+    /// Entries this function calls directly
+    pub callees: Vec<u32>,
+}
+
+/// Instruction counts over the analyzed PCs, from `insn_mix`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InsnMix {
+    /// Count per `Op` name (`"Add"`, `"LdW"`, ...)
+    pub by_op: BTreeMap<String, usize>,
+    /// Count per coarse category: `arith`, `mem` or `branch`
+    pub by_category: BTreeMap<&'static str, usize>,
+}
+
+impl InsnMix {
+    /// `by_op` most frequent first (ties by name).
+    pub fn ranked(&self) -> Vec<(&str, usize)> {
+        let mut v: Vec<(&str, usize)> = self.by_op.iter().map(|(k, &n)| (k.as_str(), n)).collect();
+        v.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        v
+    }
+}
+
+/// Coarse category for `InsnMix`: transfers (branches, calls, `ret`) are
+/// `branch`, loads/stores `mem`, everything else `arith`.
+fn insn_category(d: &Decoded) -> &'static str {
+    use tricore_rs::decoder::Op::*;
+    if d.is_branch() || d.is_call() || d.is_return() { return "branch"; }
+    match d.op {
+        LdW | StW | LdD | StD | LdB | LdBu | LdH | LdHu | StB | StH |
+        LdWPbr | LdWPcir | StWPbr | StWPcir | LdBPbr | LdBUPbr | LdHPbr | LdHUPbr |
+        LdBPcir | LdBUPcir | LdHPcir | LdHUPcir | StBPbr | StBPcir | StHPbr | StHPcir => "mem",
+        _ => "arith",
+    }
+}
+
+/// Tally the instruction at every PC in `widths` (the decoded map from
+/// `analyze_entries`) by `Op` and by category.
+pub fn insn_mix(img: &Image, widths: &HashMap<u32, u8>) -> InsnMix {
+    let dec = Tc16Decoder::new();
+    let mut mix = InsnMix::default();
+    for &pc in widths.keys() {
+        let Some(d) = fetch_insn(img, pc).and_then(|w| dec.decode(w)) else { continue };
+        *mix.by_op.entry(format!("{:?}", d.op)).or_default() += 1;
+        *mix.by_category.entry(insn_category(&d)).or_default() += 1;
+    }
+    mix
+}
+
+/// Split `functions` into those with at least `min_blocks` blocks and the
+/// smaller stubs, typically data decoded as code from a spurious seed.
+pub fn split_stubs(functions: Vec<FunctionOut>, min_blocks: usize) -> (Vec<FunctionOut>, Vec<FunctionOut>) {
+    functions.into_iter().partition(|f| f.num_blocks >= min_blocks)
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Report<Blk=Block> {
@@ -89,6 +531,284 @@ pub struct Report<Blk=Block> {
     pub blocks: Vec<Blk>,
     pub edges: Vec<EdgeOut>,
     pub functions: Vec<FunctionOut>,
+    /// Edge targets landing inside other instructions
+    pub overlaps: Vec<Overlap>,
+    /// Addresses the walk reached but could not decode
+    pub diagnostics: Vec<Diagnostic>,
+    /// `sub_`/`loc_` names for function entries and other block starts
+    pub labels: BTreeMap<u32, String>,
+    /// PCs the walk reached, decoded or not, for renderers that list single instructions
+    #[serde(skip)]
+    pub visited: HashSet<u32>,
+    /// Width of each decoded instruction
+    #[serde(skip)]
+    pub widths: HashMap<u32, u8>,
+    /// Instruction-level edges the block edges were folded from
+    #[serde(skip)]
+    pub insn_edges: Vec<Edge>,
+    /// Fetch word each instruction was decoded from, to tell what an edit replaced
+    #[serde(skip)]
+    pub words: HashMap<u32, u32>,
+    /// Edge indices by source and target block, built on first query
+    #[serde(skip)]
+    adjacency: std::sync::OnceLock<Adjacency>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Adjacency {
+    succ: HashMap<u32, Vec<usize>>,
+    pred: HashMap<u32, Vec<usize>>,
+}
+
+impl Report<Block> {
+    /// The block containing `addr`.
+    pub fn block_at(&self, addr: u32) -> Option<&Block> {
+        self.blocks.iter().find(|b| addr >= b.start && addr < b.end)
+    }
+}
+
+impl<Blk> Report<Blk> {
+    pub fn new(entries: Vec<u32>, blocks: Vec<Blk>, edges: Vec<EdgeOut>, functions: Vec<FunctionOut>, overlaps: Vec<Overlap>, diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            entries, blocks, edges, functions, overlaps, diagnostics,
+            labels: BTreeMap::new(), visited: HashSet::new(), widths: HashMap::new(), insn_edges: Vec::new(), words: HashMap::new(),
+            adjacency: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Edges leaving `block`, in `edges` order. The synthetic "ret" marker is
+    /// not a successor, so a returning block is a sink. The index is built
+    /// once, so mutate `edges` only before the first query.
+    pub fn successors(&self, block: u32) -> Vec<&EdgeOut> {
+        self.edges_at(&self.adjacency().succ, block)
+    }
+
+    /// Edges entering `block`, in `edges` order.
+    pub fn predecessors(&self, block: u32) -> Vec<&EdgeOut> {
+        self.edges_at(&self.adjacency().pred, block)
+    }
+
+    fn adjacency(&self) -> &Adjacency {
+        self.adjacency.get_or_init(|| {
+            let mut adj = Adjacency::default();
+            for (i, e) in self.edges.iter().enumerate().filter(|(_, e)| e.kind != "ret") {
+                adj.succ.entry(e.from).or_default().push(i);
+                adj.pred.entry(e.to).or_default().push(i);
+            }
+            adj
+        })
+    }
+
+    fn edges_at<'a>(&'a self, index: &HashMap<u32, Vec<usize>>, block: u32) -> Vec<&'a EdgeOut> {
+        index.get(&block).map(|ix| ix.iter().map(|&i| &self.edges[i]).collect()).unwrap_or_default()
+    }
+}
+
+/// Instructions that branch to, call or dispatch into `addr`, with the edge
+/// kind, ordered by source. Fallthroughs and returns are not references.
+pub fn xrefs_to(report: &Report, addr: u32) -> Vec<(u32, EdgeKind)> {
+    let mut refs: Vec<(u32, EdgeKind)> = report.insn_edges.iter()
+        .filter(|e| e.to == addr && !matches!(e.kind, EdgeKind::Fallthrough | EdgeKind::Return))
+        .map(|e| (e.from, e.kind))
+        .collect();
+    refs.sort_by_key(|&(from, _)| from);
+    refs.dedup_by_key(|&mut (from, _)| from);
+    refs
+}
+
+/// Run the worklist decoder from `seeds` and fold the result into blocks,
+/// block-level edges, functions (each seed and call target) and their
+/// `sub_`/`loc_` labels. `analyze_entries` is the instruction-level primitive
+/// underneath; the CLI and GUI both go through this.
+pub fn build_report(img: &Image, seeds: &[u32], max_instr: usize) -> Report {
+    let (visited, widths, edges, rets) = analyze_entries(img, seeds, max_instr);
+
+    // Compute block starts: entries + all edge destinations
+    let mut block_starts: HashSet<u32> = seeds.iter().copied().collect();
+    for e in edges.iter().filter(|e| !matches!(e.kind, EdgeKind::Return)) { block_starts.insert(e.to); }
+
+    // Build blocks by linear sweep from each start until next start/unknown
+    let mut starts: Vec<u32> = block_starts.into_iter().collect();
+    starts.sort_unstable();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut addr_to_block: HashMap<u32, u32> = HashMap::new(); // pc -> block start
+    for &start in &starts {
+        if !visited.contains(&start) { continue; }
+        // Avoid duplicating blocks if we've already assigned this start
+        if addr_to_block.contains_key(&start) { continue; }
+        let mut cur = start;
+        loop {
+            let Some(&w) = widths.get(&cur) else { break };
+            let next = cur.wrapping_add(w as u32);
+            // Is current instruction an unconditional branch? If so, close after it.
+            let is_uncond = edges.iter().any(|e| e.from == cur && matches!(e.kind, EdgeKind::Branch));
+            let is_ret = rets.contains(&cur);
+            // If next is a new block start or we hit an uncond branch or unknown/visited gap, end block at next
+            let should_end = is_uncond || is_ret
+                || !visited.contains(&next)
+                || starts.binary_search(&next).is_ok();
+            if should_end {
+                let end = next;
+                blocks.push(Block { start, end });
+                // Map all PCs from start to end into this block
+                let mut pc = start;
+                while pc < end {
+                    addr_to_block.insert(pc, start);
+                    if let Some(&ww) = widths.get(&pc) { pc = pc.wrapping_add(ww as u32); } else { break; }
+                }
+                break;
+            } else {
+                cur = next;
+            }
+        }
+    }
+
+    // Normalize edges to block-level
+    let mut edges_out: Vec<EdgeOut> = Vec::new();
+    for e in &edges {
+        let from_block = *addr_to_block.get(&e.from).unwrap_or(&e.from);
+        // A return marker stays on its own block
+        let to_block = if matches!(e.kind, EdgeKind::Return) { from_block } else { starts.iter().copied().find(|&s| s == e.to).unwrap_or(e.to) };
+        let kind = match e.kind { EdgeKind::Fallthrough => "ft", EdgeKind::Branch => "br", EdgeKind::CondBranch => "cbr", EdgeKind::Call => "call", EdgeKind::Indirect => "ind", EdgeKind::Return => "ret" }.to_string();
+        edges_out.push(EdgeOut { from: from_block, to: to_block, kind });
+    }
+
+    // Functions: each seed and each call target is a root; collect block starts
+    // reachable without following calls. Shared blocks count in every function.
+    let mut functions: Vec<FunctionOut> = Vec::new();
+    // Build adjacency from block-level edges
+    let mut adj: HashMap<u32, Vec<u32>> = HashMap::new();
+    for e in edges_out.iter().filter(|e| e.kind != "call") { adj.entry(e.from).or_default().push(e.to); }
+    let returning: HashSet<u32> = edges_out.iter().filter(|e| e.kind == "ret").map(|e| e.from).collect();
+    let block_end: HashMap<u32, u32> = blocks.iter().map(|b| (b.start, b.end)).collect();
+    let mut roots: Vec<u32> = seeds.to_vec();
+    let mut call_targets: Vec<u32> = edges_out.iter().filter(|e| e.kind == "call" && visited.contains(&e.to)).map(|e| e.to).collect();
+    call_targets.sort_unstable();
+    for t in call_targets { if !roots.contains(&t) { roots.push(t); } }
+    for &entry in &roots {
+        // Map entry to block start
+        let entry_block = starts.iter().copied().find(|&s| s == entry).unwrap_or(entry);
+        let mut seen: HashSet<u32> = HashSet::new();
+        let mut q = VecDeque::new();
+        q.push_back(entry_block);
+        while let Some(b) = q.pop_front() {
+            if !seen.insert(b) { continue; }
+            if let Some(nexts) = adj.get(&b) {
+                for &n in nexts { q.push_back(n); }
+            }
+        }
+        let mut blks: Vec<u32> = seen.into_iter().collect();
+        blks.sort_unstable();
+        let exits = blks.iter().copied().filter(|b| returning.contains(b)).collect();
+        let start = blks.first().copied().unwrap_or(entry_block);
+        let end = blks.iter().filter_map(|b| block_end.get(b)).copied().max().unwrap_or(start);
+        let size = blks.iter().filter_map(|b| block_end.get(b).map(|e| e - b)).sum();
+        let mut callees: Vec<u32> = edges_out.iter().filter(|e| e.kind == "call" && blks.binary_search(&e.from).is_ok()).map(|e| e.to).collect();
+        callees.sort_unstable();
+        callees.dedup();
+        let num_blocks = blks.len();
+        functions.push(FunctionOut { entry: entry_block, blocks: blks, exits, start, end, size, num_blocks, callers: Vec::new(), callees });
+    }
+    let calls: Vec<(u32, u32)> = functions.iter().flat_map(|f| f.callees.iter().map(move |&c| (f.entry, c))).collect();
+    for f in &mut functions {
+        f.callers = calls.iter().filter(|(_, callee)| *callee == f.entry).map(|(caller, _)| *caller).collect();
+        f.callers.sort_unstable();
+        f.callers.dedup();
+    }
+
+    let overlaps = find_overlaps(&edges, &widths, &blocks);
+    let diagnostics = boundary_diagnostics(img, &visited, &widths);
+    // Function entries are sub_, every other block start loc_
+    let mut labels: BTreeMap<u32, String> = functions.iter().map(|f| (f.entry, format!("sub_{:08x}", f.entry))).collect();
+    for b in &blocks { labels.entry(b.start).or_insert_with(|| format!("loc_{:08x}", b.start)); }
+    let mut report = Report::new(seeds.to_vec(), blocks, edges_out, functions, overlaps, diagnostics);
+    report.labels = labels;
+    report.visited = visited;
+    report.widths = widths;
+    report.words = report.widths.keys().filter_map(|&pc| Some((pc, fetch_insn(img, pc)?))).collect();
+    report.insn_edges = edges;
+    report
+}
+
+/// Re-analysis after a one-byte edit at `changed`, reusing `prev` when the
+/// edit cannot have changed the walk: it lies outside every decoded
+/// instruction, or each instruction it touches still decodes to the same
+/// width and neither old nor new form branches, calls or returns. Anything
+/// else (a width change shifts every decode after it, a new branch target)
+/// falls back to `build_report` from `prev.entries`, as does any image with a
+/// `ji`/`jli`/`calli`, whose table recognition reads both data words and the
+/// instructions before the dispatch.
+pub fn reanalyze_around(img: &Image, prev: &Report, changed: u32, max_instr: usize) -> Report {
+    use tricore_rs::decoder::Op::*;
+    let dec = Tc16Decoder::new();
+    let decode = |raw: u32| dec.decode(raw);
+    let is_dispatch = |d: &Decoded| matches!(d.op, Ji | Jli | CallI);
+    // Ops the walk treats as anything other than a plain fallthrough
+    let is_plain = |d: &Decoded| !(d.is_branch() || d.is_call() || d.is_return());
+    let full = || build_report(img, &prev.entries, max_instr);
+    if prev.words.values().filter_map(|&w| decode(w)).any(|d| is_dispatch(&d)) { return full(); }
+    for &pc in &prev.visited {
+        // Undecoded PCs were limited by their 4-byte fetch window
+        let len = prev.widths.get(&pc).map_or(4, |&w| w as u32);
+        if changed.wrapping_sub(pc) >= len { continue; }
+        let old = prev.words.get(&pc).and_then(|&w| decode(w));
+        let new = fetch_insn(img, pc).and_then(decode);
+        match (old, new) {
+            (Some(o), Some(n)) if o.width == n.width && is_plain(&o) && is_plain(&n) => {}
+            _ => return full(),
+        }
+    }
+    let mut report = prev.clone();
+    for (&pc, w) in report.words.iter_mut() {
+        if changed.wrapping_sub(pc) < 4 { if let Some(raw) = fetch_insn(img, pc) { *w = raw; } }
+    }
+    report
+}
+
+/// One instruction of a JSON Lines disassembly export. Addresses are hex
+/// strings so consumers never see them as (possibly lossy) JSON numbers.
+#[derive(Debug, Clone, Serialize)]
+pub struct InsnLine {
+    pub addr: String,
+    /// Instruction bytes in memory order, e.g. `"8210"`
+    pub bytes: String,
+    pub mnemonic: String,
+    /// Operands as printed, with branch and call targets made absolute
+    pub operands: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Start of the containing block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<String>,
+}
+
+/// Every decoded instruction of `report`, in address order, as one JSON
+/// object per line. `label(addr)` names an instruction, falling back to the
+/// report's `sub_`/`loc_` labels.
+pub fn disasm_jsonl(img: &Image, report: &Report, label: impl Fn(u32) -> Option<String>) -> String {
+    let dec = Tc16Decoder::new();
+    let mut pcs: Vec<u32> = report.visited.iter().copied().collect();
+    pcs.sort_unstable();
+    let mut out = String::new();
+    for pc in pcs {
+        let Some(d) = fetch_insn(img, pc).and_then(|raw| dec.decode(raw)) else { continue };
+        let parts = fmt_decoded_parts(&d);
+        let target = abs_target(&d, pc);
+        let line = InsnLine {
+            addr: format!("{pc:#010x}"),
+            bytes: (0..d.width as u32).filter_map(|i| read_u8(img, pc + i)).map(|b| format!("{b:02x}")).collect(),
+            mnemonic: parts.mnemonic,
+            operands: parts.operands.iter().map(|o| match (o, target) {
+                (Operand::Target(_) | Operand::AbsTarget(_), Some(t)) => format!("{t:#010x}"),
+                _ => o.to_string(),
+            }).collect(),
+            label: label(pc).or_else(|| report.labels.get(&pc).cloned()),
+            block: report.block_at(pc).map(|b| format!("{:#010x}", b.start)),
+        };
+        out.push_str(&serde_json::to_string(&line).expect("InsnLine serializes"));
+        out.push('\n');
+    }
+    out
 }
 
 #[cfg(test)]
@@ -101,9 +821,9 @@ mod tests {
         // Build a tiny image: J +2 (16-bit), then two 16-bit NOP-like (use MOV D0,#0 and MOV D0,#1)
         // Encode J disp8=1: low byte 0x3C, high byte 0x01 (little-endian)
         let mut bytes = vec![0x3C, 0x01, 0x82, 0x00, 0x82, 0x10];
-        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }] };
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
         let seeds = [0u32];
-        let (visited, widths, edges) = analyze_entries(&img, &seeds, 100);
+        let (visited, widths, edges, _rets) = analyze_entries(&img, &seeds, 100);
         assert!(visited.contains(&0));
         // target should be ft(0)+2 => 0x0004
         let ft = 0u32 + 2;
@@ -111,4 +831,322 @@ mod tests {
         assert!(edges.iter().any(|e| matches!(e.kind, EdgeKind::Branch) && e.from == 0 && e.to == tgt));
         assert!(widths.get(&0).is_some());
     }
+
+    #[test]
+    fn iter_insns_walks_widths_and_steps_over_unknown_half_words() {
+        // 0x0: mov d0,#1; 0x2: add d2,d0,d1; 0x6: unknown; 0x8: ret; range ends mid-ret
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&((2u32 << 28) | (1 << 16) | 0x0B).to_le_bytes());
+        bytes.extend_from_slice(&[0x0A, 0x00]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let seen: Vec<(u32, Option<u8>)> = iter_insns(&img, 0, 0xA).map(|(pc, d)| (pc, d.map(|d| d.width))).collect();
+        assert_eq!(seen, [(0, Some(2)), (2, Some(4)), (6, None), (8, Some(4))]);
+        // Past the mapped bytes there is nothing to yield
+        assert_eq!(iter_insns(&img, 0xC, 0x20).count(), 0);
+    }
+
+    #[test]
+    fn insn_mix_counts_ops_and_categories_over_visited_pcs() {
+        // 0x0: add d2,d0,d1; 0x4: add d3,d2,d2; 0x8: j +0 (to 0xC); 0xC: ret; 0x10: unvisited add
+        let add = |c: u32, a: u32, b: u32| ((c << 28) | (b << 12) | (a << 8) | 0x0B).to_le_bytes();
+        let mut bytes = add(2, 0, 1).to_vec();
+        bytes.extend_from_slice(&add(3, 2, 2));
+        bytes.extend_from_slice(&0x0000_001Du32.to_le_bytes());
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&add(4, 0, 0));
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let mix = insn_mix(&img, &report.widths);
+        assert_eq!(mix.ranked(), [("Add", 2), ("J", 1), ("Ret", 1)]);
+        assert_eq!(mix.by_category.into_iter().collect::<Vec<_>>(), [("arith", 2), ("branch", 2)]);
+    }
+
+    #[test]
+    fn find_instruction_start_walks_back_to_the_containing_instruction() {
+        // 0x0: mov d0,#1; 0x2: add d2,d0,d1; 0x6: mov d1,#2; 0x8: add d3,d1,d2; 0xC: ret
+        let add = |c: u32, a: u32, b: u32| ((c << 28) | (b << 16) | (a << 8) | 0x0B).to_le_bytes();
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&add(2, 0, 1));
+        bytes.extend_from_slice(&[0x82, 0x21]);
+        bytes.extend_from_slice(&add(3, 1, 2));
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let starts: Vec<Option<u32>> = (0..0x10).map(|a| find_instruction_start(&img, a, 8)).collect();
+        let want = [0, 0, 2, 2, 2, 2, 6, 6, 8, 8, 8, 8, 0xC, 0xC, 0xC, 0xC].map(Some);
+        assert_eq!(starts, want);
+        // A window reaching back only to the preceding mov still lands on the add
+        assert_eq!(find_instruction_start(&img, 0xB, 4), Some(8));
+        assert_eq!(find_instruction_start(&img, 0x20, 8), None, "unmapped");
+    }
+
+    #[test]
+    fn coverage_tallies_decoded_and_unknown_bytes_by_op1() {
+        // mov d0,#1; add d2,d0,d1; two unknown 0x0A half-words; unknown 0x4A; ret; 2 bytes of padding
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&((2u32 << 28) | (1 << 16) | 0x0B).to_le_bytes());
+        bytes.extend_from_slice(&[0x0A, 0x00, 0x0A, 0x11, 0x4A, 0x00]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let cov = coverage(&img, 0, 0x14);
+        assert_eq!((cov.decoded, cov.unknown, cov.unreached), (10, 6, 4));
+        assert_eq!(cov.top_unknown_op1(5), [(0x0A, 2), (0x4A, 1)]);
+        assert_eq!(cov.top_unknown_op1(1), [(0x0A, 2)]);
+        assert_eq!(cov.unknown_reasons.into_iter().collect::<Vec<_>>(), [(DecodeReason::UnknownOp1, 3)]);
+        // A range ending inside the ret counts only its first half
+        let cov = coverage(&img, 0, 0xE);
+        assert_eq!((cov.decoded, cov.unknown, cov.unreached), (8, 6, 0));
+
+        // A 32-bit op1 in the segment's last half-word is truncated, not skipped
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes: vec![0x82, 0x10, 0x3B, 0x00], perms: "r-x", kind: "raw" }], entry: None };
+        let cov = coverage(&img, 0, 4);
+        assert_eq!((cov.decoded, cov.unknown), (2, 2));
+        assert_eq!(cov.unknown_reasons.into_iter().collect::<Vec<_>>(), [(DecodeReason::Truncated, 1)]);
+    }
+
+    #[test]
+    fn iter_insns_decodes_a_16bit_instruction_in_the_last_two_bytes() {
+        // 0x0: add d2,d0,d1; 0x4: mov d0,#1 ends the segment
+        let mut bytes = ((2u32 << 28) | (1 << 16) | 0x0B).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0x82, 0x10]);
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let seen: Vec<(u32, String)> = iter_insns(&img, 0, 6).map(|(pc, d)| (pc, d.map(|d| tricore_rs::disasm::fmt_decoded(&d)).unwrap_or_default())).collect();
+        assert_eq!(seen, [(0, "add d2, d0, d1".to_string()), (4, "mov d0, #0x1".to_string())]);
+        // A 32-bit op1 with only two bytes left is not padded into a decoding
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes: vec![0x0B, 0x00], perms: "r-x", kind: "raw" }], entry: None };
+        assert_eq!(fetch_insn(&img, 0), None);
+        assert_eq!(iter_insns(&img, 0, 2).count(), 0);
+    }
+
+    #[test]
+    fn build_report_folds_a_small_image_into_blocks_edges_and_labels() {
+        // 0x0: j -> 0x4 (16-bit); 0x2: mov d0,#0 (never reached); 0x4: ret
+        let mut bytes = vec![0x3C, 0x01, 0x82, 0x00];
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        assert_eq!(report.blocks.iter().map(|b| (b.start, b.end)).collect::<Vec<_>>(), [(0, 2), (4, 8)]);
+        let edges: Vec<(u32, u32, &str)> = report.edges.iter().map(|e| (e.from, e.to, e.kind.as_str())).collect();
+        assert_eq!(edges, [(0, 4, "br"), (4, 4, "ret")]);
+        assert_eq!(report.functions.len(), 1);
+        assert_eq!(report.functions[0].blocks, vec![0, 4]);
+        assert_eq!(report.labels.values().map(String::as_str).collect::<Vec<_>>(), ["sub_00000000", "loc_00000004"]);
+        assert!(!report.visited.contains(&2));
+    }
+
+    #[test]
+    fn disasm_jsonl_emits_one_object_per_instruction_with_hex_addresses() {
+        // 0x0: mov d0,#1; 0x2: ret
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let out = disasm_jsonl(&img, &report, |a| (a == 0).then(|| "start".to_string()));
+        let lines: Vec<serde_json::Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], serde_json::json!({ "addr": "0x00000000", "bytes": "8210", "mnemonic": "mov", "operands": ["d0", "#0x1"], "label": "start", "block": "0x00000000" }));
+        assert_eq!(lines[1]["addr"], "0x00000002");
+        assert_eq!(lines[1]["bytes"], "0d009000");
+        assert_eq!(lines[1]["label"], "loc_00000002");
+    }
+
+    #[test]
+    fn xrefs_to_lists_the_call_and_the_jump_into_a_shared_target() {
+        // 0x0: call -> 0x10; 0x4: ret | 0x8: j -> 0x10; 0xA: padding | 0x10: ret
+        let mut bytes = ((6u32 << 16) | 0x6D).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&[0x3C, 0x03, 0x82, 0x00, 0x82, 0x00, 0x82, 0x00]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0, 8], 100);
+        assert_eq!(xrefs_to(&report, 0x10), [(0, EdgeKind::Call), (8, EdgeKind::Branch)]);
+        // The fallthrough into the ret after the call is not a reference
+        assert!(xrefs_to(&report, 4).is_empty());
+    }
+
+    #[test]
+    fn reanalysis_after_a_byte_edit_matches_full_analysis() {
+        // 0x0: mov d0,#1; 0x2: add d2,d0,d1; 0x6: mov d1,#2; 0x8: ret
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&((2u32 << 28) | (1 << 16) | 0x0B).to_le_bytes());
+        bytes.extend_from_slice(&[0x82, 0x21]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let mut img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let prev = build_report(&img, &[0], 100);
+        let same = |a: &Report, b: &Report| {
+            let sorted = |r: &Report| { let mut v: Vec<u32> = r.visited.iter().copied().collect(); v.sort_unstable(); v };
+            assert_eq!(serde_json::to_string(a).unwrap(), serde_json::to_string(b).unwrap());
+            assert_eq!(sorted(a), sorted(b));
+            assert_eq!(a.words, b.words);
+        };
+
+        // Mid-block: retarget the add to d3; same width, still a fallthrough
+        img.segments[0].bytes[5] = 0x30;
+        let inc = reanalyze_around(&img, &prev, 5, 100);
+        same(&inc, &build_report(&img, &[0], 100));
+
+        // mov16 -> 32-bit opcode: the next decode moves, so everything after is redone
+        img.segments[0].bytes[0] = 0x3B;
+        let inc = reanalyze_around(&img, &inc, 0, 100);
+        let full = build_report(&img, &[0], 100);
+        same(&inc, &full);
+        assert_ne!(full.widths.get(&0), prev.widths.get(&0));
+    }
+
+    #[test]
+    fn instruction_straddling_adjacent_segments_decodes() {
+        // 0x0: MOV D0,#0 (16-bit); 0x2: MOV.U D1,#2 (32-bit, bytes 3B 00 02 10... split across segments)
+        let movu = ((1u32 << 28) | (2u32 << 12) | 0xBB).to_le_bytes();
+        let a = Segment { name: ".text".into(), base: 0, bytes: vec![0x82, 0x00, movu[0], movu[1]], perms: "r-x", kind: "elf" };
+        let b = Segment { name: ".text2".into(), base: 4, bytes: vec![movu[2], movu[3], 0x82, 0x10, 0x00, 0x00], perms: "r-x", kind: "elf" };
+        let img = Image { segments: vec![a.clone(), b.clone()], entry: None };
+        let (visited, widths, edges, _rets) = analyze_entries(&img, &[0], 100);
+        assert_eq!(widths.get(&2), Some(&4));
+        assert!(visited.contains(&6));
+        assert!(edges.iter().any(|e| e.from == 2 && e.to == 6));
+
+        // Same bytes with a hole at 4..8: the walk stops at 0x2 without decoding it
+        let gapped = Image { segments: vec![a, Segment { base: 8, ..b }], entry: None };
+        let (visited, widths, edges, _rets) = analyze_entries(&gapped, &[0], 100);
+        assert!(widths.get(&2).is_none());
+        assert!(!visited.contains(&6));
+        assert!(edges.iter().any(|e| e.from == 0 && e.to == 2));
+    }
+
+    #[test]
+    fn vector_slot_and_bmhd_start_become_seeds() {
+        // rodata: BMHD at its base (start -> 0x8000_0100), then a vector table at +8
+        let mut rom = vec![0u8; 0x20];
+        rom[2..4].copy_from_slice(&BMHD_ID.to_le_bytes());
+        rom[4..8].copy_from_slice(&0x8000_0100u32.to_le_bytes());
+        rom[8..12].copy_from_slice(&0x8000_0140u32.to_le_bytes()); // into code
+        rom[12..16].copy_from_slice(&0xD000_0000u32.to_le_bytes()); // data segment: ignored
+        rom[16..20].copy_from_slice(&0x8000_0141u32.to_le_bytes()); // odd: ignored
+        rom[20..24].copy_from_slice(&0x9000_0000u32.to_le_bytes()); // unmapped: ignored
+        let img = Image { segments: vec![
+            Segment { name: "rom".into(), base: 0xA000_0000, bytes: rom, perms: "r--", kind: "raw" },
+            Segment { name: "code".into(), base: 0x8000_0100, bytes: vec![0; 0x80], perms: "r-x", kind: "raw" },
+            Segment { name: "ram".into(), base: 0xD000_0000, bytes: vec![0; 0x10], perms: "rw-", kind: "raw" },
+        ], entry: None };
+        assert_eq!(find_seeds(&img), vec![0x8000_0100]);
+        assert_eq!(find_seeds_with(&img, Some(0xA000_0008), 4), vec![0x8000_0100, 0x8000_0140]);
+
+        // Nothing recognizable: fall back to the first executable segment
+        let plain = Image { segments: vec![Segment { name: "s".into(), base: 0x40, bytes: vec![0; 8], perms: "r-x", kind: "raw" }], entry: None };
+        assert_eq!(find_seeds(&plain), vec![0x40]);
+    }
+
+    #[test]
+    fn d15_high_range_branch_edge_is_32_bytes_further() {
+        // JEQ D15,#0,disp4=1 in low (0x1E) and high (0x9E) forms
+        let edge_to = |op1: u8| {
+            let mut bytes = vec![op1, 0x01];
+            bytes.resize(64, 0);
+            let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+            let (_, _, edges, _) = analyze_entries(&img, &[0], 1);
+            edges.iter().find(|e| matches!(e.kind, EdgeKind::CondBranch)).map(|e| e.to).unwrap()
+        };
+        assert_eq!(edge_to(0x1E), 4);
+        assert_eq!(edge_to(0x9E), 36);
+    }
+
+    #[test]
+    fn odd_offset_decodes_as_an_alternative_stream() {
+        let img = |bytes: Vec<u8>| Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let dec = Tc16Decoder::new();
+        let text = |img: &Image, a: u32| tricore_rs::disasm::fmt_decoded(&dec.decode(fetch_insn(img, a).unwrap()).unwrap());
+
+        // 0x0: 82 82 = mov d2,#-8; from 0x1: 82 10 = mov d0,#1
+        let odd = img(vec![0x82, 0x82, 0x10, 0x00, 0x00, 0x00]);
+        let ins = overlapping_decodes(&odd, 0, 2);
+        assert_eq!(ins.iter().map(|i| (i.addr, i.primary)).collect::<Vec<_>>(), [(0, true), (1, false)]);
+        assert_ne!(text(&odd, 0), text(&odd, 1));
+        assert_eq!(text(&odd, 1), "mov d0, #0x1");
+
+        // 0x0: 32-bit mov d1,#0x820 whose upper half 82 10 is mov d0,#1 from 0x2;
+        // both streams re-synchronize at 0x4
+        let resync = img(vec![0xBB, 0x00, 0x82, 0x10, 0x82, 0x20, 0x00, 0x00, 0x00, 0x00]);
+        let ins = overlapping_decodes(&resync, 0, 6);
+        let at = |a: u32| ins.iter().find(|i| i.addr == a).copied().unwrap();
+        assert_eq!((at(0).width, at(0).primary, at(0).streams), (4, true, 1));
+        assert_eq!((at(2).width, at(2).primary, at(2).streams), (2, false, 1));
+        assert_eq!((at(4).primary, at(4).streams), (true, 2));
+    }
+
+    #[test]
+    fn successors_and_predecessors_follow_block_edges() {
+        // Diamond: 0 -> {4, 8} -> 12
+        let e = |from, to, kind: &str| EdgeOut { from, to, kind: kind.into() };
+        let report: Report = Report::new(vec![0], Vec::new(), vec![
+            e(0, 8, "cbr"), e(0, 4, "ft"), e(4, 12, "br"), e(8, 12, "ft"),
+        ], Vec::new(), Vec::new(), Vec::new());
+        let tos = |v: Vec<&EdgeOut>| v.iter().map(|e| (e.to, e.kind.clone())).collect::<Vec<_>>();
+        let froms = |v: Vec<&EdgeOut>| v.iter().map(|e| e.from).collect::<Vec<_>>();
+        assert_eq!(tos(report.successors(0)), [(8, "cbr".to_string()), (4, "ft".to_string())]);
+        assert_eq!(froms(report.predecessors(12)), [4, 8]);
+        assert!(report.successors(12).is_empty());
+        assert!(report.predecessors(0).is_empty());
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("adjacency").is_none());
+    }
+
+    #[test]
+    fn switch_jump_table_targets_become_blocks() {
+        let words: [(u32, u32); 5] = [
+            (0x00, (2 << 28) | (0x8000 << 12) | 0x91),                           // movh.a a2, #0x8000
+            (0x04, (1 << 28) | (0x28 << 22) | (2 << 12) | (2 << 8) | 0x49),      // lea a2, [a2]0x40
+            (0x08, (3 << 28) | (0x60 << 20) | (2 << 16) | (2 << 12) | (4 << 8) | 0x01), // addsc.a a3, a2, d4, #2
+            (0x0C, (0x24 << 22) | (3 << 12) | (5 << 8) | 0x09),                  // ld.w d5, [a3]0
+            (0x12, (0x03 << 20) | (4 << 8) | 0x2D),                              // ji a4
+        ];
+        let mut bytes = vec![0u8; 0x54];
+        for (off, w) in words { bytes[off as usize..off as usize + 4].copy_from_slice(&w.to_le_bytes()); }
+        bytes[0x10..0x12].copy_from_slice(&[0x60, 0x54]); // mov.a a4, d5
+        for i in 0..4u32 {
+            let tgt = 0x20 + i * 4;
+            bytes[tgt as usize..tgt as usize + 4].copy_from_slice(&0x0090_000Du32.to_le_bytes()); // ret
+            bytes[(0x40 + i * 4) as usize..(0x44 + i * 4) as usize].copy_from_slice(&(0x8000_0000 + tgt).to_le_bytes());
+        }
+        bytes[0x50..0x54].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // not a code pointer: ends the table
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0x8000_0000, bytes, perms: "r-x", kind: "raw" }], entry: None };
+
+        let (visited, _w, edges, rets) = analyze_entries(&img, &[0x8000_0000], 100);
+        let targets: Vec<u32> = (0..4).map(|i| 0x8000_0020 + i * 4).collect();
+        for t in &targets {
+            assert!(visited.contains(t), "{t:#x} not visited");
+            assert!(rets.contains(t));
+            assert!(edges.iter().any(|e| e.from == 0x8000_0012 && e.to == *t && matches!(e.kind, EdgeKind::Indirect)));
+        }
+        assert!(!edges.iter().any(|e| e.from == 0x8000_0012 && matches!(e.kind, EdgeKind::Fallthrough)), "ji does not fall through");
+
+        let (visited, _, _, _) = analyze_entries_with(&img, &[0x8000_0000], 100, 2);
+        assert_eq!(targets.iter().filter(|t| visited.contains(t)).count(), 2);
+    }
+
+    #[test]
+    fn seed_in_the_last_bytes_of_a_segment_is_reported_not_dropped() {
+        // mov d1,#1; j +0 into a 2-byte tail holding the first half of a
+        // 32-bit mov; a second seed on the very last byte
+        let bytes = vec![0x82, 0x11, 0x3C, 0x00, 0x3B, 0x12];
+        let img = Image { segments: vec![Segment { name: "text".into(), base: 0x100, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let (visited, widths, _, _) = analyze_entries(&img, &[0x100, 0x105], 100);
+        assert!(visited.contains(&0x104) && !widths.contains_key(&0x104));
+        let diags = boundary_diagnostics(&img, &visited, &widths);
+        assert_eq!(diags.iter().map(|d| d.addr).collect::<Vec<_>>(), [0x104, 0x105]);
+        assert_eq!(diags[0].message, "undecodable at boundary: 2 byte(s) left in text");
+        assert_eq!(diags[1].message, "undecodable at boundary: 1 byte(s) left in text");
+    }
+
+    #[test]
+    fn sixteen_bit_return_in_the_last_two_bytes_decodes() {
+        // mov d1,#1; ji a11 (16-bit return) as the segment's final half-word
+        let bytes = vec![0x82, 0x11, 0xDC, 0x0B];
+        let img = Image { segments: vec![Segment { name: "text".into(), base: 0x100, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0x100], 100);
+        assert_eq!(report.widths.get(&0x102), Some(&2));
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+        assert_eq!(report.words.get(&0x102), Some(&0x0BDC));
+        assert!(report.insn_edges.iter().all(|e| e.from != 0x102));
+        assert!(overlapping_decodes(&img, 0x100, 0x104).iter().any(|i| i.addr == 0x102 && i.primary));
+    }
 }
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use tricore_rs::decoder::{Decoded, Op};
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Cpu, CpuConfig, LinearMemory, Perms, Trap};
+
+use crate::model::Image;
+
+/// Why an emulation run stopped.
+#[derive(Debug)]
+pub enum RunExit {
+    /// RET executed with an empty call stack (returned from the entry function)
+    Ret { pc: u32 },
+    /// The next fetch PC is a breakpoint; that instruction has not executed
+    Break { pc: u32 },
+    /// Step budget exhausted
+    StepCap,
+    /// PC left every mapped segment
+    Unmapped { pc: u32 },
+    /// PC is mapped but the segment is not executable
+    NoExec { pc: u32, segment: String },
+    /// The core raised a trap
+    Trap(Trap),
+}
+
+/// PCs an `Emulator` stops at before executing.
+pub type Breakpoints = HashSet<u32>;
+
+/// Back all segments with one `LinearMemory` spanning [min base, max end),
+/// enforcing each segment's perms.
+pub fn image_memory(img: &Image) -> Result<LinearMemory> {
+    LinearMemory::with_regions(img.segments.iter().map(|s| (s.base, s.bytes.clone(), Perms::from_rwx(s.perms))).collect())
+        .context("segments too far apart to emulate")
+}
+
+/// Resumable emulation of an image: each `run` continues from where the
+/// previous one stopped, with memory and CPU state kept in between.
+pub struct Emulator<'a> {
+    img: &'a Image,
+    mem: LinearMemory,
+    pub cpu: Cpu,
+    pub breakpoints: Breakpoints,
+    /// Instructions executed over all runs
+    pub steps: usize,
+    /// Breakpoint the last run stopped at, stepped over by the next run
+    stopped_at: Option<u32>,
+}
+
+impl<'a> Emulator<'a> {
+    pub fn new(img: &'a Image, entry: u32) -> Result<Self> {
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(entry);
+        Ok(Self { img, mem: image_memory(img)?, cpu, breakpoints: Breakpoints::new(), steps: 0, stopped_at: None })
+    }
+
+    /// Execute up to `max_steps` instructions, stopping early at a trap, a
+    /// top-level RET, a fetch outside executable segments or a breakpoint.
+    /// `trace(pc, insn, cpu)` runs after each instruction that executes
+    /// without a trap, with the CPU state it left behind.
+    pub fn run(&mut self, max_steps: usize, mut trace: impl FnMut(u32, &Decoded, &Cpu)) -> RunExit {
+        let dec = Tc16Decoder::new();
+        let resume = self.stopped_at.take();
+        let mut n = 0usize;
+        loop {
+            if n >= max_steps { break RunExit::StepCap; }
+            let pc = self.cpu.pc;
+            match self.img.segment_containing(pc) {
+                None => break RunExit::Unmapped { pc },
+                Some(s) if !s.perms.contains('x') => break RunExit::NoExec { pc, segment: s.name.clone() },
+                Some(_) => {}
+            }
+            // The breakpoint we stopped at last time is the one being continued from
+            if self.breakpoints.contains(&pc) && !(n == 0 && resume == Some(pc)) {
+                self.stopped_at = Some(pc);
+                break RunExit::Break { pc };
+            }
+            // RET with nothing on the call stack returns out of the entry function
+            let top_level = self.cpu.call_stack.is_empty();
+            let mut top_ret = false;
+            let step = self.cpu.step_traced(&mut self.mem, &dec, &IntExecutor, |pc, d, cpu| {
+                top_ret = top_level && matches!(d.op, Op::Ret);
+                trace(pc, d, cpu);
+            });
+            if let Err(t) = step { break RunExit::Trap(t); }
+            n += 1;
+            self.steps += 1;
+            if top_ret { break RunExit::Ret { pc }; }
+        }
+    }
+}
+
+/// Emulate from `entry` until a trap, a top-level RET, a fetch outside
+/// executable segments, or `max_steps` instructions. Returns the final CPU
+/// state, the exit reason and the number of instructions executed.
+pub fn run_image(img: &Image, entry: u32, max_steps: usize) -> Result<(Cpu, RunExit, usize)> {
+    run_image_traced(img, entry, max_steps, |_, _, _| {})
+}
+
+/// `run_image` with a trace hook, as in `Emulator::run`.
+pub fn run_image_traced(img: &Image, entry: u32, max_steps: usize, trace: impl FnMut(u32, &Decoded, &Cpu)) -> Result<(Cpu, RunExit, usize)> {
+    let mut emu = Emulator::new(img, entry)?;
+    let exit = emu.run(max_steps, trace);
+    Ok((emu.cpu, exit, emu.steps))
+}
+
+/// ` d3=0x5 a2=0x10` for each D/A register of `cpu` that differs from the
+/// earlier `gpr`/`a` snapshot.
+pub fn fmt_reg_changes(gpr: &[u32; 16], a: &[u32; 16], cpu: &Cpu) -> String {
+    let mut s = String::new();
+    for (bank, old, new) in [("d", gpr, &cpu.gpr), ("a", a, &cpu.a)] {
+        for (i, (o, n)) in old.iter().zip(new.iter()).enumerate() {
+            if o != n { let _ = write!(s, " {bank}{i}={n:#x}"); }
+        }
+    }
+    s
+}
+
+/// Render D/A registers and PSW in a fixed 4-per-row layout.
+pub fn fmt_regs(cpu: &Cpu) -> String {
+    let mut s = String::new();
+    for (bank, regs) in [("d", &cpu.gpr), ("a", &cpu.a)] {
+        for row in 0..4 {
+            for i in row * 4..row * 4 + 4 {
+                let _ = write!(s, "{bank}{i:<2} = {:#010x}  ", regs[i]);
+            }
+            s.truncate(s.trim_end().len());
+            s.push('\n');
+        }
+    }
+    let _ = writeln!(s, "pc  = {:#010x}  psw = {:?}", cpu.pc, cpu.psw);
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Segment;
+
+    fn seg(name: &str, base: u32, bytes: Vec<u8>, perms: &'static str) -> Segment {
+        Segment { name: name.into(), base, bytes, perms, kind: "raw" }
+    }
+
+    #[test]
+    fn non_executable_and_unmapped_fetches_stop_cleanly() {
+        // 16-bit MOV D0,#1 then fall into a data segment
+        let img = Image { segments: vec![seg("text", 0x100, vec![0x82, 0x10], "r-x"), seg("data", 0x102, vec![0; 8], "rw-")], entry: None };
+        let (cpu, exit, steps) = run_image(&img, 0x100, 10).unwrap();
+        assert_eq!(steps, 1);
+        assert_eq!(cpu.gpr[0], 1);
+        assert!(matches!(exit, RunExit::NoExec { pc: 0x102, ref segment } if segment == "data"));
+
+        let (_, exit, steps) = run_image(&img, 0x200, 10).unwrap();
+        assert_eq!(steps, 0);
+        assert!(matches!(exit, RunExit::Unmapped { pc: 0x200 }));
+    }
+
+    #[test]
+    fn trace_reports_flags_after_compare() {
+        // mov d1,#3; mov d2,#3; cmp d1,d2 (RR 0x18); ret
+        let mut bytes = vec![0x82, 0x31, 0x82, 0x32];
+        bytes.extend_from_slice(&((0x18u32 << 20) | (2 << 16) | (1 << 8) | 0x0B).to_le_bytes());
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![seg("text", 0, bytes, "r-x")], entry: None };
+        let mut lines = Vec::new();
+        let (_, exit, _) = run_image_traced(&img, 0, 10, |pc, d, cpu| {
+            lines.push(format!("{pc:#010x}: {} {}", tricore_rs::disasm::fmt_decoded(d), cpu.psw.zncv()));
+        }).unwrap();
+        assert!(matches!(exit, RunExit::Ret { pc: 8 }));
+        // Equal operands: Z set, and C set because the subtraction does not borrow
+        assert_eq!(lines[2], "0x00000004: cmp d1, d2 [Z-C-]");
+    }
+
+    #[test]
+    fn breakpoint_stops_before_the_second_instruction_and_continues() {
+        // mov d0,#1; mov d1,#2; mov d2,#3; ret
+        let mut bytes = vec![0x82, 0x10, 0x82, 0x21, 0x82, 0x32];
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![seg("text", 0, bytes, "r-x")], entry: None };
+        let mut emu = Emulator::new(&img, 0).unwrap();
+        emu.breakpoints.insert(2);
+        assert!(matches!(emu.run(100, |_, _, _| {}), RunExit::Break { pc: 2 }));
+        assert_eq!((emu.steps, emu.cpu.pc, emu.cpu.gpr[1]), (1, 2, 0));
+
+        // Continuing steps over the breakpoint we are sitting on
+        assert!(matches!(emu.run(1, |_, _, _| {}), RunExit::StepCap));
+        assert_eq!((emu.steps, emu.cpu.gpr[1]), (2, 2));
+        assert!(matches!(emu.run(100, |_, _, _| {}), RunExit::Ret { pc: 6 }));
+
+        // A breakpoint that is never reached just runs to the budget
+        let mut emu = Emulator::new(&img, 0).unwrap();
+        emu.breakpoints.insert(0x100);
+        assert!(matches!(emu.run(2, |_, _, _| {}), RunExit::StepCap));
+        assert_eq!(emu.steps, 2);
+    }
+}
@@ -1,7 +1,10 @@
 pub mod analyze;
+pub mod asm;
+pub mod emu;
 pub mod model;
+pub mod query;
 
 // Re-export commonly used types/functions for consumers (GUI)
-pub use analyze::{analyze_entries, Block, EdgeKind, EdgeOut, FunctionOut, Report};
-pub use model::{load_raw_bin, read_u8, read_u32, Image};
+pub use analyze::{analyze_entries, boundary_diagnostics, build_report, coverage, disasm_jsonl, disassemble_range, find_instruction_start, find_overlaps, find_seeds, insn_mix, iter_insns, reanalyze_around, xrefs_to, Block, Coverage, Diagnostic, DisasmLine, DisasmOptions, EdgeKind, EdgeOut, FunctionOut, InsnMix, Overlap, Report};
+pub use model::{checksum, detect_format, is_elf, load_elf, load_ihex, load_input, load_raw_bin, load_srec, load_symbol_map, parse_ihex, parse_srec, parse_symbol_map, ChecksumAlgo, Endian, InputFormat, fetch_insn, read_u8, read_u32, read_u32_contig, save_elf, save_ihex, save_srec, write_elf, write_ihex, write_srec, Image};
 
@@ -1,17 +1,19 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand, ValueEnum};
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
-use tricore_rs::disasm::fmt_decoded;
+use tricore_rs::disasm::{fmt_decoded, fmt_decoded_styled, fmt_decoded_with_style, MnemonicStyle};
 use tricore_rs::isa::tc16::Tc16Decoder;
 use tricore_rs::decoder::Decoder;
+use tricore_rs::{Cpu, Trap};
 
 mod model;
 mod analyze;
-use analyze::{analyze_entries, Block, EdgeKind, EdgeOut, FunctionOut};
-use model::{Image, Segment, load_raw_bin, read_u8, read_u32};
+mod emu;
+use analyze::{build_report, coverage, disassemble_range, find_seeds_with, insn_mix, iter_insns, DisasmOptions, overlapping_decodes, split_stubs, Block, DEFAULT_VECTOR_SLOTS, Diagnostic, EdgeOut, FunctionOut, Overlap, Report};
+use model::{checksum, ChecksumAlgo, Endian, Image, InputFormat, Segment, load_input, load_raw_bin, save_elf, save_ihex, save_srec, fetch_insn, read_u8, load_symbol_map};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "TriCore disassembler CLI", long_about=None)]
@@ -22,12 +24,24 @@ struct Cli {
     /// Skip N bytes at start of file before loading
     #[arg(long, default_value_t = 0usize)]
     skip: usize,
-    /// Input binary path
+    /// Input binary path (not used by batch-analyze)
     #[arg(value_name = "BINFILE")]
-    input: String,
+    input: Option<String>,
     /// Limit bytes loaded (default: to EOF after --skip)
     #[arg(long)]
     len: Option<usize>,
+    /// Also map a raw file at a base (repeatable); overlapping regions are an error
+    #[arg(long = "map", value_name = "FILE@BASE[:SKIP[:LEN]]")]
+    maps: Vec<String>,
+    /// Input container (default: detect ELF by magic, S-record or Intel HEX by a well-formed first record, else raw .bin)
+    #[arg(long = "format", value_enum)]
+    input_format: Option<InputFormat>,
+    /// Byte order of the input dump; normalized to little-endian on load
+    #[arg(long, value_enum, global = true, default_value_t = Endian::Le)]
+    endian: Endian,
+    /// Instruction syntax: default, gnu (`%d0`), tasking (uppercase hex) or dest-last
+    #[arg(long, global = true, default_value_t = MnemonicStyle::Default)]
+    syntax: MnemonicStyle,
     /// Subcommand
     #[command(subcommand)]
     cmd: Command,
@@ -46,37 +60,188 @@ enum Command {
         /// Show instruction bytes
         #[arg(long)]
         show_bytes: bool,
+        /// Emit a Rust array of (raw_u32, expected_mnemonic) for use in tests
+        #[arg(long)]
+        emit_fixture: bool,
+        /// Import labels from JSON (Vec<{ addr, name }>) to name branch targets
+        #[arg(long, value_name = "FILE")]
+        labels_in: Option<String>,
+        /// Decode from every byte offset and report alternative instruction streams
+        #[arg(long)]
+        overlapping: bool,
+        /// Show addresses and targets as `segment+offset` instead of absolute
+        #[arg(long)]
+        seg_relative: bool,
         /// Write output to file instead of stdout
         #[arg(long, value_name = "FILE")]
         out: Option<String>,
     },
+    /// Tally decoded vs unknown bytes in [start, end) and list the top unknown op1 bytes
+    Coverage {
+        /// Start address (hex or dec)
+        start: String,
+        /// End address (hex or dec, exclusive)
+        end: String,
+        /// How many unknown op1 bytes to list
+        #[arg(long, default_value_t = 10usize)]
+        top: usize,
+    },
+    /// Hexdump [start, end) with an ASCII gutter; unmapped bytes print as blanks
+    Dump {
+        /// Start address (hex or dec)
+        start: String,
+        /// End address (hex or dec, exclusive)
+        end: String,
+        /// Bytes per row
+        #[arg(long, default_value_t = 16usize)]
+        width: usize,
+        /// Write the dump to file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<String>,
+    },
+    /// Checksum [start, end) and print it in hex
+    Checksum {
+        /// Start address (hex or dec)
+        start: String,
+        /// End address (hex or dec, exclusive)
+        end: String,
+        /// Checksum algorithm
+        #[arg(long, value_enum, default_value_t = ChecksumAlgo::Sum32)]
+        algo: ChecksumAlgo,
+        /// Count unmapped bytes as 0x00 instead of failing
+        #[arg(long)]
+        zero_fill: bool,
+    },
+    /// Write the loaded image (all mapped segments) back out
+    Save {
+        /// Output path
+        out: String,
+        /// Container: elf keeps every segment's base and perms; bin needs a single segment
+        #[arg(long, value_enum, default_value_t = InputFormat::Elf)]
+        format: InputFormat,
+        /// Data bytes per srec/ihex record
+        #[arg(long, default_value_t = 16usize)]
+        record_len: usize,
+    },
     /// Analyze code graph from entry points
     Analyze {
         /// Entry addresses (hex or dec). Repeat flag to add multiple entries.
         #[arg(long = "entry", value_name = "ADDR", num_args = 1.., required = false)]
         entries: Vec<String>,
+        /// Vector table base to scan for code pointers when no entry is given
+        #[arg(long, value_name = "ADDR")]
+        vector_base: Option<String>,
+        /// Entry label names resolved against `--labels-in`/`--symbols`. Repeat flag to add multiple entries.
+        #[arg(long = "entry-name", value_name = "NAME", num_args = 1.., required = false)]
+        entry_names: Vec<String>,
         /// Maximum instructions to decode before stopping
         #[arg(long, default_value_t = 100_000usize)]
         max_instr: usize,
-        /// Output format: text or json
+        /// Output format: text, json, or dot (Graphviz block-level CFG)
         #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
         format: OutputFormat,
+        /// List functions with fewer blocks than this as stubs, outside the report and graph
+        #[arg(long, default_value_t = 1usize, value_name = "N")]
+        min_func_blocks: usize,
         /// Emit a linear disassembly listing of analyzed code (text format only)
         #[arg(long)]
         listing: bool,
         /// Show instruction bytes in listing (text format only)
         #[arg(long)]
         show_bytes: bool,
+        /// Group the listing by basic block, with outgoing edges after each block
+        #[arg(long)]
+        by_block: bool,
+        /// Count analyzed instructions per op and category (text table, JSON `stats`)
+        #[arg(long)]
+        stats: bool,
         /// Import labels from JSON (Vec<{ addr, name }>)
         #[arg(long, value_name = "FILE")]
         labels_in: Option<String>,
+        /// Import labels from a text symbol map (`0xADDR name` or `nm` lines)
+        #[arg(long, value_name = "FILE")]
+        symbols: Option<String>,
         /// Export labels to JSON (Vec<{ addr, name }>)
         #[arg(long, value_name = "FILE")]
         labels_out: Option<String>,
+        /// Export every resolved branch/call target as JSON ({ by_addr, by_name })
+        #[arg(long, value_name = "FILE")]
+        targets_out: Option<String>,
         /// Write analysis output to file instead of stdout
         #[arg(long, value_name = "FILE")]
         out: Option<String>,
     },
+    /// Analyze from one address and list only that function, block by block
+    Func {
+        /// Function entry address (hex or dec)
+        addr: String,
+        /// Maximum instructions to decode before stopping
+        #[arg(long, default_value_t = 100_000usize)]
+        max_instr: usize,
+        /// Show instruction bytes
+        #[arg(long)]
+        show_bytes: bool,
+        /// Write the listing to file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<String>,
+    },
+    /// Emulate from an entry point until a trap, top-level RET, or the step cap
+    Run {
+        /// Entry address (hex or dec; default: the image entry point)
+        entry: Option<String>,
+        /// Maximum instructions to execute
+        #[arg(long, default_value_t = 1_000_000usize)]
+        max_steps: usize,
+        /// Print the final D/A registers and PSW
+        #[arg(long)]
+        dump_regs: bool,
+        /// Trace each executed instruction with the D/A registers it changed
+        #[arg(long)]
+        trace: bool,
+        /// Trace each executed instruction with the PSW Z/N/C/V flags after it
+        #[arg(long)]
+        trace_flags: bool,
+        /// Stop before executing the instruction at ADDR (repeatable)
+        #[arg(long = "break", value_name = "ADDR")]
+        breaks: Vec<String>,
+        /// After stopping at a breakpoint, continue for N more instructions
+        #[arg(long = "continue", value_name = "N")]
+        continue_steps: Option<usize>,
+    },
+    /// Analyze every matching file in a directory, one JSON report per file
+    BatchAnalyze {
+        /// Directory to scan (not recursive)
+        dir: String,
+        /// File name pattern (`*` and `?` wildcards)
+        #[arg(long, default_value = "*.bin")]
+        glob: String,
+        /// Directory receiving `<file>.json` reports
+        #[arg(long, value_name = "DIR", default_value = "reports")]
+        out_dir: String,
+        /// Maximum instructions to decode per file
+        #[arg(long, default_value_t = 100_000usize)]
+        max_instr: usize,
+    },
+}
+
+fn render_coverage(cov: &analyze::Coverage, top: usize) -> String {
+    use std::fmt::Write as _;
+    let total = cov.decoded + cov.unknown + cov.unreached;
+    let pct = |n: u32| if total == 0 { 0.0 } else { n as f64 * 100.0 / total as f64 };
+    let mut buf = String::new();
+    let _ = writeln!(buf, "decoded:   {:>8} bytes ({:5.1}%)", cov.decoded, pct(cov.decoded));
+    let _ = writeln!(buf, "unknown:   {:>8} bytes ({:5.1}%)", cov.unknown, pct(cov.unknown));
+    let _ = writeln!(buf, "unreached: {:>8} bytes ({:5.1}%)", cov.unreached, pct(cov.unreached));
+    let ranked = cov.top_unknown_op1(top);
+    if !ranked.is_empty() {
+        let _ = writeln!(buf, "top unknown op1:");
+        for (op1, n) in ranked { let _ = writeln!(buf, "  {op1:#04x} {n:>8}"); }
+    }
+    if !cov.unknown_reasons.is_empty() {
+        let _ = writeln!(buf, "unknown by reason:");
+        for (reason, n) in &cov.unknown_reasons { let _ = writeln!(buf, "  {:<18} {n:>8}", reason.to_string()); }
+    }
+    buf
 }
 
 fn parse_u32(s: &str) -> Result<u32> {
@@ -88,12 +253,6 @@ fn parse_u32(s: &str) -> Result<u32> {
     }
 }
 
-fn read_u16(img: &Image, addr: u32) -> Option<u16> { // used by range renderer
-    let b0 = read_u8(img, addr)?;
-    let b1 = read_u8(img, addr.wrapping_add(1))?;
-    Some(u16::from_le_bytes([b0, b1]))
-}
-
 fn is_mapped(img: &Image, addr: u32) -> bool {
     img.segments.iter().any(|s| {
         let start = s.base;
@@ -105,7 +264,7 @@ fn is_mapped(img: &Image, addr: u32) -> bool {
 // parse_u32 utility stays local to main for CLI parsing
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
-enum OutputFormat { Text, Json }
+enum OutputFormat { Text, Json, Dot }
 
 #[derive(Debug, Clone, serde::Serialize)]
 struct BlockOut { start: u32, end: u32, insns: Vec<String> }
@@ -113,162 +272,201 @@ struct BlockOut { start: u32, end: u32, insns: Vec<String> }
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct LabelKV { addr: u32, name: String }
 
+/// Two-way map of resolved branch/call targets; addresses are `0x%08x` keys.
+#[derive(Debug, Default, serde::Serialize)]
+struct TargetMap { by_addr: BTreeMap<String, String>, by_name: BTreeMap<String, u32> }
+
+/// Name every branch, call and jump-table target in `edges`, including ones
+/// that never became blocks (unmapped or unwalked). Imported `labels` win;
+/// otherwise call targets are `sub_` and the rest `loc_`.
+fn target_map(edges: &[EdgeOut], labels: &HashMap<u32, String>) -> TargetMap {
+    let mut names: BTreeMap<u32, String> = BTreeMap::new();
+    for e in edges.iter().filter(|e| e.kind != "ft" && e.kind != "ret") {
+        let auto = if e.kind == "call" { format!("sub_{:08x}", e.to) } else { format!("loc_{:08x}", e.to) };
+        let name = labels.get(&e.to).cloned().unwrap_or(auto);
+        // A call anywhere makes the target a function, whatever else reaches it
+        if e.kind == "call" || !names.contains_key(&e.to) { names.insert(e.to, name); }
+    }
+    let mut map = TargetMap::default();
+    for (addr, name) in names {
+        map.by_addr.insert(format!("{addr:#010x}"), name.clone());
+        map.by_name.insert(name, addr);
+    }
+    map
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 struct ReportWithLabels {
     entries: Vec<u32>,
     blocks: Vec<BlockOut>,
     edges: Vec<EdgeOut>,
     functions: Vec<FunctionOut>,
+    /// Functions below `--min-func-blocks`
+    stubs: Vec<FunctionOut>,
+    overlaps: Vec<Overlap>,
+    diagnostics: Vec<Diagnostic>,
     labels: Vec<LabelKV>,
+    /// Instruction mix, with `--stats`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<analyze::InsnMix>,
+}
+
+/// Split a `--map FILE@BASE[:SKIP[:LEN]]` spec; numbers are hex or decimal.
+fn parse_map_spec(spec: &str) -> Result<(String, u32, usize, Option<usize>)> {
+    let (path, rest) = spec.rsplit_once('@').ok_or_else(|| anyhow::anyhow!("--map {spec}: expected FILE@BASE[:SKIP[:LEN]]"))?;
+    let mut nums = rest.split(':');
+    let base = parse_u32(nums.next().unwrap_or_default())?;
+    let skip = nums.next().map(parse_u32).transpose()?.unwrap_or(0) as usize;
+    let len = nums.next().map(parse_u32).transpose()?.map(|n| n as usize);
+    anyhow::ensure!(nums.next().is_none(), "--map {spec}: too many fields");
+    Ok((path.to_string(), base, skip, len))
+}
+
+/// Load each `--map` file as a raw segment named after the file and merge
+/// them into `img`.
+fn load_maps(img: &mut Image, maps: &[String], endian: Endian) -> Result<()> {
+    for spec in maps {
+        let (path, base, skip, len) = parse_map_spec(spec)?;
+        let mut part = load_raw_bin(Path::new(&path), base, skip, len, endian)?;
+        let name = Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or(path);
+        for s in &mut part.segments { s.name = name.clone(); }
+        img.merge(part)?;
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let img = load_raw_bin(Path::new(&cli.input), cli.base, cli.skip, cli.len)?;
+    if let Command::BatchAnalyze { dir, glob, out_dir, max_instr } = &cli.cmd {
+        let load = |p: &Path| load_input(p, cli.input_format, cli.base, cli.skip, cli.len, cli.endian);
+        for line in batch_analyze(Path::new(dir), glob, Path::new(out_dir), max_instr.to_owned(), load)? {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+    let mut img = match &cli.input {
+        Some(input) => load_input(Path::new(input), cli.input_format, cli.base, cli.skip, cli.len, cli.endian)?,
+        None if !cli.maps.is_empty() => Image::new(Vec::new(), None)?,
+        None => anyhow::bail!("missing BINFILE argument"),
+    };
+    load_maps(&mut img, &cli.maps, cli.endian)?;
 
     match cli.cmd {
         Command::Sections => {
             println!("{:<10} {:<#12} {:<#12} {:<6} {:<6}", "name", "start", "end", "perms", "kind");
             for s in &img.segments {
                 let start = s.base;
-                let end = s.base + (s.bytes.len() as u32);
+                let end = s.end()?;
                 println!(
                     "{:<10} {start:#010x} {end:#010x} {:<6} {:<6}",
                     s.name, s.perms, s.kind
                 );
             }
         }
-        Command::Range { start, end, show_bytes, out } => {
+        Command::Range { start, end, show_bytes, emit_fixture, labels_in, overlapping, seg_relative, out } => {
             let start = parse_u32(&start)?;
             let end = parse_u32(&end)?;
             anyhow::ensure!(end >= start, "end must be >= start");
+            if emit_fixture {
+                let buf = emit_fixture_rs(&img, start, end);
+                if let Some(path) = out { std::fs::write(path, buf)?; } else { print!("{}", buf); }
+                return Ok(());
+            }
+            if overlapping {
+                let buf = render_overlapping(&img, start, end);
+                if let Some(path) = out { std::fs::write(path, buf)?; } else { print!("{}", buf); }
+                return Ok(());
+            }
 
-            let dec = Tc16Decoder::new();
-            let mut pc = start;
+            let labels: HashMap<u32, String> = labels_in.as_deref().map(import_labels).transpose()?.unwrap_or_default();
+            // Labels win; unlabeled targets follow the address column's style
+            let opts = DisasmOptions { show_bytes, labels: Some(&labels), seg_relative, style: cli.syntax };
+            use std::fmt::Write as _;
             let mut buf = String::new();
-            while pc < end {
-                let Some(raw32) = read_u32(&img, pc) else { println!("{pc:#010x}: <oob>"); break; };
-                if let Some(d) = dec.decode(raw32) {
-                    if show_bytes {
-                        let w = d.width as u32;
-                        let mut bytes = Vec::new();
-                        for i in 0..w { bytes.push(read_u8(&img, pc + i).unwrap_or(0)); }
-                        use std::fmt::Write as _;
-                        let _ = write!(buf, "{pc:#010x}: ");
-                        for b in bytes { let _ = write!(buf, "{:02x} ", b); }
-                        let _ = writeln!(buf, "  {}", fmt_decoded(&d));
-                    } else {
-                        use std::fmt::Write as _;
-                        let _ = writeln!(buf, "{pc:#010x}: {}", fmt_decoded(&d));
-                    }
-                    pc = pc.wrapping_add(d.width as u32);
+            let mut next = start;
+            for line in disassemble_range(&img, start, end, opts) {
+                let addr = img.fmt_addr(line.addr, seg_relative);
+                if show_bytes && !line.text.starts_with(".2byte") {
+                    let _ = write!(buf, "{addr}: ");
+                    for b in &line.bytes { let _ = write!(buf, "{b:02x} "); }
+                    let _ = writeln!(buf, "  {}", line.text);
                 } else {
-                    use std::fmt::Write as _;
-                    let _ = writeln!(buf, "{pc:#010x}: .word {raw32:#010x}");
-                    pc = pc.wrapping_add(4);
+                    let _ = writeln!(buf, "{addr}: {}", line.text);
                 }
+                next = line.addr.saturating_add(line.len as u32);
             }
+            if next < end { let _ = writeln!(buf, "{}: <oob>", img.fmt_addr(next, seg_relative)); }
             if let Some(path) = out { std::fs::write(path, buf)?; } else { print!("{}", buf); }
         }
-        Command::Analyze { entries, max_instr, format, listing, show_bytes, labels_in, labels_out, out } => {
-            // default seed: start of first segment
-            let mut seeds: Vec<u32> = if entries.is_empty() {
-                img.segments.get(0).map(|s| s.base).into_iter().collect()
+        Command::Coverage { start, end, top } => {
+            let start = parse_u32(&start)?;
+            let end = parse_u32(&end)?;
+            anyhow::ensure!(end >= start, "end must be >= start");
+            print!("{}", render_coverage(&coverage(&img, start, end), top));
+        }
+        Command::Dump { start, end, width, out } => {
+            let start = parse_u32(&start)?;
+            let end = parse_u32(&end)?;
+            anyhow::ensure!(end >= start, "end must be >= start");
+            anyhow::ensure!(width > 0, "--width must be at least 1");
+            let buf = render_dump(&img, start, end, width);
+            if let Some(path) = out { std::fs::write(path, buf)?; } else { print!("{}", buf); }
+        }
+        Command::Checksum { start, end, algo, zero_fill } => {
+            let start = parse_u32(&start)?;
+            let end = parse_u32(&end)?;
+            anyhow::ensure!(end >= start, "end must be >= start");
+            println!("{:#010x}", checksum(&img, start, end, algo, zero_fill)?);
+        }
+        Command::Save { out, format, record_len } => match format {
+            InputFormat::Elf => save_elf(&img, Path::new(&out))?,
+            InputFormat::Bin => {
+                let [seg] = img.segments.as_slice() else {
+                    anyhow::bail!("a raw .bin holds one segment but the image has {}; use --format elf", img.segments.len());
+                };
+                std::fs::write(&out, &seg.bytes)?;
+            }
+            InputFormat::Srec => save_srec(&img, Path::new(&out), record_len)?,
+            InputFormat::Ihex => save_ihex(&img, Path::new(&out), record_len)?,
+        },
+        Command::Analyze { entries, entry_names, vector_base, max_instr, format, min_func_blocks, listing, show_bytes, by_block, stats, labels_in, symbols, labels_out, targets_out, out } => {
+            // Imported labels; needed up front to resolve --entry-name
+            let mut labels: HashMap<u32, String> = labels_in.as_deref().map(import_labels).transpose()?.unwrap_or_default();
+            if let Some(path) = &symbols {
+                let (syms, skipped) = load_symbol_map(Path::new(path))?;
+                if skipped > 0 { eprintln!("warning: skipped {skipped} malformed line(s) in {path}"); }
+                labels.extend(syms);
+            }
+            if !entry_names.is_empty() && labels_in.is_none() && symbols.is_none() {
+                anyhow::bail!("--entry-name requires --labels-in or --symbols");
+            }
+            // default seeds: image entry, boot header and vector table pointers
+            let mut seeds: Vec<u32> = if entries.is_empty() && entry_names.is_empty() {
+                let vb = vector_base.as_deref().map(parse_u32).transpose()?;
+                find_seeds_with(&img, vb, DEFAULT_VECTOR_SLOTS)
             } else {
                 let mut v = Vec::new();
                 for e in entries { v.push(parse_u32(&e)?); }
+                for n in &entry_names { v.push(resolve_label(&labels, n)?); }
                 v
             };
             seeds.sort_unstable();
             seeds.dedup();
-            let (visited, widths, edges, rets) = analyze_entries(&img, &seeds, max_instr);
-
-            // Compute block starts: entries + all edge destinations
-            let mut block_starts: HashSet<u32> = seeds.iter().copied().collect();
-            for e in &edges { block_starts.insert(e.to); }
-
-            // Build blocks by linear sweep from each start until next start/unknown
-            let mut starts: Vec<u32> = block_starts.into_iter().collect();
-            starts.sort_unstable();
-            let mut blocks: Vec<Block> = Vec::new();
-            let mut addr_to_block: HashMap<u32, u32> = HashMap::new(); // pc -> block start
-            for &start in &starts {
-                if !visited.contains(&start) { continue; }
-                // Avoid duplicating blocks if we've already assigned this start
-                if addr_to_block.contains_key(&start) { continue; }
-                let mut cur = start;
-                loop {
-                    let Some(&w) = widths.get(&cur) else { break };
-                    let next = cur.wrapping_add(w as u32);
-                    // Is current instruction an unconditional branch? If so, close after it.
-                    let is_uncond = edges.iter().any(|e| e.from == cur && matches!(e.kind, EdgeKind::Branch));
-                    let is_ret = rets.contains(&cur);
-                    // If next is a new block start or we hit an uncond branch or unknown/visited gap, end block at next
-                    let should_end = is_uncond || is_ret
-                        || !visited.contains(&next)
-                        || starts.binary_search(&next).is_ok();
-                    if should_end {
-                        let end = next;
-                        blocks.push(Block { start, end });
-                        // Map all PCs from start to end into this block
-                        let mut pc = start;
-                        while pc < end {
-                            addr_to_block.insert(pc, start);
-                            if let Some(&ww) = widths.get(&pc) { pc = pc.wrapping_add(ww as u32); } else { break; }
-                        }
-                        break;
-                    } else {
-                        cur = next;
-                    }
-                }
+            let Report { blocks, edges: edges_out, functions, overlaps, diagnostics, labels: auto_labels, visited, widths, .. } = build_report(&img, &seeds, max_instr);
+            let (functions, stubs) = split_stubs(functions, min_func_blocks);
+            // Before the loc_ fill below, so call targets keep their sub_ names
+            if let Some(path) = &targets_out {
+                std::fs::write(path, serde_json::to_string_pretty(&target_map(&edges_out, &labels))?)?;
             }
 
-            // Normalize edges to block-level
-            let mut edges_out: Vec<EdgeOut> = Vec::new();
-            for e in &edges {
-                let from_block = *addr_to_block.get(&e.from).unwrap_or(&e.from);
-                let to_block = starts.iter().copied().find(|&s| s == e.to).unwrap_or(e.to);
-                let kind = match e.kind { EdgeKind::Fallthrough => "ft", EdgeKind::Branch => "br", EdgeKind::CondBranch => "cbr", EdgeKind::Call => "call" }.to_string();
-                edges_out.push(EdgeOut { from: from_block, to: to_block, kind });
-            }
+            let stats = stats.then(|| insn_mix(&img, &widths));
 
-            // Functions: treat each seed as a root and collect reachable block starts
-            let mut functions: Vec<FunctionOut> = Vec::new();
-            // Build adjacency from block-level edges
-            let mut adj: HashMap<u32, Vec<u32>> = HashMap::new();
-            for e in &edges_out { adj.entry(e.from).or_default().push(e.to); }
-            for &entry in &seeds {
-                // Map entry to block start
-                let entry_block = starts.iter().copied().find(|&s| s == entry).unwrap_or(entry);
-                let mut seen: HashSet<u32> = HashSet::new();
-                let mut q = VecDeque::new();
-                q.push_back(entry_block);
-                while let Some(b) = q.pop_front() {
-                    if !seen.insert(b) { continue; }
-                    if let Some(nexts) = adj.get(&b) {
-                        for &n in nexts { q.push_back(n); }
-                    }
-                }
-                let mut blks: Vec<u32> = seen.into_iter().collect();
-                blks.sort_unstable();
-                functions.push(FunctionOut { entry: entry_block, blocks: blks });
-            }
-
-            // Prepare labels (imported or autogenerated)
-            let mut labels: HashMap<u32, String> = HashMap::new();
-            if let Some(path) = &labels_in {
-                if let Ok(txt) = std::fs::read_to_string(path) {
-                    if let Ok(v) = serde_json::from_str::<Vec<LabelKV>>(&txt) {
-                        for kv in v { labels.insert(kv.addr, kv.name); }
-                    }
-                }
-            }
-            for &e in &seeds { labels.entry(e).or_insert_with(|| format!("sub_{e:08x}")); }
-            for b in &blocks { labels.entry(b.start).or_insert_with(|| format!("loc_{:08x}", b.start)); }
+            // Fill in autogenerated labels
+            for (a, n) in auto_labels { labels.entry(a).or_insert(n); }
 
             match format {
                 OutputFormat::Json => {
-                    let report_blocks = enrich_blocks_with_mnemonics(&img, &widths, &blocks, show_bytes);
+                    let report_blocks = enrich_blocks_with_mnemonics(&img, &widths, &blocks, show_bytes, None, cli.syntax);
                     // Optionally export labels
                     if let Some(outp) = &labels_out {
                         let mut arr: Vec<LabelKV> = Vec::new();
@@ -277,22 +475,49 @@ fn main() -> Result<()> {
                     }
                     let mut lbl_vec: Vec<LabelKV> = labels.iter().map(|(k,v)| LabelKV { addr: *k, name: v.clone() }).collect();
                     lbl_vec.sort_by_key(|kv| kv.addr);
-                    let report = ReportWithLabels { entries: seeds.clone(), blocks: report_blocks, edges: edges_out, functions, labels: lbl_vec };
+                    let report = ReportWithLabels { entries: seeds.clone(), blocks: report_blocks, edges: edges_out, functions, stubs, overlaps, diagnostics, labels: lbl_vec, stats };
                     let json = serde_json::to_string_pretty(&report)?;
                     if let Some(path) = out { std::fs::write(path, json)?; } else { println!("{}", json); }
                 }
+                OutputFormat::Dot => {
+                    let (blocks, edges_out) = without_stub_blocks(&blocks, &edges_out, &functions, &stubs);
+                    let dot = render_dot(&blocks, &edges_out, &labels);
+                    if let Some(path) = out { std::fs::write(path, dot)?; } else { print!("{}", dot); }
+                }
                 OutputFormat::Text => {
                     println!("Analysis summary:");
                     println!("  entries   : {:?}", seeds.iter().map(|a| format!("{a:#010x}")).collect::<Vec<_>>());
                     println!("  insts     : {}", visited.len());
                     println!("  blocks    : {}", blocks.len());
-                    println!("  edges     : {}", edges.len());
+                    println!("  edges     : {}", edges_out.len());
                     println!("  functions : {}", functions.len());
+                    if !stubs.is_empty() {
+                        println!("  stubs     : {}", stubs.len());
+                        for f in &stubs {
+                            println!("    {:#010x} ({} block(s))", f.entry, f.num_blocks);
+                        }
+                    }
+                    println!("  overlaps  : {}", overlaps.len());
+                    for o in &overlaps {
+                        println!("    {:#010x} lands inside the instruction at {:#010x} (block {:#010x})", o.addr, o.insn, o.inside_block);
+                    }
+                    println!("  diagnostics: {}", diagnostics.len());
+                    for d in &diagnostics {
+                        println!("    {:#010x}: {}", d.addr, d.message);
+                    }
+                    if let Some(mix) = &stats {
+                        println!("Instruction mix:");
+                        for (cat, n) in &mix.by_category { println!("  {cat:<10} {n:>8}"); }
+                        for (op, n) in mix.ranked() { println!("    {op:<12} {n:>8}"); }
+                    }
                     println!("Edges:");
                     for e in &edges_out {
                         println!("  {:#010x} -> {:#010x} ({})", e.from, e.to, e.kind);
                     }
-                    if listing {
+                    if listing && by_block {
+                        println!("\nListing (by block):");
+                        print!("{}", render_by_block(&img, &widths, &blocks, &edges_out, &labels, show_bytes, cli.syntax));
+                    } else if listing {
                         // Order visited addresses ascending
                         let mut pcs: Vec<u32> = visited.iter().copied().collect();
                         pcs.sort_unstable();
@@ -302,7 +527,7 @@ fn main() -> Result<()> {
                             if let Some(lbl) = labels.get(&pc) {
                                 println!("{pc:#010x} <{lbl}>:");
                             }
-                            if let Some(raw32) = read_u32(&img, pc) {
+                            if let Some(raw32) = fetch_insn(&img, pc) {
                                 if let Some(d) = dec.decode(raw32) {
                                     if show_bytes {
                                         let w = d.width as u32;
@@ -310,9 +535,9 @@ fn main() -> Result<()> {
                                         for i in 0..w { bytes.push(read_u8(&img, pc + i).unwrap_or(0)); }
                                         print!("  {pc:#010x}: ");
                                         for b in bytes { print!("{:02x} ", b); }
-                                        println!("  {}", fmt_decoded(&d));
+                                        println!("  {}", fmt_decoded_with_style(&d, pc, cli.syntax, |a| labels.get(&a).cloned()));
                                     } else {
-                                        println!("  {pc:#010x}: {}", fmt_decoded(&d));
+                                        println!("  {pc:#010x}: {}", fmt_decoded_with_style(&d, pc, cli.syntax, |a| labels.get(&a).cloned()));
                                     }
                                 } else {
                                     println!("  {pc:#010x}: .word {raw32:#010x}");
@@ -329,6 +554,37 @@ fn main() -> Result<()> {
                 }
             }
         }
+        Command::Func { addr, max_instr, show_bytes, out } => {
+            let txt = render_function(&img, parse_u32(&addr)?, max_instr, show_bytes, cli.syntax)?;
+            if let Some(path) = out { std::fs::write(path, txt)?; } else { print!("{}", txt); }
+        }
+        Command::Run { entry, max_steps, dump_regs, trace, trace_flags, breaks, continue_steps } => {
+            let entry = match entry {
+                Some(e) => parse_u32(&e)?,
+                None => img.entry.ok_or_else(|| anyhow::anyhow!("no entry given and the image has none"))?,
+            };
+            let mut emu = emu::Emulator::new(&img, entry)?;
+            for b in &breaks { emu.breakpoints.insert(parse_u32(b)?); }
+            // Registers as of the previous traced step (a fresh CPU starts zeroed)
+            let (mut gpr, mut a) = ([0u32; 16], [0u32; 16]);
+            let mut tracer = |pc: u32, d: &tricore_rs::decoder::Decoded, cpu: &Cpu| {
+                if !trace && !trace_flags { return; }
+                let mut line = format!("{pc:#010x}: {:<32}", fmt_decoded_styled(d, cli.syntax));
+                if trace { line.push_str(&emu::fmt_reg_changes(&gpr, &a, cpu)); }
+                if trace_flags { line.push_str(&format!(" {}", cpu.psw.zncv())); }
+                println!("{}", line.trim_end());
+                (gpr, a) = (cpu.gpr, cpu.a);
+            };
+            let mut exit = emu.run(max_steps, &mut tracer);
+            if let (emu::RunExit::Break { .. }, Some(n)) = (&exit, continue_steps) {
+                println!("{}", describe_exit(&exit, &emu.cpu, emu.steps));
+                if dump_regs { print!("{}", emu::fmt_regs(&emu.cpu)); }
+                exit = emu.run(n, &mut tracer);
+            }
+            println!("{}", describe_exit(&exit, &emu.cpu, emu.steps));
+            if dump_regs { print!("{}", emu::fmt_regs(&emu.cpu)); }
+        }
+        Command::BatchAnalyze { .. } => unreachable!("handled before loading an image"),
     }
 
     Ok(())
@@ -337,6 +593,7 @@ fn main() -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use model::{read_u32, read_u32_contig};
 
     #[test]
     fn parse_u32_hex_and_dec() {
@@ -350,23 +607,305 @@ mod tests {
         let cwd = std::env::current_dir().unwrap();
         let path = cwd.join("_test_bin.bin");
         std::fs::write(&path, [0u8, 1, 2, 3, 4, 5]).unwrap();
-        let img = load_raw_bin(&path, 0x1000_0000, 2, Some(3)).unwrap();
+        let img = load_raw_bin(&path, 0x1000_0000, 2, Some(3), Endian::Le).unwrap();
         assert_eq!(img.segments.len(), 1);
         let s = &img.segments[0];
         assert_eq!(s.base, 0x1000_0000);
         assert_eq!(s.bytes, vec![2, 3, 4]);
-        assert_eq!(read_u32(&img, 0x1000_0000).unwrap(), 0x00040302);
-        assert!(read_u32(&img, 0x1000_0002 + 2).is_none());
+        assert_eq!(read_u8(&img, 0x1000_0002).unwrap(), 4);
+        assert!(read_u32(&img, 0x1000_0000).is_none());
+        assert!(read_u8(&img, 0x1000_0002 + 1).is_none());
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn dump_aligns_hex_and_ascii_columns_across_gaps() {
+        let img = Image { segments: vec![
+            Segment { name: "a".into(), base: 0x100, bytes: b"Hi!\x00\x7fAB".to_vec(), perms: "r--", kind: "raw" },
+            Segment { name: "b".into(), base: 0x10A, bytes: vec![0x7E, 0x20], perms: "r--", kind: "raw" },
+        ], entry: None };
+        let text = render_dump(&img, 0x100, 0x10C, 8);
+        assert_eq!(text,
+            "0x00000100  48 69 21 00 7f 41 42     |Hi!..AB |\n\
+             0x00000108        7e 20              |  ~     |\n");
+        // Every row lines up, including the trailing partial one
+        assert!(text.lines().all(|l| l.len() == text.lines().next().unwrap().len()));
+    }
+
+    #[test]
+    fn emitted_fixture_matches_decoder() {
+        // mov d1,#2 (16-bit); movu d3,#0x1234; add d1,d1,d3; ret
+        let mut bytes = vec![0x82, 0x21];
+        bytes.extend_from_slice(&((3u32 << 28) | (0x1234u32 << 12) | 0xBB).to_le_bytes());
+        bytes.extend_from_slice(&((1u32 << 28) | (3u32 << 16) | (1u32 << 8) | 0x0B).to_le_bytes());
+        bytes.extend_from_slice(&0x0000_000Du32.to_le_bytes());
+        let end = bytes.len() as u32;
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let text = emit_fixture_rs(&img, 0, end);
+        let dec = Tc16Decoder::new();
+        let mut n = 0;
+        for line in text.lines().filter(|l| l.trim_start().starts_with('(')) {
+            let inner = line.trim().trim_start_matches('(').trim_end_matches("),");
+            let (raw_s, mn_s) = inner.split_once(", ").unwrap();
+            let raw = parse_u32(raw_s).unwrap();
+            let expect: String = serde_json::from_str(mn_s).unwrap();
+            assert_eq!(fmt_decoded(&dec.decode(raw).unwrap()), expect);
+            n += 1;
+        }
+        assert_eq!(n, 4);
+        assert!(text.contains("(0x00002182, \"mov d1, #0x2\")"));
+    }
+
+    #[test]
+    fn branch_into_the_middle_of_a_32bit_insn_is_flagged() {
+        // 0x0: mov.u d1,#0x820 (4 bytes; upper half 82 10 is mov d0,#1); 0x4: j -> 0x2
+        let bytes = vec![0xBB, 0x00, 0x82, 0x10, 0x3C, 0xFE, 0x00, 0x00, 0x00, 0x00];
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        assert_eq!(report.overlaps, vec![Overlap { addr: 2, insn: 0, inside_block: 0 }]);
+
+        let clean = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes: vec![0x82, 0x10, 0x3C, 0xFF, 0, 0, 0, 0], perms: "r-x", kind: "raw" }], entry: None };
+        assert!(build_report(&clean, &[0], 100).overlaps.is_empty());
+    }
+
+    #[test]
+    fn func_lists_only_blocks_reachable_from_its_entry() {
+        // 0x0: call +4 -> 0x8; 0x4: ret | 0x8: mov d0,#1; 0xA: mov d1,#1; 0xC: ret
+        let mut bytes = ((2u32 << 16) | 0x6D).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&[0x82, 0x10, 0x82, 0x11]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let headers = |txt: &str| txt.lines().filter(|l| !l.starts_with([' ', ';']) && !l.is_empty()).map(|l| l.split(':').next().unwrap().to_string()).collect::<Vec<_>>();
+
+        let caller = render_function(&img, 0, 100, false, MnemonicStyle::Default).unwrap();
+        assert_eq!(headers(&caller), ["sub_00000000", "loc_00000004"]);
+        assert!(caller.contains("0x00000000: call sub_00000008"), "{caller}");
+        assert!(caller.contains("  -> sub_00000008 (call)"));
+        assert!(!caller.contains("0x00000008:"), "callee body must not be listed");
+
+        let callee = render_function(&img, 8, 100, false, MnemonicStyle::Default).unwrap();
+        assert!(headers(&callee).iter().all(|h| h != "sub_00000000" && h != "loc_00000004"), "{callee}");
+        assert!(callee.starts_with("; sub_00000008: [0x00000008, 0x00000010)"));
+        assert!(render_function(&img, 0x100, 100, false, MnemonicStyle::Default).is_err());
+    }
+
+    #[test]
+    fn target_map_names_call_targets_sub_both_ways() {
+        // 0x0: call +4 -> 0x8; 0x4: ret | 0x8: j -> 0xC; 0xA: pad; 0xC: ret
+        let mut bytes = ((2u32 << 16) | 0x6D).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&[0x3C, 0x01, 0x00, 0x00]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let map = target_map(&report.edges, &HashMap::new());
+        assert_eq!(map.by_addr.get("0x00000008").map(String::as_str), Some("sub_00000008"));
+        assert_eq!(map.by_name.get("sub_00000008"), Some(&8));
+        assert_eq!(map.by_addr.get("0x0000000c").map(String::as_str), Some("loc_0000000c"));
+        // Fallthrough and return edges are not targets
+        assert!(!map.by_addr.contains_key("0x00000004"));
+
+        let labels = HashMap::from([(8u32, "init".to_string())]);
+        assert_eq!(target_map(&report.edges, &labels).by_name.get("init"), Some(&8));
+    }
+
+    #[test]
+    fn min_func_blocks_moves_one_block_functions_to_stubs() {
+        // Real: 0x0: jeq d0,d0 -> 0x8; 0x4: mov d0,#1; 0x6: j -> 0x8; 0x8: ret
+        // Spurious seed at 0xC: a lone ret (data that happens to decode)
+        let mut bytes = ((2u32 << 15) | 0x5F).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0x82, 0x10, 0x3C, 0x00]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0, 0xC], 100);
+        assert_eq!(report.functions.len(), 2);
+        let (functions, stubs) = split_stubs(report.functions.clone(), 2);
+        assert_eq!(functions.iter().map(|f| f.entry).collect::<Vec<_>>(), [0]);
+        assert_eq!(stubs.iter().map(|f| (f.entry, f.num_blocks)).collect::<Vec<_>>(), [(0xC, 1)]);
+
+        let (blocks, edges) = without_stub_blocks(&report.blocks, &report.edges, &functions, &stubs);
+        let dot = render_dot(&blocks, &edges, &HashMap::new());
+        assert!(dot.contains("n00000000") && !dot.contains("n0000000c"), "{dot}");
+    }
+
+    #[test]
+    fn by_block_listing_groups_header_insns_and_edges() {
+        // 0x0: J +2 (16-bit) -> 0x4; 0x2: MOV D0,#0 (skipped); 0x4: MOV D0,#1; pad for the 32-bit fetch
+        let bytes = vec![0x3C, 0x01, 0x82, 0x00, 0x82, 0x10, 0x00, 0x00];
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let mut labels = HashMap::new();
+        for b in &report.blocks { labels.insert(b.start, format!("loc_{:08x}", b.start)); }
+        let txt = render_by_block(&img, &report.widths, &report.blocks, &report.edges, &labels, false, MnemonicStyle::Default);
+        let lines: Vec<&str> = txt.lines().collect();
+        let hdr = lines.iter().position(|l| *l == "loc_00000000: [0x00000000, 0x00000002)").expect("header");
+        assert!(lines[hdr + 1].starts_with("  0x00000000: j"));
+        assert_eq!(lines[hdr + 2], "  -> loc_00000004 (br)");
+        assert!(txt.contains("loc_00000004: [0x00000004, "));
+        assert!(!txt.contains("0x00000002:"), "skipped instruction must not be listed");
+    }
+
+    #[test]
+    fn caller_and_callee_functions_report_each_other() {
+        // 0x0: call +4 -> 0x8; 0x4: ret | 0x8: mov d0,#1; 0xA: mov d1,#1; 0xC: ret
+        let mut bytes = ((2u32 << 16) | 0x6D).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&[0x82, 0x10, 0x82, 0x11]);
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let f = |entry: u32| report.functions.iter().find(|f| f.entry == entry).unwrap_or_else(|| panic!("no function at {entry:#x}"));
+        let (caller, callee) = (f(0), f(8));
+        assert_eq!(caller.callees, vec![8]);
+        assert!(caller.callers.is_empty());
+        assert_eq!(callee.callers, vec![0]);
+        assert!(callee.callees.is_empty());
+        assert_eq!(caller.blocks, vec![0, 4], "calls are not followed into the callee");
+        assert_eq!((caller.start, caller.end, caller.size, caller.num_blocks), (0, 8, 8, 2));
+        assert_eq!((callee.start, callee.end, callee.size, callee.num_blocks), (8, 0x10, 8, callee.blocks.len()));
+    }
+
+    #[test]
+    fn ret_block_is_a_sink_without_fallthrough() {
+        // 0x0: mov d0,#1; 0x2: ret | 0x6: mov d1,#1 (decodable, but after the ret)
+        let mut bytes = vec![0x82, 0x10];
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&[0x82, 0x11]);
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        assert!(!report.visited.contains(&6));
+        let exit = *report.functions[0].exits.last().unwrap();
+        assert!(report.successors(exit).is_empty(), "{:?}", report.successors(exit));
+        assert!(report.edges.iter().any(|e| e.from == exit && e.kind == "ret"));
+        assert!(!report.edges.iter().any(|e| e.from == exit && e.kind == "ft"));
+    }
+
+    #[test]
+    fn function_with_two_returns_reports_both_exits() {
+        // 0x0: beq.f -> 0x8; 0x4: ret; 0x8: ret
+        let mut bytes = ((2u32 << 15) | 0x4D).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        bytes.extend_from_slice(&0x0090_000Du32.to_le_bytes());
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        assert_eq!(report.functions.len(), 1);
+        assert_eq!(report.functions[0].blocks, vec![0, 4, 8]);
+        assert_eq!(report.functions[0].exits, vec![4, 8]);
+        assert!(report.edges.iter().any(|e| e.from == 8 && e.to == 8 && e.kind == "ret"));
+        let json = serde_json::to_value(&report.functions[0]).unwrap();
+        assert_eq!(json["exits"], serde_json::json!([4, 8]));
+
+        let dot = render_dot(&report.blocks, &report.edges, &HashMap::new());
+        assert!(dot.contains("  n00000004 [label=\"0x00000004\", peripheries=2];"), "{dot}");
+        assert!(!dot.contains("n00000004 -> n00000004"));
+    }
+
+    #[test]
+    fn dot_output_has_block_nodes_and_styled_edges() {
+        // 0x0: J +2 -> 0x4; 0x4: MOV D0,#1; pad for the 32-bit fetch
+        let bytes = vec![0x3C, 0x01, 0x82, 0x00, 0x82, 0x10, 0x00, 0x00];
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let report = build_report(&img, &[0], 100);
+        let mut labels = HashMap::new();
+        labels.insert(0u32, "main".to_string());
+        let mut edges = report.edges.clone();
+        edges.push(EdgeOut { from: 4, to: 0, kind: "call".into() });
+        let dot = render_dot(&report.blocks, &edges, &labels);
+        assert!(dot.starts_with("digraph cfg {"));
+        assert!(dot.contains("  n00000000 [label=\"main\"];"), "{dot}");
+        assert!(dot.contains("  n00000004 [label=\"0x00000004\"];"), "{dot}");
+        assert!(dot.contains("  n00000000 -> n00000004 [color=blue];"), "{dot}");
+        assert!(dot.contains("  n00000004 -> n00000000 [style=dashed, color=red];"), "{dot}");
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn map_files_merge_into_adjacent_segments_and_reject_overlaps() {
+        let cwd = std::env::current_dir().unwrap();
+        let (a, b) = (cwd.join("_test_map_rom.bin"), cwd.join("_test_map_app.bin"));
+        std::fs::write(&a, [0x11, 0x22, 0x33, 0x44]).unwrap();
+        std::fs::write(&b, [0xFF, 0x55, 0x66, 0x77, 0x88]).unwrap();
+        let spec = |p: &Path, rest: &str| format!("{}@{rest}", p.display());
+
+        let mut img = Image::new(Vec::new(), None).unwrap();
+        load_maps(&mut img, &[spec(&a, "0x1000"), spec(&b, "0x1004:1")], Endian::Le).unwrap();
+        assert_eq!(img.segments.len(), 2);
+        assert_eq!(img.segment_containing(0x1003).unwrap().name, "_test_map_rom.bin");
+        assert_eq!(img.segment_containing(0x1004).unwrap().name, "_test_map_app.bin");
+        assert!(img.segment_containing(0x1008).is_none());
+        // A word read straddling the two files
+        assert_eq!(read_u32_contig(&img, 0x1002), Some(0x6655_4433));
+
+        let mut img = Image::new(Vec::new(), None).unwrap();
+        let err = load_maps(&mut img, &[spec(&a, "0x1000"), spec(&b, "0x1002")], Endian::Le).unwrap_err();
+        assert!(err.to_string().contains("overlaps segment _test_map_rom.bin"), "{err}");
+        let _ = std::fs::remove_file(&a);
+        let _ = std::fs::remove_file(&b);
+    }
+
+    #[test]
+    fn batch_analyze_writes_one_report_per_file() {
+        let root = std::env::current_dir().unwrap().join("_test_batch");
+        let (dir, out) = (root.join("in"), root.join("out"));
+        std::fs::create_dir_all(&dir).unwrap();
+        // MOV D0,#1 then an undecodable word (RR op2 0xFF); J +2 -> MOV D0,#1;
+        // MOV D0,#1 then half of a 32-bit MOV cut off by the end of the file
+        std::fs::write(dir.join("a.bin"), [0x82, 0x10, 0x0B, 0x00, 0xF0, 0x0F]).unwrap();
+        std::fs::write(dir.join("b.bin"), [0x3C, 0x01, 0x82, 0x00, 0x82, 0x10, 0x00, 0x00]).unwrap();
+        std::fs::write(dir.join("c.bin"), [0x82, 0x10, 0x3B, 0x00]).unwrap();
+        std::fs::write(dir.join("notes.txt"), "skip me").unwrap();
+        let lines = batch_analyze(&dir, "*.bin", &out, 100, |p| load_raw_bin(p, 0, 0, None, Endian::Le)).unwrap();
+        let reports: Vec<String> = std::fs::read_dir(&out).unwrap().map(|e| e.unwrap().file_name().into_string().unwrap()).collect();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("a.bin: functions=1 blocks=1 undecodable=1"), "{}", lines[0]);
+        assert!(lines[1].starts_with("b.bin: functions=1 blocks=2 undecodable="), "{}", lines[1]);
+        assert_eq!(lines[2], "c.bin: functions=1 blocks=1 undecodable=0");
+        let mut reports = reports;
+        reports.sort();
+        assert_eq!(reports, ["a.bin.json", "b.bin.json", "c.bin.json"]);
+        assert!(glob_match("fw_??.b*", "fw_01.bin") && !glob_match("*.bin", "a.elf"));
+        assert!(Cli::try_parse_from(["tricore-disasm", "batch-analyze", "fw"]).is_ok());
+    }
+
+    #[test]
+    fn entry_name_resolves_from_imported_labels() {
+        // 0x0: filler; 0x4: main: MOV D0,#1 (16-bit) + pad
+        let bytes = vec![0x00, 0x00, 0x00, 0x00, 0x82, 0x10, 0x00, 0x00];
+        let img = Image { segments: vec![Segment { name: "s".into(), base: 0, bytes, perms: "r-x", kind: "raw" }], entry: None };
+        let path = std::env::current_dir().unwrap().join("_test_labels.json");
+        let kv = vec![LabelKV { addr: 4, name: "main".into() }];
+        std::fs::write(&path, serde_json::to_string(&kv).unwrap()).unwrap();
+        let labels = import_labels(path.to_str().unwrap()).unwrap();
+        std::fs::write(&path, "not json").unwrap();
+        let bad = import_labels(path.to_str().unwrap()).unwrap_err().to_string();
+        let _ = std::fs::remove_file(&path);
+        assert!(bad.contains("parsing labels"), "{bad}");
+        assert!(import_labels(path.to_str().unwrap()).unwrap_err().to_string().contains("reading labels"));
+
+        let entry = resolve_label(&labels, "main").unwrap();
+        assert_eq!(entry, 4);
+        let report = build_report(&img, &[entry], 100);
+        assert_eq!(report.entries, vec![4]);
+        assert!(report.visited.contains(&4) && !report.visited.contains(&0));
+        let err = resolve_label(&labels, "reset_handler").unwrap_err().to_string();
+        assert!(err.contains("reset_handler"), "{err}");
+
+        let dup = HashMap::from([(8u32, "main".to_string()), (4, "main".to_string())]);
+        let err = resolve_label(&dup, "main").unwrap_err().to_string();
+        assert!(err.contains("ambiguous") && err.contains("0x00000004, 0x00000008"), "{err}");
+    }
+
     #[test]
     fn range_disasm_decodes_simple() {
         // Craft a 32-bit MOV.U D0,#2 instruction: c=0, imm16=2, op1=0xBB
         let raw32 = (0u32 << 28) | (2u32 << 12) | 0xBBu32;
         let bytes = raw32.to_le_bytes();
         let seg = Segment { name: "s".into(), base: 0, bytes: bytes.to_vec(), perms: "r-x", kind: "raw" };
-        let img = Image { segments: vec![seg] };
+        let img = Image { segments: vec![seg], entry: None };
         let dec = Tc16Decoder::new();
         let pc = 0u32;
         let raw32_rd = read_u32(&img, pc).unwrap();
@@ -377,31 +916,242 @@ mod tests {
     }
 }
 
-fn enrich_blocks_with_mnemonics(img: &Image, widths: &HashMap<u32,u8>, blocks: &Vec<Block>, show_bytes: bool) -> Vec<BlockOut> {
+/// Every valid decoding in [start, end), one per line. Alternative-stream
+/// instructions are marked `+`; addresses where several streams meet note
+/// how many.
+fn render_overlapping(img: &Image, start: u32, end: u32) -> String {
+    use std::fmt::Write as _;
+    let dec = Tc16Decoder::new();
+    let insns = overlapping_decodes(img, start, end);
+    let mut buf = String::new();
+    for i in &insns {
+        let Some(d) = fetch_insn(img, i.addr).and_then(|raw| dec.decode(raw)) else { continue };
+        let mark = if i.primary { ' ' } else { '+' };
+        let _ = write!(buf, "{:#010x}: {mark} {}", i.addr, fmt_decoded(&d));
+        if i.streams > 1 { let _ = write!(buf, "  ; {} streams", i.streams); }
+        buf.push('\n');
+    }
+    let alt = insns.iter().filter(|i| !i.primary).count();
+    let shared = insns.iter().filter(|i| i.streams > 1).count();
+    let _ = writeln!(buf, "; {} decodings, {alt} off the primary stream, {shared} reached by several streams", insns.len());
+    buf
+}
+
+/// Classic hexdump of [start, end): `width` bytes per row from `start`, then
+/// the printable ASCII (`.` otherwise) between bars. Bytes outside the
+/// range or any segment print as blanks so columns stay aligned.
+fn render_dump(img: &Image, start: u32, end: u32, width: usize) -> String {
+    use std::fmt::Write as _;
+    let mut buf = String::new();
+    let mut row = start as u64;
+    while row < end as u64 {
+        let (mut hex, mut ascii) = (String::new(), String::new());
+        for i in 0..width as u64 {
+            let a = row + i;
+            match (a < end as u64).then(|| read_u8(img, a as u32)).flatten() {
+                Some(b) => {
+                    let _ = write!(hex, "{b:02x} ");
+                    ascii.push(if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' });
+                }
+                None => { hex.push_str("   "); ascii.push(' '); }
+            }
+        }
+        let _ = writeln!(buf, "{row:#010x}  {hex} |{ascii}|");
+        row += width as u64;
+    }
+    buf
+}
+
+/// Render decoded instructions in [start, end) as a Rust array literal of
+/// `(raw_u32, expected_mnemonic)` pairs. 16-bit encodings keep only the low half-word.
+fn emit_fixture_rs(img: &Image, start: u32, end: u32) -> String {
+    use std::fmt::Write as _;
     let dec = Tc16Decoder::new();
+    let mut buf = String::new();
+    let _ = writeln!(buf, "// {start:#010x}..{end:#010x}");
+    let _ = writeln!(buf, "const FIXTURE: &[(u32, &str)] = &[");
+    let mut pc = start;
+    while pc < end {
+        let Some(raw32) = fetch_insn(img, pc) else { break };
+        let Some(d) = dec.decode(raw32) else { break };
+        let raw = if d.width == 2 { raw32 & 0xFFFF } else { raw32 };
+        let _ = writeln!(buf, "    ({raw:#010x}, {:?}),", fmt_decoded(&d));
+        pc = pc.wrapping_add(d.width as u32);
+    }
+    let _ = writeln!(buf, "];");
+    buf
+}
+
+/// Match a file name against a pattern with `*` (any run) and `?` (one char).
+fn glob_match(pat: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pat.chars().collect(), name.chars().collect());
+    let (mut pi, mut ni, mut star, mut mark) = (0, 0, None, 0);
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) { pi += 1; ni += 1; }
+        else if pi < p.len() && p[pi] == '*' { star = Some(pi); mark = ni; pi += 1; }
+        else if let Some(s) = star { pi = s + 1; mark += 1; ni = mark; }
+        else { return false; }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// Analyze each file in `dir` matching `pattern` from its default seeds,
+/// writing `<out_dir>/<file>.json` and returning one summary line per file.
+/// Files that fail to load are reported and skipped.
+fn batch_analyze(dir: &Path, pattern: &str, out_dir: &Path, max_instr: usize, load: impl Fn(&Path) -> Result<Image>) -> Result<Vec<String>> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.file_name().and_then(|n| n.to_str()).is_some_and(|n| glob_match(pattern, n)))
+        .collect();
+    files.sort();
+    std::fs::create_dir_all(out_dir)?;
+    let mut lines = Vec::new();
+    for path in files {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        let img = match load(&path) {
+            Ok(img) => img,
+            Err(e) => { lines.push(format!("{name}: load error: {e}")); continue; }
+        };
+        let seeds = find_seeds_with(&img, None, DEFAULT_VECTOR_SLOTS);
+        let report = build_report(&img, &seeds, max_instr);
+        // Words the walk read but could not decode; a read past the image end is not one
+        let dec = Tc16Decoder::new();
+        let undecodable = report.visited.iter()
+            .filter(|&&pc| !report.widths.contains_key(&pc) && fetch_insn(&img, pc).is_some_and(|raw| dec.decode(raw).is_none()))
+            .count();
+        std::fs::write(out_dir.join(format!("{name}.json")), serde_json::to_string_pretty(&report)?)?;
+        lines.push(format!("{name}: functions={} blocks={} undecodable={undecodable}", report.functions.len(), report.blocks.len()));
+    }
+    Ok(lines)
+}
+
+/// One-line summary of why `run` stopped.
+fn describe_exit(exit: &emu::RunExit, cpu: &Cpu, steps: usize) -> String {
+    match exit {
+        emu::RunExit::Ret { pc } => format!("returned at {pc:#010x} after {steps} steps"),
+        emu::RunExit::Break { pc } => format!("breakpoint at {pc:#010x} after {steps} steps"),
+        emu::RunExit::StepCap => format!("step cap reached ({steps} steps), pc={:#010x}", cpu.pc),
+        emu::RunExit::Unmapped { pc } => format!("fetch from unmapped address {pc:#010x} after {steps} steps"),
+        emu::RunExit::NoExec { pc, segment } => format!("fetch from non-executable segment '{segment}' at {pc:#010x} after {steps} steps"),
+        emu::RunExit::Trap(Trap::IllegalInstruction { pc, raw }) => format!("illegal instruction {raw:#x} at {pc:#010x} after {steps} steps"),
+        emu::RunExit::Trap(t) => format!("trap at {:#010x} after {steps} steps: {t}", cpu.pc),
+    }
+}
+
+/// Read a labels JSON file (Vec<{ addr, name }>).
+fn import_labels(path: &str) -> Result<HashMap<u32, String>> {
+    let txt = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("reading labels {path}: {e}"))?;
+    let kvs: Vec<LabelKV> = serde_json::from_str(&txt).map_err(|e| anyhow::anyhow!("parsing labels {path}: {e}"))?;
+    Ok(kvs.into_iter().map(|kv| (kv.addr, kv.name)).collect())
+}
+
+/// Look up the address of label `name`; a name on several addresses is an
+/// error rather than an arbitrary pick.
+fn resolve_label(labels: &HashMap<u32, String>, name: &str) -> Result<u32> {
+    let mut addrs: Vec<u32> = labels.iter().filter(|(_, n)| n.as_str() == name).map(|(&a, _)| a).collect();
+    addrs.sort_unstable();
+    match addrs[..] {
+        [] => anyhow::bail!("entry name '{name}' not found in imported labels"),
+        [a] => Ok(a),
+        _ => {
+            let list: Vec<String> = addrs.iter().map(|a| format!("{a:#010x}")).collect();
+            anyhow::bail!("entry name '{name}' is ambiguous: labels {} share it", list.join(", "))
+        }
+    }
+}
+
+/// Analyze from `entry` and list just the function starting there: a summary
+/// line, then its blocks as in `analyze --listing --by-block`.
+fn render_function(img: &Image, entry: u32, max_instr: usize, show_bytes: bool, style: MnemonicStyle) -> Result<String> {
+    let report = build_report(img, &[entry], max_instr);
+    let start = report.block_at(entry).map(|b| b.start).ok_or_else(|| anyhow::anyhow!("no code decoded at {entry:#010x}"))?;
+    let func = report.functions.iter().find(|f| f.entry == start).ok_or_else(|| anyhow::anyhow!("no function at {entry:#010x}"))?;
+    let labels: HashMap<u32, String> = report.labels.iter().map(|(&a, n)| (a, n.clone())).collect();
+    let blocks: Vec<Block> = report.blocks.iter().filter(|b| func.blocks.binary_search(&b.start).is_ok()).cloned().collect();
+    let edges: Vec<EdgeOut> = report.edges.iter().filter(|e| func.blocks.binary_search(&e.from).is_ok()).cloned().collect();
+    let mut txt = format!(
+        "; {}: [{:#010x}, {:#010x}) {} bytes in {} blocks, {} callees\n",
+        labels[&func.entry], func.start, func.end, func.size, func.num_blocks, func.callees.len()
+    );
+    txt.push_str(&render_by_block(img, &report.widths, &blocks, &edges, &labels, show_bytes, style));
+    Ok(txt)
+}
+
+/// Text listing grouped by basic block: a `label: [start, end)` header, the
+/// block's instructions, then one `-> target (kind)` footer per outgoing edge.
+fn render_by_block(img: &Image, widths: &HashMap<u32,u8>, blocks: &Vec<Block>, edges: &[EdgeOut], labels: &HashMap<u32, String>, show_bytes: bool, style: MnemonicStyle) -> String {
+    use std::fmt::Write as _;
+    let name = |a: u32| labels.get(&a).cloned().unwrap_or_else(|| format!("{a:#010x}"));
+    let mut buf = String::new();
+    for b in enrich_blocks_with_mnemonics(img, widths, blocks, show_bytes, Some(labels), style) {
+        let _ = writeln!(buf, "{}: [{:#010x}, {:#010x})", name(b.start), b.start, b.end);
+        for l in &b.insns { let _ = writeln!(buf, "  {l}"); }
+        for e in edges.iter().filter(|e| e.from == b.start) {
+            let _ = writeln!(buf, "  -> {} ({})", name(e.to), e.kind);
+        }
+        buf.push('\n');
+    }
+    buf
+}
+
+/// `blocks` and `edges` minus the blocks that belong only to `stubs`, so
+/// filtered-out functions do not show up as graph nodes.
+fn without_stub_blocks(blocks: &[Block], edges: &[EdgeOut], functions: &[FunctionOut], stubs: &[FunctionOut]) -> (Vec<Block>, Vec<EdgeOut>) {
+    let kept: HashSet<u32> = functions.iter().flat_map(|f| f.blocks.iter().copied()).collect();
+    let dropped: HashSet<u32> = stubs.iter().flat_map(|f| f.blocks.iter().copied()).filter(|b| !kept.contains(b)).collect();
+    let blocks = blocks.iter().filter(|b| !dropped.contains(&b.start)).cloned().collect();
+    let edges = edges.iter().filter(|e| !dropped.contains(&e.from) && !dropped.contains(&e.to)).cloned().collect();
+    (blocks, edges)
+}
+
+/// Graphviz digraph of the block-level CFG. Nodes are block starts labeled
+/// by name; fallthrough edges are plain, branches colored, calls dashed,
+/// returning blocks double-bordered.
+fn render_dot(blocks: &[Block], edges: &[EdgeOut], labels: &HashMap<u32, String>) -> String {
+    use std::fmt::Write as _;
+    let mut buf = String::from("digraph cfg {\n  node [shape=box, fontname=monospace];\n");
+    let returning: HashSet<u32> = edges.iter().filter(|e| e.kind == "ret").map(|e| e.from).collect();
+    for b in blocks {
+        let name = labels.get(&b.start).cloned().unwrap_or_else(|| format!("{:#010x}", b.start));
+        // Returning blocks are drawn with a double border instead of a self-edge
+        let ret = if returning.contains(&b.start) { ", peripheries=2" } else { "" };
+        let _ = writeln!(buf, "  n{:08x} [label=\"{}\"{ret}];", b.start, name.replace('"', "\\\""));
+    }
+    for e in edges.iter().filter(|e| e.kind != "ret") {
+        let style = match e.kind.as_str() {
+            "br" => " [color=blue]",
+            "cbr" => " [color=darkgreen]",
+            "call" => " [style=dashed, color=red]",
+            "ind" => " [style=dotted, color=purple]",
+            _ => "",
+        };
+        let _ = writeln!(buf, "  n{:08x} -> n{:08x}{style};", e.from, e.to);
+    }
+    buf.push_str("}\n");
+    buf
+}
+
+/// Decode each block's instructions; with `labels`, branch targets print as
+/// absolute names (`fmt_decoded_at`) instead of relative offsets.
+fn enrich_blocks_with_mnemonics(img: &Image, widths: &HashMap<u32,u8>, blocks: &Vec<Block>, show_bytes: bool, labels: Option<&HashMap<u32, String>>, style: MnemonicStyle) -> Vec<BlockOut> {
+    let text = |d: &tricore_rs::decoder::Decoded, pc: u32| match labels {
+        Some(l) => fmt_decoded_with_style(d, pc, style, |a| l.get(&a).cloned()),
+        None => fmt_decoded_styled(d, style),
+    };
     let mut out = Vec::with_capacity(blocks.len());
     for b in blocks {
         let mut lines = Vec::new();
-        let mut pc = b.start;
-        while pc < b.end {
-            if let Some(raw32) = read_u32(img, pc) {
-                if let Some(d) = dec.decode(raw32) {
-                    if show_bytes {
-                        let mut bs = Vec::new();
-                        for i in 0..(d.width as u32) { bs.push(read_u8(img, pc + i).unwrap_or(0)); }
-                        let mut s = format!("{pc:#010x}: ");
-                        for bb in bs { s.push_str(&format!("{:02x} ", bb)); }
-                        s.push_str("  ");
-                        s.push_str(&fmt_decoded(&d));
-                        lines.push(s);
-                    } else {
-                        lines.push(format!("{pc:#010x}: {}", fmt_decoded(&d)));
-                    }
-                    pc = pc.wrapping_add(d.width as u32);
-                    continue;
-                }
+        for (pc, d) in iter_insns(img, b.start, b.end) {
+            let Some(d) = d else { break };
+            if show_bytes {
+                let mut s = format!("{pc:#010x}: ");
+                for i in 0..(d.width as u32) { s.push_str(&format!("{:02x} ", read_u8(img, pc + i).unwrap_or(0))); }
+                s.push_str("  ");
+                s.push_str(&text(&d, pc));
+                lines.push(s);
+            } else {
+                lines.push(format!("{pc:#010x}: {}", text(&d, pc)));
             }
-            break;
         }
         out.push(BlockOut { start: b.start, end: b.end, insns: lines });
     }
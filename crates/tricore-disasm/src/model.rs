@@ -1,5 +1,9 @@
 use anyhow::Result;
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
+use tricore_rs::decoder::Decoder;
+use tricore_rs::isa::tc16::Tc16Decoder;
 
 #[derive(Debug, Clone)]
 pub struct Segment {
@@ -13,9 +17,101 @@ pub struct Segment {
 #[derive(Debug, Clone)]
 pub struct Image {
     pub segments: Vec<Segment>,
+    /// Entry point recorded by the container format (ELF `e_entry`)
+    pub entry: Option<u32>,
 }
 
-pub fn load_raw_bin(path: &Path, base: u32, skip: usize, len: Option<usize>) -> Result<Image> {
+impl Segment {
+    /// Exclusive end address; errors if `base + len` does not fit in 32 bits.
+    pub fn end(&self) -> Result<u32> {
+        u32::try_from(self.bytes.len())
+            .ok()
+            .and_then(|len| self.base.checked_add(len))
+            .ok_or_else(|| anyhow::anyhow!("segment {} at {:#010x} with {:#x} bytes overflows the 32-bit address space", self.name, self.base, self.bytes.len()))
+    }
+}
+
+impl Image {
+    /// Build an image after checking every segment's range fits in 32 bits.
+    pub fn new(segments: Vec<Segment>, entry: Option<u32>) -> Result<Image> {
+        for s in &segments { s.end()?; }
+        Ok(Image { segments, entry })
+    }
+
+    /// Like `new`, for segments whose size was declared separately (a
+    /// container header or manifest): each must hold exactly that many bytes.
+    pub fn with_declared_sizes(segments: Vec<(Segment, usize)>, entry: Option<u32>) -> Result<Image> {
+        for (s, declared) in &segments {
+            anyhow::ensure!(
+                s.bytes.len() == *declared,
+                "segment {} declares {declared:#x} bytes but holds {:#x}",
+                s.name,
+                s.bytes.len()
+            );
+        }
+        Image::new(segments.into_iter().map(|(s, _)| s).collect(), entry)
+    }
+
+    /// Add `other`'s segments, refusing any that overlap one already present.
+    /// The first known entry point is kept.
+    pub fn merge(&mut self, other: Image) -> Result<()> {
+        for s in other.segments {
+            let (lo, hi) = (s.base, s.end()?);
+            for e in &self.segments {
+                let (elo, ehi) = (e.base, e.end()?);
+                anyhow::ensure!(
+                    hi <= elo || ehi <= lo,
+                    "segment {} [{lo:#010x}, {hi:#010x}) overlaps segment {} [{elo:#010x}, {ehi:#010x})",
+                    s.name,
+                    e.name
+                );
+            }
+            self.segments.push(s);
+        }
+        self.entry = self.entry.or(other.entry);
+        Ok(())
+    }
+
+    /// The segment mapping `addr`, if any.
+    pub fn segment_containing(&self, addr: u32) -> Option<&Segment> {
+        self.segments.iter().find(|s| addr >= s.base && ((addr - s.base) as usize) < s.bytes.len())
+    }
+
+    /// `addr` as `segment+0xOFF`, for position-independent listings.
+    pub fn seg_relative(&self, addr: u32) -> Option<String> {
+        self.segment_containing(addr).map(|s| format!("{}+{:#x}", s.name, addr - s.base))
+    }
+
+    /// Listing address: segment-relative when asked and mapped, else absolute hex.
+    pub fn fmt_addr(&self, addr: u32, seg_relative: bool) -> String {
+        seg_relative.then(|| self.seg_relative(addr)).flatten().unwrap_or_else(|| format!("{addr:#010x}"))
+    }
+}
+
+/// Byte order of a raw dump. Images are normalized to little-endian at load
+/// time so readers and the decoder always see canonical TriCore words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Endian {
+    /// Little-endian, as the core stores it
+    #[default]
+    Le,
+    /// Big-endian 32-bit words (each word byte-reversed)
+    Be,
+    /// Bytes swapped within each 16-bit halfword
+    Swap,
+}
+
+impl Endian {
+    /// Rewrite `bytes` in place into little-endian order.
+    pub fn normalize(self, bytes: &mut [u8]) -> Result<()> {
+        let n = match self { Endian::Le => return Ok(()), Endian::Be => 4, Endian::Swap => 2 };
+        anyhow::ensure!(bytes.len() % n == 0, "payload length {} is not a multiple of {n} bytes for --endian {self:?}", bytes.len());
+        for w in bytes.chunks_exact_mut(n) { w.reverse(); }
+        Ok(())
+    }
+}
+
+pub fn load_raw_bin(path: &Path, base: u32, skip: usize, len: Option<usize>, endian: Endian) -> Result<Image> {
     let file = std::fs::read(path)?;
     anyhow::ensure!(skip <= file.len(), "--skip exceeds file size");
     let mut payload = &file[skip..];
@@ -23,8 +119,364 @@ pub fn load_raw_bin(path: &Path, base: u32, skip: usize, len: Option<usize>) ->
         anyhow::ensure!(lim <= payload.len(), "--len exceeds remaining file size after skip");
         payload = &payload[..lim];
     }
-    let seg = Segment { name: "segment0".into(), base, bytes: payload.to_vec(), perms: "r-x", kind: "raw" };
-    Ok(Image { segments: vec![seg] })
+    let mut bytes = payload.to_vec();
+    endian.normalize(&mut bytes)?;
+    let seg = Segment { name: "segment0".into(), base, bytes, perms: "r-x", kind: "raw" };
+    Image::new(vec![seg], None)
+}
+
+/// Largest PT_LOAD memory size we are willing to materialize.
+const MAX_ELF_SEGMENT: u32 = 64 << 20;
+
+/// True if `bytes` start with the ELF magic.
+pub fn is_elf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\x7fELF")
+}
+
+/// Load a 32-bit little-endian ELF, one segment per PT_LOAD program header.
+pub fn load_elf(path: &Path) -> Result<Image> {
+    parse_elf(&std::fs::read(path)?)
+}
+
+/// Parse PT_LOAD program headers of an ELF32 LE image. Segments get their
+/// `p_vaddr` as base, zero-fill up to `p_memsz`, and perms from `p_flags`.
+pub fn parse_elf(file: &[u8]) -> Result<Image> {
+    const PT_LOAD: u32 = 1;
+    const PF_X: u32 = 1;
+    const PF_W: u32 = 2;
+    const PF_R: u32 = 4;
+    let u16_at = |off: usize| file.get(off..off + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_at = |off: usize| file.get(off..off + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    anyhow::ensure!(is_elf(file), "not an ELF file");
+    anyhow::ensure!(file.len() >= 52, "truncated ELF header");
+    anyhow::ensure!(file[4] == 1, "only ELF32 is supported");
+    anyhow::ensure!(file[5] == 1, "only little-endian ELF is supported");
+    let trunc = || anyhow::anyhow!("truncated ELF header");
+    let entry = u32_at(24).ok_or_else(trunc)?;
+    let phoff = u32_at(28).ok_or_else(trunc)? as usize;
+    let phentsize = u16_at(42).ok_or_else(trunc)? as usize;
+    let phnum = u16_at(44).ok_or_else(trunc)? as usize;
+    anyhow::ensure!(phentsize >= 32, "bad program header size {phentsize}");
+
+    let mut segments = Vec::new();
+    for i in 0..phnum {
+        let ph = phoff + i * phentsize;
+        let field = |n: usize| u32_at(ph + n * 4).ok_or_else(|| anyhow::anyhow!("program header {i} out of bounds"));
+        if field(0)? != PT_LOAD { continue; }
+        let (offset, vaddr, filesz, memsz, flags) = (field(1)? as usize, field(2)?, field(4)? as usize, field(5)?, field(6)?);
+        anyhow::ensure!(memsz <= MAX_ELF_SEGMENT, "PT_LOAD {i} is {memsz:#x} bytes, too large");
+        anyhow::ensure!(filesz <= memsz as usize, "PT_LOAD {i} file size exceeds memory size");
+        let data = file.get(offset..offset + filesz).ok_or_else(|| anyhow::anyhow!("PT_LOAD {i} data out of bounds"))?;
+        let mut bytes = data.to_vec();
+        bytes.resize(memsz as usize, 0);
+        let perms = match (flags & PF_R != 0, flags & PF_W != 0, flags & PF_X != 0) {
+            (true, true, true) => "rwx",
+            (true, true, false) => "rw-",
+            (true, false, true) => "r-x",
+            (true, false, false) => "r--",
+            (false, true, true) => "-wx",
+            (false, true, false) => "-w-",
+            (false, false, true) => "--x",
+            (false, false, false) => "---",
+        };
+        let kind = if flags & PF_X != 0 { "code" } else { "data" };
+        segments.push((Segment { name: format!("load{}", segments.len()), base: vaddr, bytes, perms, kind }, memsz as usize));
+    }
+    anyhow::ensure!(!segments.is_empty(), "ELF has no PT_LOAD segments");
+    Image::with_declared_sizes(segments, Some(entry))
+}
+
+/// Load a Motorola S-record file (see `parse_srec`).
+pub fn load_srec(path: &Path) -> Result<Image> {
+    parse_srec(&std::fs::read_to_string(path)?)
+}
+
+/// Load an Intel HEX file (see `parse_ihex`).
+pub fn load_ihex(path: &Path) -> Result<Image> {
+    parse_ihex(&std::fs::read_to_string(path)?)
+}
+
+/// Hex digits of one record after its start character, as bytes.
+fn record_bytes(hex: &str, line: usize) -> Result<Vec<u8>> {
+    anyhow::ensure!(hex.len() % 2 == 0 && hex.is_ascii(), "line {line}: odd or non-ASCII hex record");
+    (0..hex.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow::anyhow!("line {line}: bad hex digits {:?}", &hex[i..i + 2])))
+        .collect()
+}
+
+/// One S-record's type character and bytes (count through checksum), with
+/// the byte count and checksum verified.
+fn srec_record(l: &str, i: usize) -> Result<(u8, Vec<u8>)> {
+    let (Some(b'S'), Some(&ty)) = (l.as_bytes().first(), l.as_bytes().get(1)) else {
+        anyhow::bail!("line {i}: not an S-record");
+    };
+    let b = record_bytes(&l[2..], i)?;
+    anyhow::ensure!(!b.is_empty() && b.len() == b[0] as usize + 1, "line {i}: byte count does not match the record length");
+    let sum = b.iter().fold(0u8, |a, &x| a.wrapping_add(x));
+    anyhow::ensure!(sum == 0xFF, "line {i}: checksum mismatch (record {:#04x}, expected {:#04x})", b[b.len() - 1], !sum.wrapping_sub(b[b.len() - 1]));
+    Ok((ty, b))
+}
+
+/// One Intel HEX record's bytes (count through checksum), with the byte
+/// count and checksum verified.
+fn ihex_record(l: &str, i: usize) -> Result<Vec<u8>> {
+    let hex = l.strip_prefix(':').ok_or_else(|| anyhow::anyhow!("line {i}: not an Intel HEX record"))?;
+    let b = record_bytes(hex, i)?;
+    anyhow::ensure!(b.len() >= 5 && b.len() == b[0] as usize + 5, "line {i}: byte count does not match the record length");
+    let sum = b.iter().fold(0u8, |a, &x| a.wrapping_add(x));
+    anyhow::ensure!(sum == 0, "line {i}: checksum mismatch (record {:#04x}, expected {:#04x})", b[b.len() - 1], b[b.len() - 1].wrapping_sub(sum));
+    Ok(b)
+}
+
+/// Whether `line` is one well-formed S-record, for format detection.
+pub fn is_srec_record(line: &str) -> bool {
+    matches!(srec_record(line.trim(), 1), Ok((b'0'..=b'9', _)))
+}
+
+/// Whether `line` is one well-formed Intel HEX record, for format detection.
+pub fn is_ihex_record(line: &str) -> bool {
+    matches!(ihex_record(line.trim(), 1), Ok(b) if b[3] <= 0x05)
+}
+
+/// Container of an input file. Serialized in lowercase; `raw` also reads
+/// as `bin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    #[serde(alias = "raw")]
+    Bin,
+    Elf,
+    Srec,
+    Ihex,
+}
+
+/// Bytes read to detect the input format: more than the longest S-record
+/// or Intel HEX line (255 data bytes), so a whole first line fits.
+pub const SNIFF_LEN: u64 = 600;
+
+/// Guess the container from the start of a file: ELF by magic, S-record or
+/// Intel HEX only when the whole first line is a well-formed record (raw
+/// code may well start with `:` or `S<digit>`), else raw.
+pub fn sniff_format(head: &[u8], whole_file: bool) -> InputFormat {
+    if is_elf(head) { return InputFormat::Elf; }
+    let first_line = match head.iter().position(|&b| b == b'\n') {
+        Some(n) => &head[..n],
+        None if whole_file => head,
+        None => return InputFormat::Bin,
+    };
+    let Ok(line) = std::str::from_utf8(first_line) else { return InputFormat::Bin };
+    if is_srec_record(line) { InputFormat::Srec } else if is_ihex_record(line) { InputFormat::Ihex } else { InputFormat::Bin }
+}
+
+/// `sniff_format` over the first `SNIFF_LEN` bytes of `path`.
+pub fn detect_format(path: &Path) -> Result<InputFormat> {
+    let mut head = Vec::new();
+    std::fs::File::open(path)?.take(SNIFF_LEN).read_to_end(&mut head)?;
+    Ok(sniff_format(&head, (head.len() as u64) < SNIFF_LEN))
+}
+
+/// Load `path` as `format`, or as `sniff_format` detects it. `base`, `skip`,
+/// `len` and `endian` only describe raw dumps; a container carries its own
+/// layout, so setting any of them for one is an error rather than ignored.
+pub fn load_input(path: &Path, format: Option<InputFormat>, base: u32, skip: usize, len: Option<usize>, endian: Endian) -> Result<Image> {
+    let format = match format {
+        Some(f) => f,
+        None => detect_format(path)?,
+    };
+    if format != InputFormat::Bin {
+        let set: Vec<&str> = [("--base", base != 0), ("--skip", skip != 0), ("--len", len.is_some()), ("--endian", endian != Endian::Le)]
+            .into_iter().filter(|&(_, on)| on).map(|(flag, _)| flag).collect();
+        if !set.is_empty() {
+            anyhow::bail!("{}: only meaningful for raw bin input, not {format:?}", set.join(", "));
+        }
+    }
+    match format {
+        InputFormat::Bin => load_raw_bin(path, base, skip, len, endian),
+        InputFormat::Elf => load_elf(path),
+        InputFormat::Srec => load_srec(path),
+        InputFormat::Ihex => load_ihex(path),
+    }
+}
+
+/// Data records as segments: sorted by address, with records that continue
+/// one another joined. Overlapping records are an error.
+fn records_to_image(mut records: Vec<(u32, Vec<u8>)>, entry: Option<u32>, kind: &'static str) -> Result<Image> {
+    records.sort_by_key(|(addr, _)| *addr);
+    let mut segments: Vec<Segment> = Vec::new();
+    for (addr, data) in records.into_iter().filter(|(_, d)| !d.is_empty()) {
+        if let Some(last) = segments.last_mut() {
+            let end = last.base as u64 + last.bytes.len() as u64;
+            anyhow::ensure!(addr as u64 >= end, "{kind} record at {addr:#010x} overlaps data up to {end:#010x}");
+            if addr as u64 == end { last.bytes.extend(data); continue; }
+        }
+        segments.push(Segment { name: format!("seg{}", segments.len()), base: addr, bytes: data, perms: "r-x", kind });
+    }
+    anyhow::ensure!(!segments.is_empty(), "{kind} file has no data records");
+    Image::new(segments, entry)
+}
+
+/// Parse S-records: S1/S2/S3 data with 16/24/32-bit addresses, S7/S8/S9
+/// as the entry point; S0 headers and S5/S6 counts are skipped. Every
+/// record's checksum is verified.
+pub fn parse_srec(text: &str) -> Result<Image> {
+    let (mut records, mut entry) = (Vec::new(), None);
+    for (i, l) in text.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+        if l.is_empty() { continue; }
+        let (ty, b) = srec_record(l, i)?;
+        let addr_len = match ty {
+            b'0' | b'1' | b'5' | b'9' => 2,
+            b'2' | b'6' | b'8' => 3,
+            b'3' | b'7' => 4,
+            _ => anyhow::bail!("line {i}: unknown record type S{}", ty as char),
+        };
+        let body = &b[1..b.len() - 1];
+        anyhow::ensure!(body.len() >= addr_len, "line {i}: record too short for its address");
+        let addr = body[..addr_len].iter().fold(0u32, |a, &x| (a << 8) | x as u32);
+        match ty {
+            b'1' | b'2' | b'3' => records.push((addr, body[addr_len..].to_vec())),
+            b'7' | b'8' | b'9' => entry = Some(addr),
+            _ => {}
+        }
+    }
+    records_to_image(records, entry, "srec")
+}
+
+/// Parse Intel HEX: type 00 data, 01 end of file, 02/04 extended segment
+/// and linear addresses for targets past 64 KiB, 03/05 start address as the
+/// entry point. Every record's checksum is verified.
+pub fn parse_ihex(text: &str) -> Result<Image> {
+    let (mut records, mut entry, mut upper) = (Vec::new(), None, 0u32);
+    for (i, l) in text.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+        if l.is_empty() { continue; }
+        let b = ihex_record(l, i)?;
+        let offset = u16::from_be_bytes([b[1], b[2]]) as u32;
+        let data = &b[4..b.len() - 1];
+        let be = |n: usize| -> Result<u32> {
+            anyhow::ensure!(data.len() == n, "line {i}: record type {:02x} needs {n} data bytes", b[3]);
+            Ok(data.iter().fold(0u32, |a, &x| (a << 8) | x as u32))
+        };
+        match b[3] {
+            0x00 => records.push((upper.wrapping_add(offset), data.to_vec())),
+            0x01 => break,
+            0x02 => upper = be(2)? << 4,
+            0x03 => { let cs_ip = be(4)?; entry = Some(((cs_ip >> 16) << 4) + (cs_ip & 0xFFFF)); }
+            0x04 => upper = be(2)? << 16,
+            0x05 => entry = Some(be(4)?),
+            t => anyhow::bail!("line {i}: unknown record type {t:02x}"),
+        }
+    }
+    records_to_image(records, entry, "ihex")
+}
+
+/// Write `img` to `path` as S-records (see `write_srec`).
+pub fn save_srec(img: &Image, path: &Path, record_len: usize) -> Result<()> {
+    Ok(std::fs::write(path, write_srec(img, record_len)?)?)
+}
+
+/// Write `img` to `path` as Intel HEX (see `write_ihex`).
+pub fn save_ihex(img: &Image, path: &Path, record_len: usize) -> Result<()> {
+    Ok(std::fs::write(path, write_ihex(img, record_len)?)?)
+}
+
+/// One record line: `start` then the bytes in upper-case hex.
+fn record_line(start: &str, bytes: &[u8]) -> String {
+    let mut line = String::from(start);
+    for b in bytes { line.push_str(&format!("{b:02X}")); }
+    line.push('\n');
+    line
+}
+
+/// Serialize `img` as S-records: an S0 header, S3 data records of up to
+/// `record_len` bytes with 32-bit addresses, and an S7 with the entry point
+/// (0 when there is none). Empty segments emit nothing.
+pub fn write_srec(img: &Image, record_len: usize) -> Result<String> {
+    anyhow::ensure!((1..=250).contains(&record_len), "S3 records hold 1 to 250 data bytes, not {record_len}");
+    let srec = |ty: char, addr: &[u8], data: &[u8]| {
+        let mut b = vec![(addr.len() + data.len() + 1) as u8];
+        b.extend_from_slice(addr);
+        b.extend_from_slice(data);
+        b.push(!b.iter().fold(0u8, |a, &x| a.wrapping_add(x)));
+        record_line(&format!("S{ty}"), &b)
+    };
+    let mut out = srec('0', &[0, 0], b"tricore");
+    for s in &img.segments {
+        for (i, chunk) in s.bytes.chunks(record_len).enumerate() {
+            out.push_str(&srec('3', &(s.base + (i * record_len) as u32).to_be_bytes(), chunk));
+        }
+    }
+    out.push_str(&srec('7', &img.entry.unwrap_or(0).to_be_bytes(), &[]));
+    Ok(out)
+}
+
+/// Serialize `img` as Intel HEX: type 04 extended linear address records
+/// whenever the upper 16 address bits change, type 00 data records of up to
+/// `record_len` bytes that never cross a 64 KiB page, a type 05 start
+/// address when the image has an entry, and the type 01 end record.
+pub fn write_ihex(img: &Image, record_len: usize) -> Result<String> {
+    anyhow::ensure!((1..=255).contains(&record_len), "Intel HEX records hold 1 to 255 data bytes, not {record_len}");
+    let ihex = |ty: u8, offset: u16, data: &[u8]| {
+        let mut b = vec![data.len() as u8];
+        b.extend_from_slice(&offset.to_be_bytes());
+        b.push(ty);
+        b.extend_from_slice(data);
+        b.push(b.iter().fold(0u8, |a, &x| a.wrapping_sub(x)));
+        record_line(":", &b)
+    };
+    let (mut out, mut upper) = (String::new(), None);
+    for s in &img.segments {
+        let mut pos = 0usize;
+        while pos < s.bytes.len() {
+            let addr = s.base + pos as u32;
+            if upper != Some(addr >> 16) {
+                upper = Some(addr >> 16);
+                out.push_str(&ihex(0x04, 0, &((addr >> 16) as u16).to_be_bytes()));
+            }
+            let to_page_end = 0x1_0000 - (addr & 0xFFFF) as usize;
+            let n = record_len.min(to_page_end).min(s.bytes.len() - pos);
+            out.push_str(&ihex(0x00, addr as u16, &s.bytes[pos..pos + n]));
+            pos += n;
+        }
+    }
+    if let Some(entry) = img.entry { out.push_str(&ihex(0x05, 0, &entry.to_be_bytes())); }
+    out.push_str(&ihex(0x01, 0, &[]));
+    Ok(out)
+}
+
+/// Write `img` to `path` as an ELF32 LE executable (see `write_elf`).
+pub fn save_elf(img: &Image, path: &Path) -> Result<()> {
+    Ok(std::fs::write(path, write_elf(img))?)
+}
+
+/// Serialize `img` as an ELF32 LE `ET_EXEC` for TriCore: one PT_LOAD per
+/// segment, in order, with its base as `p_vaddr`/`p_paddr`, its bytes as both
+/// file and memory size and `p_flags` from its perms, plus the entry point.
+/// There are no section headers; `parse_elf` reads the result back segment
+/// for segment (names become `loadN`).
+pub fn write_elf(img: &Image) -> Vec<u8> {
+    const EHSIZE: usize = 52;
+    const PHENTSIZE: usize = 32;
+    let mut f = vec![0u8; EHSIZE];
+    f[..7].copy_from_slice(b"\x7fELF\x01\x01\x01"); // ELF32, LE, version 1
+    f[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    f[18..20].copy_from_slice(&44u16.to_le_bytes()); // EM_TRICORE
+    f[20..24].copy_from_slice(&1u32.to_le_bytes());
+    f[24..28].copy_from_slice(&img.entry.unwrap_or(0).to_le_bytes());
+    f[28..32].copy_from_slice(&(EHSIZE as u32).to_le_bytes()); // e_phoff
+    f[40..42].copy_from_slice(&(EHSIZE as u16).to_le_bytes());
+    f[42..44].copy_from_slice(&(PHENTSIZE as u16).to_le_bytes());
+    f[44..46].copy_from_slice(&(img.segments.len() as u16).to_le_bytes());
+    let mut offset = EHSIZE + img.segments.len() * PHENTSIZE;
+    for s in &img.segments {
+        let size = s.bytes.len() as u32;
+        let flags = [(b'r', 4), (b'w', 2), (b'x', 1)].iter()
+            .filter(|(c, _)| s.perms.as_bytes().contains(c))
+            .fold(0u32, |acc, (_, bit)| acc | bit);
+        for w in [1, offset as u32, s.base, s.base, size, size, flags, 1] { f.extend_from_slice(&w.to_le_bytes()); }
+        offset += s.bytes.len();
+    }
+    for s in &img.segments { f.extend_from_slice(&s.bytes); }
+    f
 }
 
 pub fn read_u8(img: &Image, addr: u32) -> Option<u8> {
@@ -53,6 +505,60 @@ pub fn read_u32(img: &Image, addr: u32) -> Option<u32> {
     Some(u32::from_le_bytes([b0, b1, b2, b3]))
 }
 
+/// Fetch a little-endian word that may straddle adjacent segments (e.g. code
+/// laid across back-to-back ELF sections). Each chunk is copied from the
+/// segment holding it; any unmapped byte in the window yields `None`.
+pub fn read_u32_contig(img: &Image, addr: u32) -> Option<u32> {
+    let mut buf = [0u8; 4];
+    let mut got = 0usize;
+    while got < 4 {
+        let at = addr.wrapping_add(got as u32);
+        let s = img.segments.iter().find(|s| at >= s.base && ((at - s.base) as usize) < s.bytes.len())?;
+        let off = (at - s.base) as usize;
+        let n = (4 - got).min(s.bytes.len() - off);
+        buf[got..got + n].copy_from_slice(&s.bytes[off..off + n]);
+        got += n;
+    }
+    Some(u32::from_le_bytes(buf))
+}
+
+/// The instruction word at `addr`, reading only the bytes it occupies: a
+/// 16-bit encoding (op1 bit 0 clear) comes back as its half-word with the
+/// high half zero, so one in the last two bytes of a segment still decodes;
+/// a 32-bit one is read whole, possibly across adjacent segments.
+pub fn fetch_insn(img: &Image, addr: u32) -> Option<u32> {
+    let half = read_u16(img, addr)?;
+    if Tc16Decoder::new().width_hint(half as u8) == 2 { Some(u32::from(half)) } else { read_u32_contig(img, addr) }
+}
+
+/// Checksum for `checksum`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ChecksumAlgo {
+    /// Wrapping 32-bit sum of the bytes
+    #[default]
+    Sum32,
+    /// CRC-32 (IEEE 802.3, as zlib and `crc32` compute it)
+    Crc32,
+}
+
+/// Checksum [start, end) byte by byte. An unmapped byte is an error unless
+/// `zero_fill`, which counts it as 0x00.
+pub fn checksum(img: &Image, start: u32, end: u32, algo: ChecksumAlgo, zero_fill: bool) -> Result<u32> {
+    let mut acc: u32 = match algo { ChecksumAlgo::Sum32 => 0, ChecksumAlgo::Crc32 => !0 };
+    for addr in start..end {
+        let b = match read_u8(img, addr) {
+            Some(b) => b,
+            None if zero_fill => 0,
+            None => anyhow::bail!("{addr:#010x} is not mapped (use zero fill to count holes as 0x00)"),
+        };
+        acc = match algo {
+            ChecksumAlgo::Sum32 => acc.wrapping_add(b as u32),
+            ChecksumAlgo::Crc32 => (0..8).fold(acc ^ b as u32, |c, _| if c & 1 != 0 { (c >> 1) ^ 0xEDB8_8320 } else { c >> 1 }),
+        };
+    }
+    Ok(match algo { ChecksumAlgo::Sum32 => acc, ChecksumAlgo::Crc32 => !acc })
+}
+
 pub fn is_mapped(img: &Image, addr: u32) -> bool {
     img.segments.iter().any(|s| {
         let start = s.base;
@@ -61,23 +567,306 @@ pub fn is_mapped(img: &Image, addr: u32) -> bool {
     })
 }
 
+/// Parse a text symbol map: one `ADDR name` per line, or `nm` output
+/// (`ADDR T name`). Addresses are hex with or without `0x`; blank lines and
+/// `#`/`;` comments are ignored. A repeated address keeps the last name.
+/// Returns the labels and the number of malformed lines skipped.
+pub fn parse_symbol_map(text: &str) -> (HashMap<u32, String>, usize) {
+    let mut labels = HashMap::new();
+    let mut skipped = 0;
+    for line in text.lines() {
+        let line = line.split(['#', ';']).next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (addr, name) = match fields[..] {
+            [addr, name] => (addr, name),
+            [addr, kind, name] if kind.len() == 1 => (addr, name),
+            _ => { skipped += 1; continue; }
+        };
+        let hex = addr.strip_prefix("0x").or_else(|| addr.strip_prefix("0X")).unwrap_or(addr);
+        match u32::from_str_radix(hex, 16) {
+            Ok(a) => { labels.insert(a, name.to_string()); }
+            Err(_) => skipped += 1,
+        }
+    }
+    (labels, skipped)
+}
+
+/// Read and parse a symbol map file (see `parse_symbol_map`).
+pub fn load_symbol_map(path: &Path) -> Result<(HashMap<u32, String>, usize)> {
+    Ok(parse_symbol_map(&std::fs::read_to_string(path)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn segment_ranges_must_fit_and_match_declared_size() {
+        let seg = |base: u32, len: usize| Segment { name: "s".into(), base, bytes: vec![0; len], perms: "r-x", kind: "raw" };
+        assert!(seg(0xFFFF_FFF0, 0x10).end().is_err(), "an exclusive end of 4 GiB is not representable");
+        let err = Image::new(vec![seg(0xFFFF_FFF0, 0x20)], None).unwrap_err();
+        assert!(err.to_string().contains("overflows the 32-bit address space"), "{err}");
+        assert_eq!(seg(0xFFFF_FFF0, 0x0F).end().unwrap(), 0xFFFF_FFFF);
+
+        let err = Image::with_declared_sizes(vec![(seg(0x1000, 0x10), 0x20)], None).unwrap_err();
+        assert!(err.to_string().contains("declares 0x20 bytes but holds 0x10"), "{err}");
+        assert!(Image::with_declared_sizes(vec![(seg(0x1000, 0x10), 0x10)], None).is_ok());
+    }
+
+    #[test]
+    fn checksum_sum32_and_crc32_match_known_values_and_holes_need_zero_fill() {
+        let seg = |base: u32, bytes: &[u8]| Segment { name: "s".into(), base, bytes: bytes.to_vec(), perms: "r--", kind: "raw" };
+        let img = Image::new(vec![seg(0x100, b"123456789")], None).unwrap();
+        assert_eq!(checksum(&img, 0x100, 0x109, ChecksumAlgo::Sum32, false).unwrap(), 0x1DD);
+        assert_eq!(checksum(&img, 0x100, 0x109, ChecksumAlgo::Crc32, false).unwrap(), 0xCBF4_3926);
+        assert_eq!(checksum(&img, 0x100, 0x100, ChecksumAlgo::Crc32, false).unwrap(), 0);
+
+        // "1234" and "6789" with a one-byte hole where the '5' was
+        let img = Image::new(vec![seg(0x100, b"1234"), seg(0x105, b"6789")], None).unwrap();
+        let err = checksum(&img, 0x100, 0x109, ChecksumAlgo::Sum32, false).unwrap_err();
+        assert!(err.to_string().contains("0x00000104 is not mapped"), "{err}");
+        assert_eq!(checksum(&img, 0x100, 0x109, ChecksumAlgo::Sum32, true).unwrap(), 0x1DD - 0x35);
+        let zeroed = Image::new(vec![seg(0x100, b"1234\x006789")], None).unwrap();
+        assert_eq!(checksum(&img, 0x100, 0x109, ChecksumAlgo::Crc32, true).unwrap(),
+                   checksum(&zeroed, 0x100, 0x109, ChecksumAlgo::Crc32, false).unwrap());
+    }
+
+    #[test]
+    fn symbol_map_parses_addr_name_lines_and_skips_comments() {
+        let text = "# exported by the linker\n0x80000000 _start\n80000100 T main\n";
+        let (labels, skipped) = parse_symbol_map(text);
+        assert_eq!(skipped, 0);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[&0x8000_0000], "_start");
+        assert_eq!(labels[&0x8000_0100], "main");
+
+        let (labels, skipped) = parse_symbol_map("0x10 a\nnot-an-address b\n0x10 b\njunk\n");
+        assert_eq!(labels[&0x10], "b", "last definition wins");
+        assert_eq!(skipped, 2);
+    }
+
     #[test]
     fn loader_maps_skip_and_len() {
         let cwd = std::env::current_dir().unwrap();
         let path = cwd.join("_test_bin.bin");
         std::fs::write(&path, [0u8, 1, 2, 3, 4, 5]).unwrap();
-        let img = load_raw_bin(&path, 0x1000_0000, 2, Some(3)).unwrap();
+        let img = load_raw_bin(&path, 0x1000_0000, 2, Some(3), Endian::Le).unwrap();
         assert_eq!(img.segments.len(), 1);
         let s = &img.segments[0];
         assert_eq!(s.base, 0x1000_0000);
         assert_eq!(s.bytes, vec![2, 3, 4]);
-        assert_eq!(read_u32(&img, 0x1000_0000).unwrap(), 0x00040302);
-        assert!(read_u32(&img, 0x1000_0002 + 2).is_none());
+        assert_eq!(read_u16(&img, 0x1000_0000).unwrap(), 0x0302);
+        assert_eq!(read_u8(&img, 0x1000_0002).unwrap(), 4);
+        assert!(read_u32(&img, 0x1000_0000).is_none());
+        assert!(read_u8(&img, 0x1000_0002 + 1).is_none());
         let _ = std::fs::remove_file(&path);
     }
-}
 
+    #[test]
+    fn endian_option_normalizes_words_before_decode() {
+        use tricore_rs::decoder::Decoder;
+        use tricore_rs::disasm::fmt_decoded;
+        use tricore_rs::isa::tc16::Tc16Decoder;
+        // MOV.U D3,#0x1234 stored big-endian
+        let word = (3u32 << 28) | (0x1234u32 << 12) | 0xBB;
+        let path = std::env::current_dir().unwrap().join("_test_be.bin");
+        std::fs::write(&path, word.to_be_bytes()).unwrap();
+        let le = load_raw_bin(&path, 0, 0, None, Endian::Le).unwrap();
+        let be = load_raw_bin(&path, 0, 0, None, Endian::Be).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let dec = Tc16Decoder::new();
+        assert_eq!(read_u32(&be, 0), Some(word));
+        assert_eq!(fmt_decoded(&dec.decode(read_u32(&be, 0).unwrap()).unwrap()), "mov d3, #0x1234");
+        let as_le = read_u32(&le, 0).unwrap();
+        assert_ne!(as_le, word);
+        assert_ne!(dec.decode(as_le).map(|d| fmt_decoded(&d)).as_deref(), Some("mov d3, #0x1234"));
+
+        let mut half = word.to_le_bytes();
+        Endian::Swap.normalize(&mut half).unwrap();
+        Endian::Swap.normalize(&mut half).unwrap();
+        assert_eq!(u32::from_le_bytes(half), word);
+        assert!(Endian::Be.normalize(&mut [0u8; 3]).is_err());
+    }
+
+    /// Minimal ELF32 LE with a code PT_LOAD, a data PT_LOAD (with .bss tail)
+    /// and a PT_NOTE that must be ignored.
+    fn fixture_elf() -> Vec<u8> {
+        let mut f = vec![0u8; 52];
+        f[..7].copy_from_slice(b"\x7fELF\x01\x01\x01");
+        f[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        f[18..20].copy_from_slice(&44u16.to_le_bytes()); // EM_TRICORE
+        f[24..28].copy_from_slice(&0x8000_0004u32.to_le_bytes()); // e_entry
+        f[28..32].copy_from_slice(&52u32.to_le_bytes()); // e_phoff
+        f[42..44].copy_from_slice(&32u16.to_le_bytes());
+        f[44..46].copy_from_slice(&3u16.to_le_bytes());
+        let code_off = 52 + 3 * 32;
+        let phdr = |ty: u32, off: u32, vaddr: u32, filesz: u32, memsz: u32, flags: u32| {
+            [ty, off, vaddr, vaddr, filesz, memsz, flags, 4].iter().flat_map(|w| w.to_le_bytes()).collect::<Vec<u8>>()
+        };
+        f.extend(phdr(1, code_off, 0x8000_0000, 8, 8, 5));
+        f.extend(phdr(4, 0, 0, 0, 0, 4));
+        f.extend(phdr(1, code_off + 8, 0xD000_0000, 4, 16, 6));
+        f.extend([0x00, 0x00, 0x00, 0x00, 0x82, 0x10, 0x00, 0x00]); // filler; entry: MOV D0,#1; pad
+        f.extend([0xAA, 0xBB, 0xCC, 0xDD]);
+        f
+    }
+
+    #[test]
+    fn elf_pt_loads_become_segments_with_entry() {
+        let img = parse_elf(&fixture_elf()).unwrap();
+        assert_eq!(img.entry, Some(0x8000_0004));
+        assert_eq!(img.segments.len(), 2);
+        let (code, data) = (&img.segments[0], &img.segments[1]);
+        assert_eq!((code.base, code.perms, code.kind, code.bytes.len()), (0x8000_0000, "r-x", "code", 8));
+        assert_eq!((data.base, data.perms, data.kind, data.bytes.len()), (0xD000_0000, "rw-", "data", 16));
+        assert_eq!(read_u32(&img, 0xD000_0000), Some(0xDDCC_BBAA));
+        assert_eq!(read_u32(&img, 0xD000_000C), Some(0), "bss tail is zero-filled");
+        assert!(parse_elf(b"\x7fELF").is_err());
+        assert!(!is_elf(&[0x82, 0x10]));
+    }
+
+    #[test]
+    fn patched_elf_saves_and_reloads_with_the_same_layout() {
+        let mut img = parse_elf(&fixture_elf()).unwrap();
+        img.segments[0].bytes[4] = 0x92; // MOV D0,#1 -> ADD D0,#1
+        let path = std::env::temp_dir().join(format!("tricore_disasm_save_{}.elf", std::process::id()));
+        save_elf(&img, &path).unwrap();
+        let back = load_elf(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(back.entry, Some(0x8000_0004));
+        assert_eq!(read_u8(&back, 0x8000_0004), Some(0x92), "the edit persisted");
+        let layout = |img: &Image| img.segments.iter().map(|s| (s.base, s.perms, s.kind, s.bytes.clone())).collect::<Vec<_>>();
+        assert_eq!(layout(&back), layout(&img));
+        // Saving the reloaded image is byte-for-byte stable
+        assert_eq!(write_elf(&back), write_elf(&img));
+    }
+
+    #[test]
+    fn srec_records_join_into_segments_at_their_addresses() {
+        let text = "S0050000746323\n\
+                    S309800000008210009054\n\
+                    S309800000040D009000D5\n\
+                    S1050100AABB94\n\
+                    S705800000007A\n";
+        let img = parse_srec(text).unwrap();
+        assert_eq!(img.entry, Some(0x8000_0000));
+        let layout: Vec<(u32, Vec<u8>)> = img.segments.iter().map(|s| (s.base, s.bytes.clone())).collect();
+        assert_eq!(layout, [(0x100, vec![0xAA, 0xBB]), (0x8000_0000, vec![0x82, 0x10, 0x00, 0x90, 0x0D, 0x00, 0x90, 0x00])]);
+        assert_eq!(img.segments[1].kind, "srec");
+
+        let err = parse_srec("S1050100AABB95\n").unwrap_err();
+        assert!(err.to_string().contains("line 1: checksum mismatch (record 0x95, expected 0x94)"), "{err}");
+    }
+
+    #[test]
+    fn ihex_extended_addresses_reach_past_64k() {
+        let text = ":0200000480007A\n\
+                    :02FFFE0082106F\n\
+                    :02000004800179\n\
+                    :040000000D0090005F\n\
+                    :020000021000EC\n\
+                    :020010001122BB\n\
+                    :040000058000FFFE7A\n\
+                    :00000001FF\n";
+        let img = parse_ihex(text).unwrap();
+        assert_eq!(img.entry, Some(0x8000_FFFE));
+        let layout: Vec<(u32, Vec<u8>)> = img.segments.iter().map(|s| (s.base, s.bytes.clone())).collect();
+        // The record at 0x8000FFFE runs on into the next 64 KiB page
+        assert_eq!(layout, [(0x1_0010, vec![0x11, 0x22]), (0x8000_FFFE, vec![0x82, 0x10, 0x0D, 0x00, 0x90, 0x00])]);
+        assert_eq!(read_u32(&img, 0x8001_0000), Some(0x0090_000D));
+
+        let err = parse_ihex(":020010001122BC\n").unwrap_err();
+        assert!(err.to_string().contains("line 1: checksum mismatch"), "{err}");
+        assert!(parse_ihex(":0400000011220000\n").is_err(), "short record");
+    }
+
+    #[test]
+    fn srec_and_ihex_export_round_trips_bases_and_bytes() {
+        let seg = |base: u32, bytes: Vec<u8>| Segment { name: "s".into(), base, bytes, perms: "r-x", kind: "raw" };
+        let img = Image::new(vec![
+            seg(0x100, vec![1, 2, 3]),
+            seg(0x200, Vec::new()),
+            // Crosses from page 0x8000 into 0x8001
+            seg(0x8000_FFF0, (0..40).collect()),
+        ], Some(0x8000_FFF0)).unwrap();
+        let layout = |img: &Image| img.segments.iter().map(|s| (s.base, s.bytes.clone())).collect::<Vec<_>>();
+        let want = vec![(0x100, vec![1, 2, 3]), (0x8000_FFF0, (0..40).collect())];
+
+        let text = write_srec(&img, 16).unwrap();
+        assert!(text.lines().all(|l| l.len() <= 2 + 2 * (1 + 4 + 16 + 1)), "{text}");
+        let back = parse_srec(&text).unwrap();
+        assert_eq!((layout(&back), back.entry), (want.clone(), Some(0x8000_FFF0)));
+
+        let text = write_ihex(&img, 32).unwrap();
+        // The 0x8000FFF0 segment splits at the page boundary under a new 04 record
+        assert!(text.contains(":0200000480007A\n:10FFF000"), "{text}");
+        assert!(text.contains(":02000004800179\n:18000000"), "{text}");
+        assert!(text.ends_with(":040000058000FFF088\n:00000001FF\n"), "{text}");
+        let back = parse_ihex(&text).unwrap();
+        assert_eq!((layout(&back), back.entry), (want, Some(0x8000_FFF0)));
+
+        assert!(write_ihex(&img, 0).is_err());
+        assert!(write_srec(&img, 251).is_err());
+    }
+
+    #[test]
+    fn contig_read_spans_adjacent_segments_only() {
+        let a = Segment { name: "a".into(), base: 0x100, bytes: vec![0x11, 0x22, 0x33], perms: "r-x", kind: "raw" };
+        let b = Segment { name: "b".into(), base: 0x103, bytes: vec![0x44, 0x55], perms: "r-x", kind: "raw" };
+        let img = Image { segments: vec![b.clone(), a.clone()], entry: None };
+        assert_eq!(read_u32_contig(&img, 0x100), Some(0x4433_2211));
+        assert_eq!(read_u32_contig(&img, 0x101), Some(0x5544_3322));
+        assert_eq!(read_u32_contig(&img, 0x102), None);
+        let gap = Image { segments: vec![a, Segment { base: 0x104, ..b }], entry: None };
+        assert_eq!(read_u32_contig(&gap, 0x100), None);
+    }
+
+    #[test]
+    fn segment_relative_rendering_matches_absolute_target() {
+        use tricore_rs::decoder::Decoder;
+        use tricore_rs::disasm::{fmt_decoded_at, fmt_decoded_with};
+        // j +6 at flash+0x40 (16-bit J disp8 = 3, relative to the next insn)
+        let mut bytes = vec![0; 0x80];
+        bytes[0x40..0x42].copy_from_slice(&[0x3C, 0x03]);
+        let img = Image::new(vec![Segment { name: "flash".into(), base: 0x8000_0000, bytes, perms: "r-x", kind: "raw" }], None).unwrap();
+        let pc = 0x8000_0040;
+        let d = tricore_rs::isa::tc16::Tc16Decoder::new().decode(0x033C).unwrap();
+
+        assert_eq!(format!("{}: {}", img.fmt_addr(pc, false), fmt_decoded_at(&d, pc, &HashMap::new())), "0x80000040: j 0x80000048");
+        assert_eq!(format!("{}: {}", img.fmt_addr(pc, true), fmt_decoded_with(&d, pc, |a| img.seg_relative(a))), "flash+0x40: j flash+0x48");
+        assert_eq!(img.fmt_addr(0x9000_0000, true), "0x90000000", "unmapped addresses stay absolute");
+    }
+
+    #[test]
+    fn raw_code_starting_like_a_record_still_sniffs_as_bin() {
+        // `eq d15, ...` (op1 0x3A) and an `S` followed by a digit are plausible code
+        assert_eq!(sniff_format(&[0x3A, 0x21, 0x00, 0x00, 0x82, 0x10, 0x00, 0x00], true), InputFormat::Bin);
+        assert_eq!(sniff_format(b"S1\x82\x10\x00\x90", true), InputFormat::Bin);
+        // A long first line cut off by the sniff window is not a record either
+        assert_eq!(sniff_format(&[b':'; SNIFF_LEN as usize], false), InputFormat::Bin);
+
+        let img = Image::new(vec![Segment { name: "s".into(), base: 0x8000_0000, bytes: vec![0x82, 0x10, 0x00, 0x90], perms: "r-x", kind: "raw" }], Some(0x8000_0000)).unwrap();
+        let srec = write_srec(&img, 16).unwrap();
+        let ihex = write_ihex(&img, 16).unwrap();
+        assert_eq!(sniff_format(srec.as_bytes(), true), InputFormat::Srec);
+        assert_eq!(sniff_format(ihex.as_bytes(), true), InputFormat::Ihex);
+        // Only the first line has to be complete
+        assert_eq!(sniff_format(&ihex.as_bytes()[..ihex.find('\n').unwrap() + 3], false), InputFormat::Ihex);
+    }
+
+    #[test]
+    fn raw_load_flags_are_rejected_for_containers() {
+        let path = std::env::current_dir().unwrap().join("_test_flags.s19");
+        let img = Image::new(vec![Segment { name: "s".into(), base: 0x8000_0000, bytes: vec![0x82, 0x10, 0x00, 0x90], perms: "r-x", kind: "raw" }], Some(0x8000_0000)).unwrap();
+        std::fs::write(&path, write_srec(&img, 16).unwrap()).unwrap();
+        assert_eq!(load_input(&path, None, 0, 0, None, Endian::Le).unwrap().segments[0].base, 0x8000_0000);
+        let err = load_input(&path, None, 0x1000, 0, Some(4), Endian::Le).unwrap_err();
+        assert!(err.to_string().contains("--base, --len: only meaningful for raw bin input, not Srec"), "{err}");
+        assert!(load_input(&path, Some(InputFormat::Srec), 0, 0, None, Endian::Be).is_err());
+        // The same flags still apply when the file is forced to load as raw
+        assert_eq!(load_input(&path, Some(InputFormat::Bin), 0x1000, 0, Some(4), Endian::Le).unwrap().segments[0].bytes, b"S00A");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+use tricore_rs::decoder::Decoded;
+use tricore_rs::disasm::{fmt_decoded_parts, Operand};
+
+/// A register named by a `reg:` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegRef { D(u8), A(u8) }
+
+/// Addressing mode named by a `mem:` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemMode { Any, Abs, BaseOff, PreInc, PostInc, Pair }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    /// Instruction names the register as an operand or memory base
+    Reg(RegRef),
+    /// Mnemonic, compared case-insensitively
+    Op(String),
+    /// Instruction has a memory operand in this mode
+    Mem(MemMode),
+}
+
+/// Structured code search such as `reg:d4 op:ld.w`: whitespace-separated
+/// terms, all of which must match the decoded operands (not the text).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query { pub terms: Vec<Term> }
+
+impl Query {
+    /// Parse a search string. `Ok(None)` when no word has a `key:` prefix, so
+    /// the caller can fall back to a plain text search.
+    pub fn parse(s: &str) -> Result<Option<Self>, String> {
+        if !s.split_whitespace().any(|w| w.contains(':')) { return Ok(None); }
+        let terms = s.split_whitespace().map(parse_term).collect::<Result<_, _>>()?;
+        Ok(Some(Self { terms }))
+    }
+
+    pub fn matches(&self, d: &Decoded) -> bool {
+        let parts = fmt_decoded_parts(d);
+        self.terms.iter().all(|t| match t {
+            Term::Op(m) => parts.mnemonic.eq_ignore_ascii_case(m),
+            Term::Reg(r) => parts.operands.iter().any(|o| uses_reg(o, *r)),
+            Term::Mem(m) => parts.operands.iter().any(|o| mem_mode(o).is_some_and(|mode| *m == MemMode::Any || mode == *m)),
+        })
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term, String> {
+    let (key, val) = word.split_once(':').ok_or_else(|| format!("expected key:value, got '{word}'"))?;
+    let val = val.to_ascii_lowercase();
+    match key.to_ascii_lowercase().as_str() {
+        "reg" => parse_reg(&val).map(Term::Reg).ok_or_else(|| format!("unknown register '{val}'")),
+        "op" if !val.is_empty() => Ok(Term::Op(val)),
+        "mem" => {
+            let mode = match val.as_str() {
+                "any" => MemMode::Any,
+                "abs" => MemMode::Abs,
+                "bo" => MemMode::BaseOff,
+                "pre" => MemMode::PreInc,
+                "post" => MemMode::PostInc,
+                "pair" => MemMode::Pair,
+                _ => return Err(format!("unknown memory mode '{val}' (any, abs, bo, pre, post, pair)")),
+            };
+            Ok(Term::Mem(mode))
+        }
+        _ => Err(format!("unknown search term '{word}' (reg:, op:, mem:)")),
+    }
+}
+
+/// `d0`..`d15` or `a0`..`a15`.
+fn parse_reg(s: &str) -> Option<RegRef> {
+    let n: u8 = s.get(1..)?.parse().ok().filter(|&n| n < 16)?;
+    match s.as_bytes().first()? {
+        b'd' => Some(RegRef::D(n)),
+        b'a' => Some(RegRef::A(n)),
+        _ => None,
+    }
+}
+
+fn uses_reg(o: &Operand, r: RegRef) -> bool {
+    match (*o, r) {
+        (Operand::Reg(n), RegRef::D(m)) | (Operand::AReg(n), RegRef::A(m)) => n == m,
+        // eN is the pair dN/dN+1, pN the pair aN/aN+1
+        (Operand::EReg(n), RegRef::D(m)) | (Operand::MemPair(n), RegRef::A(m)) => m & !1 == n,
+        (Operand::MemBaseOff { a, .. } | Operand::MemPreInc { a, .. } | Operand::MemPostInc { a, .. }, RegRef::A(m)) => a == m,
+        _ => false,
+    }
+}
+
+fn mem_mode(o: &Operand) -> Option<MemMode> {
+    match o {
+        Operand::MemAbs(_) => Some(MemMode::Abs),
+        Operand::MemBaseOff { .. } => Some(MemMode::BaseOff),
+        Operand::MemPreInc { .. } => Some(MemMode::PreInc),
+        Operand::MemPostInc { .. } => Some(MemMode::PostInc),
+        Operand::MemPair(_) => Some(MemMode::Pair),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tricore_rs::decoder::Decoder;
+    use tricore_rs::isa::tc16::Tc16Decoder;
+
+    fn query(s: &str) -> Query { Query::parse(s).unwrap().unwrap() }
+
+    #[test]
+    fn reg_term_matches_ld_w_destination_and_base() {
+        // ld.w d4, [a2+0x4] (BO: op1 0x09, op2 0x24)
+        let raw = (0x24u32 << 22) | (4 << 16) | (2 << 12) | (4 << 8) | 0x09;
+        let d = Tc16Decoder::new().decode(raw).unwrap();
+        assert_eq!(tricore_rs::disasm::fmt_decoded(&d), "ld.w d4, [a2+0x4]");
+        assert!(query("reg:d4").matches(&d));
+        assert!(!query("reg:d5").matches(&d));
+        assert!(query("reg:a2 op:LD.W mem:bo").matches(&d));
+        assert!(!query("reg:d4 mem:abs").matches(&d));
+
+        assert_eq!(Query::parse("ld.w d4").unwrap(), None);
+        assert!(Query::parse("reg:d16").is_err());
+        assert!(Query::parse("size:4").is_err());
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use tracing_subscriber::EnvFilter;
 
-use tricore_rs::{exec::IntExecutor, isa::tc16::Tc16Decoder, Cpu, CpuConfig, LinearMemory};
+use tricore_rs::{exec::IntExecutor, isa::tc16::Tc16Decoder, Cpu, CpuConfig, LinearMemory, Trap};
 use tricore_rs::Bus;
 use tricore_rs::disasm::fmt_decoded;
 use tricore_rs::decoder::Decoder;
@@ -63,10 +63,11 @@ fn main() -> Result<()> {
         return Ok(());
     } else {
         // Simple run loop with step cap
-        for _ in 0..10_000_000u64 {
-            if let Err(trap) = cpu.step(&mut mem, &dec, &exec) {
-                eprintln!("TRAP: {trap:?}");
-                break;
+        if let Err(trap) = cpu.run_until(&mut mem, &dec, &exec, 10_000_000, |_| false) {
+            match &trap {
+                Trap::IllegalInstruction { pc, raw } => eprintln!("ILLEGAL INSTRUCTION: {raw:#x} at {pc:#010x}"),
+                Trap::Break => eprintln!("BREAK at {:#010x}", cpu.pc),
+                _ => eprintln!("TRAP: {trap}"),
             }
         }
     }
@@ -1,7 +1,7 @@
 use anyhow::Error;
-use crate::decoder::Decoder;
+use crate::decoder::{Decoded, Decoder};
 use crate::exec::Executor;
-use crate::memory::Bus;
+use crate::memory::{Access, Bus, PermError};
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +10,56 @@ pub struct CpuConfig {
     pub little_endian: bool, // TriCore is typically little-endian
     pub has_fpu: bool,
     pub has_dsp: bool,
+    /// Skip undecodable words (2 or 4 bytes by op1 bit 0) instead of trapping
+    #[serde(default)]
+    pub unknown_as_nop: bool,
+    /// Approximate cost of each instruction class, summed into `Cpu::cycles`
+    #[serde(default)]
+    pub cycle_costs: CycleCosts,
+}
+
+/// Rough per-instruction cycle costs by class, for performance estimates
+/// only: they never change what an instruction does. The defaults are round
+/// numbers in the spirit of the TC1.6 pipeline, not a timing model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CycleCosts {
+    /// Everything not in another class: moves, arithmetic, logic, compares
+    pub alu: u32,
+    pub mul: u32,
+    pub div: u32,
+    /// Loads of any width or addressing mode
+    pub load: u32,
+    pub store: u32,
+    /// Jumps, loops and conditional branches, taken or not
+    pub branch: u32,
+    /// Calls and returns (context save/restore)
+    pub call: u32,
+}
+
+impl Default for CycleCosts {
+    fn default() -> Self {
+        Self { alu: 1, mul: 2, div: 8, load: 2, store: 2, branch: 2, call: 4 }
+    }
+}
+
+impl CycleCosts {
+    /// Cycles charged for executing `d`.
+    pub fn cost(&self, d: &Decoded) -> u32 {
+        use crate::decoder::Op::*;
+        if d.is_call() || d.is_return() { return self.call; }
+        if d.is_branch() { return self.branch; }
+        match d.op {
+            Mul | MulU => self.mul,
+            Div | DivU => self.div,
+            LdW | LdD | LdB | LdBu | LdH | LdHu |
+            LdWPbr | LdWPcir | LdBPbr | LdBUPbr | LdHPbr | LdHUPbr |
+            LdBPcir | LdBUPcir | LdHPcir | LdHUPcir => self.load,
+            StW | StD | StB | StH |
+            StWPbr | StWPcir | StBPbr | StBPcir | StHPbr | StHPcir => self.store,
+            _ => self.alu,
+        }
+    }
 }
 
 impl Default for CpuConfig {
@@ -18,6 +68,8 @@ impl Default for CpuConfig {
             little_endian: true,
             has_fpu: false,
             has_dsp: false,
+            unknown_as_nop: false,
+            cycle_costs: CycleCosts::default(),
         }
     }
 }
@@ -30,6 +82,9 @@ pub struct Cpu {
     pub a: [u32; 16],   // Address regs (A0..A15) — model as needed
     pub cfg: CpuConfig,
     pub call_stack: Vec<u32>,
+    /// Sum of `cfg.cycle_costs` over executed instructions
+    #[serde(default)]
+    cycles: u64,
 }
 
 bitflags! {
@@ -47,16 +102,38 @@ const SAV = 1 << 7; // Sticky Advanced Overflow
 }
 }
 
+impl Psw {
+    /// Z/N/C/V as `[ZNCV]`, with `-` for each clear flag (e.g. `[Z-C-]`).
+    pub fn zncv(self) -> String {
+        let f = |flag: Psw, c: char| if self.contains(flag) { c } else { '-' };
+        format!("[{}{}{}{}]", f(Psw::Z, 'Z'), f(Psw::N, 'N'), f(Psw::C, 'C'), f(Psw::V, 'V'))
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Trap {
-    #[error("Invalid instruction at {pc:#010x}")]
-    InvalidInstruction { pc: u32 },
+    #[error("Illegal instruction {raw:#x} at {pc:#010x}")]
+    IllegalInstruction { pc: u32, raw: u32 },
     #[error("Unaligned access at {addr:#010x}")]
     Unaligned { addr: u32 },
     #[error("Bus error at {addr:#010x}: {source}")]
     Bus { addr: u32, #[source] source: Error },
+    #[error("{access:?} permission fault at {addr:#010x}")]
+    Perm { addr: u32, access: Access },
     #[error("Breakpoint")]
     Break,
+    #[error("Divide by zero")]
+    DivideByZero,
+}
+
+impl Trap {
+    /// Map a bus error to a trap, surfacing permission faults as `Trap::Perm`.
+    pub fn bus(addr: u32, source: Error) -> Self {
+        match source.downcast_ref::<PermError>() {
+            Some(p) => Trap::Perm { addr: p.addr, access: p.access },
+            None => Trap::Bus { addr, source },
+        }
+    }
 }
 
 impl Cpu {
@@ -68,9 +145,16 @@ impl Cpu {
             a: [0; 16],
             cfg,
             call_stack: Vec::new(),
+            cycles: 0,
         }
     }
 
+    /// Approximate cycles spent so far, by `CpuConfig::cycle_costs`. Traps
+    /// and skipped unknown words cost nothing.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
     pub fn reset(&mut self, reset_pc: u32) {
         self.pc = reset_pc;
     }
@@ -80,15 +164,61 @@ impl Cpu {
         bus: &mut B,
         dec: &D,
         exec: &X,
+    ) -> Result<(), Trap> {
+        self.step_traced(bus, dec, exec, |_, _, _| {})
+    }
+
+    /// `step`, then `trace(pc, insn, cpu)` with the state the instruction left
+    /// behind. Not called for traps or for unknown words skipped as NOPs; the
+    /// hook sees the CPU read-only, so it cannot change what executes.
+    pub fn step_traced<B: Bus, D: Decoder, X: Executor>(
+        &mut self,
+        bus: &mut B,
+        dec: &D,
+        exec: &X,
+        mut trace: impl FnMut(u32, &Decoded, &Cpu),
     ) -> Result<(), Trap> {
         let pc = self.pc;
         // TriCore supports 16-bit and 32-bit encodings; fetch 32 then let decoder decide width
         let raw32 = bus
-            .read_u32(pc)
-            .map_err(|source| Trap::Bus { addr: pc, source })?;
-        let d = dec.decode(raw32).ok_or(Trap::InvalidInstruction { pc })?;
+            .fetch_u32(pc)
+            .map_err(|source| Trap::bus(pc, source))?;
+        let Some(d) = dec.decode(raw32) else {
+            // op1 tells the encoding width even when the opcode is unknown
+            let width = dec.width_hint(raw32 as u8) as u32;
+            if self.cfg.unknown_as_nop {
+                self.pc = pc.wrapping_add(width);
+                return Ok(());
+            }
+            let raw = if width == 2 { raw32 & 0xFFFF } else { raw32 };
+            return Err(Trap::IllegalInstruction { pc, raw });
+        };
         // Advance PC by decoded width (2 or 4)
         self.pc = pc.wrapping_add(d.width as u32);
-        exec.exec(self, bus, d)
+        // A trapping instruction leaves PC pointing at itself
+        exec.exec(self, bus, d).inspect_err(|_| self.pc = pc)?;
+        self.cycles += self.cfg.cycle_costs.cost(&d) as u64;
+        trace(pc, &d, self);
+        Ok(())
+    }
+
+    /// Single-step until `stop` returns true or `max_steps` instructions have
+    /// run, returning the number of steps executed. Traps (including
+    /// `Trap::Break`) are returned unchanged with PC left on the faulting
+    /// instruction.
+    pub fn run_until<B: Bus, D: Decoder, X: Executor>(
+        &mut self,
+        bus: &mut B,
+        dec: &D,
+        exec: &X,
+        max_steps: usize,
+        stop: impl Fn(&Cpu) -> bool,
+    ) -> Result<usize, Trap> {
+        let mut steps = 0;
+        while steps < max_steps && !stop(self) {
+            self.step(bus, dec, exec)?;
+            steps += 1;
+        }
+        Ok(steps)
     }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Width {
@@ -14,6 +15,12 @@ pub enum Op {
     Mov,
     MovI, // move immediate (sign/zero/high are handled in decode)
     MovHA, // MOVH.A (address high move)
+    MovA,  // MOV.A A[a], D[b]
+    MovD,  // MOV.D D[a], A[b]
+    MovAA, // MOV.AA A[a], A[b]
+    AddA,  // ADD.A A[a], A[b] (address add, no flags)
+    AddAI, // ADD.A A[a], const4
+    SubA,  // SUB.A (address subtract, no flags)
     Lea,   // Load effective address into A
     And,
     Or,
@@ -32,6 +39,8 @@ pub enum Op {
     MulU,
     Div,
     DivU,
+    Absdif,  // |a - b| signed, V on overflow
+    AbsdifI, // |a - const9|
     // Flag-based branches (use PSW)
     BeqF,   // if Z (flag-based)
     BneF,   // if !Z
@@ -45,8 +54,41 @@ pub enum Op {
     CmpUI,
     Addc,
     Addx,
+    // Subtract with borrow-in from PSW.C (SUBC) or just setting it (SUBX)
+    Subc,
+    Subx,
+    Rsub, // RSUB D[c], D[a], const9: const9 - D[a]
+    // Conditional ops on D[d] != 0, with d in `imm2`: SEL/SELN pick D[a] or
+    // D[b], CADD/CSUB add or subtract D[b] from D[a]; the I forms take const9
+    Sel,
+    Seln,
+    Cadd,
+    Csub,
+    SelI,
+    SelnI,
+    CaddI,
+    // Bit fields at `imm` = pos with `imm2` = width: EXTR sign-extends the
+    // field, EXTR.U zero-extends it, INSERT writes D[b] into D[a]'s field
+    Extr,
+    ExtrU,
+    Insert,
+    Dextr, // DEXTR D[c], D[a], D[b], pos: ({D[a], D[b]} << pos)[63:32]
+    // Leading zeros / ones / redundant sign bits of D[a]
+    Clz,
+    Clo,
+    Cls,
+    // Saturating add/sub: signed clamp to i32, .U clamp to u32; the I forms take const9
+    Adds,
+    AddsU,
+    Subs,
+    SubsU,
+    AddsI,
+    AddsUI,
     LdW,
     StW,
+    /// 64-bit load/store of the even/odd pair E[rd] = D[rd+1]:D[rd]
+    LdD,
+    StD,
     // P[b] addressing (bit-reverse and circular)
     LdWPbr,
     LdWPcir,
@@ -89,6 +131,11 @@ pub enum Op {
     Call,
     CallA,
     CallI,
+    Ji,    // JI A[a]: indirect jump
+    Jli,   // JLI A[a]: indirect jump, return address in A[11]
+    Loop,  // LOOP A[b], disp: branch while A[b] != 0, then decrement A[b]
+    LoopU, // LOOPU disp: unconditional loop-back
+    AddscA, // ADDSC.A A[c], A[b], D[a], n: A[b] + (D[a] << n)
     Ret,
     JzA,
     JnzA,
@@ -110,6 +157,128 @@ pub struct Decoded {
     pub pre: bool, // true for pre-increment, false for post-increment when wb=true
 }
 
+impl Decoded {
+    /// Jumps and branches, conditional or not: `j`, `loop`/`loopu`, `ji` and
+    /// the compare-and-branch and flag branches. Calls and `ret` are not.
+    pub fn is_branch(&self) -> bool {
+        use Op::*;
+        matches!(self.op,
+            J | LoopU | Loop | Ji |
+            Jeq | Jne | JeqImm | JneImm | Jge | JgeU | JgeImm | JgeUImm |
+            Jlt | JltU | JltImm | JltUImm | JeqA | JneA | Bne | JzA | JnzA |
+            BeqF | BneF | BgeF | BltF | BgeUF | BltUF)
+    }
+
+    /// Transfers that come back to the next instruction: `call`, `calla`,
+    /// `calli`, and `jli` (which links the return address in A[11]).
+    pub fn is_call(&self) -> bool {
+        matches!(self.op, Op::Call | Op::CallA | Op::CallI | Op::Jli)
+    }
+
+    pub fn is_return(&self) -> bool {
+        matches!(self.op, Op::Ret)
+    }
+
+    /// Control never reaches the next instruction: `j`, `loopu`, `ji`, `ret`.
+    pub fn is_unconditional(&self) -> bool {
+        matches!(self.op, Op::J | Op::LoopU | Op::Ji | Op::Ret)
+    }
+
+    /// The first formatted operand is a register this instruction writes;
+    /// false for branches, calls, `ret`, `syscall`, compares and stores.
+    pub fn writes_first_operand(&self) -> bool {
+        use Op::*;
+        let no_dest = matches!(self.op,
+            Cmp | CmpU | CmpI | CmpUI | Syscall |
+            StW | StD | StB | StH | StWPbr | StWPcir | StBPbr | StBPcir | StHPbr | StHPcir);
+        !(no_dest || self.is_branch() || self.is_call() || self.is_return())
+    }
+
+    /// Ends a basic block: a branch or a return. Calls fall through.
+    pub fn is_terminator(&self) -> bool {
+        self.is_branch() || self.is_return()
+    }
+
+    /// Absolute target of a direct branch or call at `pc`: `imm` counts from
+    /// the next instruction, except for `calla`, whose `imm` is the address.
+    /// `None` for indirect transfers, `ret`, and everything else.
+    pub fn branch_target(&self, pc: u32) -> Option<u32> {
+        match self.op {
+            Op::CallA => Some(self.imm),
+            Op::Ji | Op::Jli | Op::CallI => None,
+            _ if self.is_branch() || self.is_call() => Some(pc.wrapping_add(self.width as u32).wrapping_add(self.imm)),
+            _ => None,
+        }
+    }
+}
+
+/// Why `Decoder::decode_full` rejected an instruction word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DecodeReason {
+    /// No encoding uses this `op1`
+    UnknownOp1,
+    /// `op1` is known, but not with this secondary opcode
+    UnknownOp2,
+    /// A 64-bit E[n] operand names an odd register
+    OddRegisterPair,
+    /// Fewer bytes than the instruction width `op1` implies
+    Truncated,
+}
+
+impl fmt::Display for DecodeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DecodeReason::UnknownOp1 => "unknown op1",
+            DecodeReason::UnknownOp2 => "unknown op2",
+            DecodeReason::OddRegisterPair => "odd register pair",
+            DecodeReason::Truncated => "truncated",
+        })
+    }
+}
+
+/// A failed decode: the primary opcode, the secondary one for formats that
+/// have it (as extracted by that format, e.g. RR [27:20], BO [27:22]), and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodeError {
+    pub op1: u8,
+    pub op2: Option<u32>,
+    pub reason: DecodeReason,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "op1 {:#04x}", self.op1)?;
+        if let Some(op2) = self.op2 { write!(f, ", op2 {op2:#04x}")?; }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 pub trait Decoder {
     fn decode(&self, raw32: u32) -> Option<Decoded>;
+
+    /// Width in bytes of any instruction starting with `first_byte` (its
+    /// `op1`), so callers fetch exactly that many bytes instead of four.
+    fn width_hint(&self, first_byte: u8) -> u8;
+
+    /// `decode` that says why a word is not a known instruction. Decoders
+    /// that can tell `op2` misses apart override this and implement `decode`
+    /// as its `.ok()`; the default only knows the `op1`.
+    fn decode_full(&self, raw32: u32) -> Result<Decoded, DecodeError> {
+        self.decode(raw32).ok_or(DecodeError { op1: raw32 as u8, op2: None, reason: DecodeReason::UnknownOp1 })
+    }
+
+    /// Decode from the bytes at an address, in memory order. Running out of
+    /// bytes before the width `op1` implies is `DecodeReason::Truncated`.
+    fn decode_bytes(&self, bytes: &[u8]) -> Result<Decoded, DecodeError> {
+        let op1 = bytes.first().copied().unwrap_or(0);
+        let width = self.width_hint(op1) as usize;
+        if bytes.len() < width {
+            return Err(DecodeError { op1, op2: None, reason: DecodeReason::Truncated });
+        }
+        let mut word = [0u8; 4];
+        word[..width].copy_from_slice(&bytes[..width]);
+        self.decode_full(u32::from_le_bytes(word))
+    }
 }
@@ -1,77 +1,329 @@
+use std::collections::HashMap;
+use std::fmt;
+
 use crate::decoder::{Decoded, Op};
 
+/// One rendered operand of a decoded instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    /// Data register `dN`
+    Reg(u8),
+    /// Address register `aN`
+    AReg(u8),
+    /// Extended (even/odd data pair) register `eN`
+    EReg(u8),
+    Imm { val: u32, style: ImmStyle },
+    /// Absolute address `[0x..]`
+    MemAbs(u32),
+    /// `[aN+off]`
+    MemBaseOff { a: u8, off: u32 },
+    /// Pre-increment `[+aN]off`: the base is advanced before the access
+    MemPreInc { a: u8, off: u32 },
+    /// Post-increment `[aN+]off`: the base is advanced after the access
+    MemPostInc { a: u8, off: u32 },
+    /// Address register pair `[pN]` (bit-reverse/circular modes)
+    MemPair(u8),
+    /// PC-relative displacement, printed signed
+    Target(i32),
+    /// Absolute call target
+    AbsTarget(u32),
+}
+
+/// How an immediate is printed; kept per operand so text output is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImmStyle {
+    /// `0x10`
+    Hex,
+    /// `#0x10`
+    HashHex,
+    /// `#-3`
+    HashDec,
+    /// `+0x4` / `-0x4`
+    Signed,
+}
+
+/// A decoded instruction split into mnemonic and operands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub mnemonic: String,
+    pub operands: Vec<Operand>,
+    /// The first operand is the destination (see `Decoded::writes_first_operand`)
+    pub dest_first: bool,
+}
+
+/// Toolchain syntax for rendered text; `fmt_decoded_parts` stays style-free
+/// and the style only applies when operands are turned into text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MnemonicStyle {
+    /// `ld.w d2, [a3+0x4]`
+    #[default]
+    Default,
+    /// GNU as: `%`-prefixed registers, `ld.w %d2, [%a3+0x4]`
+    Gnu,
+    /// Vendor listings: uppercase hex digits, `mov d2, #0x1F`
+    Tasking,
+    /// Destination last, `add d1, 0x10, d3` for `add d3, d1, 0x10`; operands
+    /// of instructions without a destination stay in order
+    DestLast,
+}
+
+impl MnemonicStyle {
+    pub const ALL: [MnemonicStyle; 4] = [MnemonicStyle::Default, MnemonicStyle::Gnu, MnemonicStyle::Tasking, MnemonicStyle::DestLast];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MnemonicStyle::Default => "default",
+            MnemonicStyle::Gnu => "gnu",
+            MnemonicStyle::Tasking => "tasking",
+            MnemonicStyle::DestLast => "dest-last",
+        }
+    }
+
+    fn reg_prefix(self) -> &'static str {
+        if self == MnemonicStyle::Gnu { "%" } else { "" }
+    }
+
+    fn hex(self, v: u32) -> String {
+        if self == MnemonicStyle::Tasking { format!("{v:#X}") } else { format!("{v:#x}") }
+    }
+
+    fn signed_hex(self, v: i32) -> String {
+        if self == MnemonicStyle::Tasking { format!("{v:+#X}") } else { format!("{v:+#x}") }
+    }
+
+    /// Absolute addresses, zero-padded to eight digits
+    fn addr(self, v: u32) -> String {
+        if self == MnemonicStyle::Tasking { format!("{v:#010X}") } else { format!("{v:#010x}") }
+    }
+}
+
+impl fmt::Display for MnemonicStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for MnemonicStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MnemonicStyle::ALL.into_iter().find(|m| m.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = MnemonicStyle::ALL.iter().map(|m| m.name()).collect();
+            format!("unknown syntax `{s}` (expected one of: {})", names.join(", "))
+        })
+    }
+}
+
+impl Operand {
+    /// This operand in `style`'s syntax; `Display` is the default style.
+    pub fn render(&self, style: MnemonicStyle) -> String {
+        let r = style.reg_prefix();
+        let hex = |v: u32| style.hex(v);
+        match *self {
+            Operand::Reg(n) => format!("{r}d{n}"),
+            Operand::AReg(n) => format!("{r}a{n}"),
+            Operand::EReg(n) => format!("{r}e{n}"),
+            Operand::Imm { val, style: ImmStyle::Hex } => hex(val),
+            Operand::Imm { val, style: ImmStyle::HashHex } => format!("#{}", hex(val)),
+            Operand::Imm { val, style: ImmStyle::HashDec } => format!("#{}", val as i32),
+            Operand::Imm { val, style: ImmStyle::Signed } => style.signed_hex(val as i32),
+            Operand::MemAbs(addr) => format!("[{}]", hex(addr)),
+            Operand::MemBaseOff { a, off } => format!("[{r}a{a}+{}]", hex(off)),
+            Operand::MemPreInc { a, off } => format!("[+{r}a{a}]{}", hex(off)),
+            Operand::MemPostInc { a, off } => format!("[{r}a{a}+]{}", hex(off)),
+            Operand::MemPair(p) => format!("[{r}p{p}]"),
+            Operand::Target(off) => style.signed_hex(off),
+            Operand::AbsTarget(addr) => hex(addr),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(MnemonicStyle::Default))
+    }
+}
+
+impl DecodedText {
+    /// The whole instruction in `style`'s syntax.
+    pub fn render(&self, style: MnemonicStyle) -> String {
+        join_operands(&self.mnemonic, self.operands.iter().map(|op| op.render(style)).collect(), style, self.dest_first)
+    }
+}
+
+impl fmt::Display for DecodedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(MnemonicStyle::Default))
+    }
+}
+
+/// `mnemonic op, op, ...`, with the first operand moved last for `DestLast`
+/// when it is the destination; branches, compares and stores keep their order.
+fn join_operands(mnemonic: &str, mut operands: Vec<String>, style: MnemonicStyle, dest_first: bool) -> String {
+    if operands.is_empty() { return mnemonic.to_string(); }
+    if style == MnemonicStyle::DestLast && dest_first { operands.rotate_left(1); }
+    format!("{mnemonic} {}", operands.join(", "))
+}
+
 pub fn fmt_decoded(d: &Decoded) -> String {
+    fmt_decoded_parts(d).to_string()
+}
+
+/// `fmt_decoded` in another toolchain's syntax.
+pub fn fmt_decoded_styled(d: &Decoded, style: MnemonicStyle) -> String {
+    fmt_decoded_parts(d).render(style)
+}
+
+/// Like `fmt_decoded`, but for an instruction at `pc`: branch and call
+/// targets print as absolute addresses (a label when one is known) instead of
+/// PC-relative offsets, and labelled absolute memory operands print `[name]`.
+pub fn fmt_decoded_at(d: &Decoded, pc: u32, labels: &HashMap<u32, String>) -> String {
+    fmt_decoded_with(d, pc, |addr| labels.get(&addr).cloned())
+}
+
+/// `fmt_decoded_at` with a caller-supplied namer: addresses `name` maps are
+/// printed by that name (memory operands as `[name]`), the rest as hex.
+pub fn fmt_decoded_with(d: &Decoded, pc: u32, namer: impl Fn(u32) -> Option<String>) -> String {
+    fmt_decoded_with_style(d, pc, MnemonicStyle::Default, namer)
+}
+
+/// `fmt_decoded_with` in another toolchain's syntax.
+pub fn fmt_decoded_with_style(d: &Decoded, pc: u32, style: MnemonicStyle, namer: impl Fn(u32) -> Option<String>) -> String {
+    let parts = fmt_decoded_parts(d);
+    // Displacements are relative to the next instruction, as in exec and analysis
+    let ft = pc.wrapping_add(d.width as u32);
+    let name = |addr: u32| namer(addr).unwrap_or_else(|| style.addr(addr));
+    let operands: Vec<String> = parts
+        .operands
+        .iter()
+        .map(|op| match (target_addr(op, ft), *op) {
+            (Some(addr), _) => name(addr),
+            (None, Operand::MemAbs(addr)) => namer(addr).map_or_else(|| op.render(style), |n| format!("[{n}]")),
+            _ => op.render(style),
+        })
+        .collect();
+    join_operands(&parts.mnemonic, operands, style, parts.dest_first)
+}
+
+/// Absolute branch or call target of the instruction at `pc`, as
+/// `fmt_decoded_at` prints it; `None` for non-branches and indirect jumps.
+pub fn abs_target(d: &Decoded, pc: u32) -> Option<u32> {
+    let ft = pc.wrapping_add(d.width as u32);
+    fmt_decoded_parts(d).operands.iter().find_map(|op| target_addr(op, ft))
+}
+
+fn target_addr(op: &Operand, ft: u32) -> Option<u32> {
+    match *op {
+        Operand::Target(off) => Some(ft.wrapping_add(off as u32)),
+        Operand::AbsTarget(addr) => Some(addr),
+        _ => None,
+    }
+}
+
+/// Split a decoded instruction into mnemonic and typed operands.
+pub fn fmt_decoded_parts(d: &Decoded) -> DecodedText {
+    use Operand::{AReg, AbsTarget, MemPair, Reg, Target};
+    let t = |mn: &str, operands: Vec<Operand>| DecodedText { mnemonic: mn.to_string(), operands, dest_first: d.writes_first_operand() };
+    let off = Target(d.imm as i32);
     match d.op {
-        Op::Mov => format!("mov d{}, d{}", d.rd, d.rs1),
-        Op::MovI => format!("mov d{}, #{:#x}", d.rd, d.imm),
-        Op::MovHA => format!("movh.a a{}, #{:#x}", d.rd, d.imm >> 16),
+        Op::Mov => t("mov", vec![Reg(d.rd), Reg(d.rs1)]),
+        Op::MovI => t("mov", vec![Reg(d.rd), imm(d.imm, ImmStyle::HashHex)]),
+        Op::MovHA => t("movh.a", vec![AReg(d.rd), imm(d.imm >> 16, ImmStyle::HashHex)]),
+        Op::MovA => t("mov.a", vec![AReg(d.rd), Reg(d.rs1)]),
+        Op::MovD => t("mov.d", vec![Reg(d.rd), AReg(d.rs1)]),
+        Op::MovAA => t("mov.aa", vec![AReg(d.rd), AReg(d.rs1)]),
+        Op::AddA => t("add.a", vec![AReg(d.rd), AReg(d.rs2)]),
+        Op::AddAI => t("add.a", vec![AReg(d.rd), imm(d.imm, ImmStyle::HashHex)]),
+        Op::SubA => if d.rs2 != 0 { t("sub.a", vec![AReg(d.rd), AReg(d.rs1), AReg(d.rs2)]) } else { t("sub.a", vec![AReg(d.rd), imm(d.imm, ImmStyle::HashHex)]) },
         Op::Lea => {
-            if d.abs { format!("lea a{}, [{:#x}]", d.rd, d.imm) }
-            else if d.wb && d.pre { format!("lea a{}, [a{}+{:#x}]!", d.rd, d.rs1, d.imm) }
-            else { format!("lea a{}, [a{}+{:#x}]", d.rd, d.rs1, d.imm) }
-        }
-        Op::Add => {
-            if d.rs2 != 0 { format!("add d{}, d{}, d{}", d.rd, d.rs1, d.rs2) }
-            else { format!("addi d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) }
+            // LEA has no post-increment form; writeback without pre still prints base+off
+            let m = if d.wb && !d.pre { Operand::MemBaseOff { a: d.rs1, off: d.imm } } else { addr(d) };
+            t("lea", vec![AReg(d.rd), m])
         }
-        Op::Addx => if d.rs2 != 0 { format!("addx d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("addx d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Addc => if d.rs2 != 0 { format!("addc d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("addc d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Sub => {
-            if d.rs2 != 0 { format!("sub d{}, d{}, d{}", d.rd, d.rs1, d.rs2) }
-            else { format!("rsub d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) }
+        Op::Add => rri("add", "addi", d),
+        Op::Addx => rri("addx", "addx", d),
+        Op::Adds => t("adds", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::AddsU => t("adds.u", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::Subs => t("subs", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::SubsU => t("subs.u", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::AddsI => t("adds", vec![Reg(d.rd), Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
+        Op::AddsUI => t("adds.u", vec![Reg(d.rd), Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
+        Op::Addc => rri("addc", "addc", d),
+        Op::Sub => t("sub", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::Subc => t("subc", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::Subx => t("subx", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::Rsub => t("rsub", vec![Reg(d.rd), Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
+        Op::Clz => t("clz", vec![Reg(d.rd), Reg(d.rs1)]),
+        Op::Clo => t("clo", vec![Reg(d.rd), Reg(d.rs1)]),
+        Op::Cls => t("cls", vec![Reg(d.rd), Reg(d.rs1)]),
+        Op::Extr | Op::ExtrU => t(if matches!(d.op, Op::Extr) { "extr" } else { "extr.u" }, vec![Reg(d.rd), Reg(d.rs1), imm(d.imm, ImmStyle::HashDec), imm(d.imm2, ImmStyle::HashDec)]),
+        Op::Insert => t("insert", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::HashDec), imm(d.imm2, ImmStyle::HashDec)]),
+        Op::Dextr => t("dextr", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::HashDec)]),
+        Op::Sel | Op::Seln | Op::Cadd | Op::Csub | Op::SelI | Op::SelnI | Op::CaddI => {
+            let mn = match d.op { Op::Sel | Op::SelI => "sel", Op::Seln | Op::SelnI => "seln", Op::Cadd | Op::CaddI => "cadd", _ => "csub" };
+            let last = if matches!(d.op, Op::SelI | Op::SelnI | Op::CaddI) { imm(d.imm, ImmStyle::Hex) } else { Reg(d.rs2) };
+            t(mn, vec![Reg(d.rd), Reg(d.imm2 as u8), Reg(d.rs1), last])
         }
-        Op::And => if d.rs2 != 0 { format!("and d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("and d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Or  => if d.rs2 != 0 { format!("or d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("or d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Xor => if d.rs2 != 0 { format!("xor d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("xor d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Shl => if d.rs2 != 0 { format!("shl d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("shl d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Shr => if d.rs2 != 0 { format!("shr d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("shr d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Sar => if d.rs2 != 0 { format!("sar d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("sar d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Ror => if d.rs2 != 0 { format!("ror d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("ror d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Andn => if d.rs2 != 0 { format!("andn d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("andn d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Not => format!("not d{}, d{}", d.rd, d.rs1),
-        Op::Min => if d.rs2 != 0 { format!("min d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("min d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Max => if d.rs2 != 0 { format!("max d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("max d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::MinU => if d.rs2 != 0 { format!("min.u d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("min.u d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::MaxU => if d.rs2 != 0 { format!("max.u d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("max.u d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Mul => if d.rs2 != 0 { format!("mul d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("mul d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::MulU => if d.rs2 != 0 { format!("mul.u d{}, d{}, d{}", d.rd, d.rs1, d.rs2) } else { format!("mul.u d{}, d{}, {:#x}", d.rd, d.rs1, d.imm) },
-        Op::Div => format!("div d{}, d{}, d{}", d.rd, d.rs1, d.rs2),
-        Op::DivU => format!("div.u d{}, d{}, d{}", d.rd, d.rs1, d.rs2),
-        Op::BeqF => format!("beq {:+#x}", d.imm as i32),
-        Op::BneF => format!("bne {:+#x}", d.imm as i32),
-        Op::BgeF => format!("bge {:+#x}", d.imm as i32),
-        Op::BltF => format!("blt {:+#x}", d.imm as i32),
-        Op::BgeUF => format!("bge.u {:+#x}", d.imm as i32),
-        Op::BltUF => format!("blt.u {:+#x}", d.imm as i32),
-        Op::Cmp => if d.rs2 != 0 { format!("cmp d{}, d{}", d.rs1, d.rs2) } else { format!("cmp d{}, {:#x}", d.rs1, d.imm) },
-        Op::CmpU => if d.rs2 != 0 { format!("cmp.u d{}, d{}", d.rs1, d.rs2) } else { format!("cmp.u d{}, {:#x}", d.rs1, d.imm) },
-        Op::CmpI => format!("cmp d{}, {:#x}", d.rs1, d.imm),
-        Op::CmpUI => format!("cmp.u d{}, {:#x}", d.rs1, d.imm),
+        Op::And => rri("and", "and", d),
+        Op::Or => rri("or", "or", d),
+        Op::Xor => rri("xor", "xor", d),
+        Op::Shl => shift("shl", d),
+        Op::Shr => rri("shr", "shr", d),
+        Op::Sar => shift("sar", d),
+        Op::Ror => rri("ror", "ror", d),
+        Op::Andn => rri("andn", "andn", d),
+        Op::Not => t("not", vec![Reg(d.rd), Reg(d.rs1)]),
+        Op::Min => rri("min", "min", d),
+        Op::Max => rri("max", "max", d),
+        Op::MinU => rri("min.u", "min.u", d),
+        Op::MaxU => rri("max.u", "max.u", d),
+        Op::Mul => rri("mul", "mul", d),
+        Op::MulU => rri("mul.u", "mul.u", d),
+        Op::Div => t("div", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::DivU => t("div.u", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::Absdif => t("absdif", vec![Reg(d.rd), Reg(d.rs1), Reg(d.rs2)]),
+        Op::AbsdifI => t("absdif", vec![Reg(d.rd), Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
+        Op::BeqF => t("beq", vec![off]),
+        Op::BneF => t("bne", vec![off]),
+        Op::BgeF => t("bge", vec![off]),
+        Op::BltF => t("blt", vec![off]),
+        Op::BgeUF => t("bge.u", vec![off]),
+        Op::BltUF => t("blt.u", vec![off]),
+        Op::Cmp => if d.rs2 != 0 { t("cmp", vec![Reg(d.rs1), Reg(d.rs2)]) } else { t("cmp", vec![Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]) },
+        Op::CmpU => if d.rs2 != 0 { t("cmp.u", vec![Reg(d.rs1), Reg(d.rs2)]) } else { t("cmp.u", vec![Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]) },
+        Op::CmpI => t("cmp", vec![Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
+        Op::CmpUI => t("cmp.u", vec![Reg(d.rs1), imm(d.imm, ImmStyle::Hex)]),
         Op::LdB => mem("ld.b", d),
         Op::LdBu => mem("ld.bu", d),
         Op::LdH => mem("ld.h", d),
         Op::LdHu => mem("ld.hu", d),
         Op::LdW => mem("ld.w", d),
-        Op::LdWPbr => format!("ld.w d{}, [p{}]", d.rd, d.rs1),
-        Op::LdWPcir => format!("ld.w d{}, [p{}], {:+#x}", d.rd, d.rs1, d.imm as i32),
-        Op::LdBPbr => format!("ld.b d{}, [p{}]", d.rd, d.rs1),
-        Op::LdBUPbr => format!("ld.bu d{}, [p{}]", d.rd, d.rs1),
-        Op::LdHPbr => format!("ld.h d{}, [p{}]", d.rd, d.rs1),
-        Op::LdHUPbr => format!("ld.hu d{}, [p{}]", d.rd, d.rs1),
-        Op::LdBPcir => format!("ld.b d{}, [p{}], {:+#x}", d.rd, d.rs1, d.imm as i32),
-        Op::LdBUPcir => format!("ld.bu d{}, [p{}], {:+#x}", d.rd, d.rs1, d.imm as i32),
-        Op::LdHPcir => format!("ld.h d{}, [p{}], {:+#x}", d.rd, d.rs1, d.imm as i32),
-        Op::LdHUPcir => format!("ld.hu d{}, [p{}], {:+#x}", d.rd, d.rs1, d.imm as i32),
+        Op::LdD => {
+            let mut m = mem("ld.d", d);
+            m.operands[0] = Operand::EReg(d.rd);
+            m
+        }
+        Op::LdWPbr => t("ld.w", vec![Reg(d.rd), MemPair(d.rs1)]),
+        Op::LdWPcir => t("ld.w", vec![Reg(d.rd), MemPair(d.rs1), imm(d.imm, ImmStyle::Signed)]),
+        Op::LdBPbr => t("ld.b", vec![Reg(d.rd), MemPair(d.rs1)]),
+        Op::LdBUPbr => t("ld.bu", vec![Reg(d.rd), MemPair(d.rs1)]),
+        Op::LdHPbr => t("ld.h", vec![Reg(d.rd), MemPair(d.rs1)]),
+        Op::LdHUPbr => t("ld.hu", vec![Reg(d.rd), MemPair(d.rs1)]),
+        Op::LdBPcir => t("ld.b", vec![Reg(d.rd), MemPair(d.rs1), imm(d.imm, ImmStyle::Signed)]),
+        Op::LdBUPcir => t("ld.bu", vec![Reg(d.rd), MemPair(d.rs1), imm(d.imm, ImmStyle::Signed)]),
+        Op::LdHPcir => t("ld.h", vec![Reg(d.rd), MemPair(d.rs1), imm(d.imm, ImmStyle::Signed)]),
+        Op::LdHUPcir => t("ld.hu", vec![Reg(d.rd), MemPair(d.rs1), imm(d.imm, ImmStyle::Signed)]),
         Op::StB => mems("st.b", d),
         Op::StH => mems("st.h", d),
         Op::StW => mems("st.w", d),
-        Op::StWPbr => format!("st.w [p{}], d{}", d.rs1, d.rs2),
-        Op::StWPcir => format!("st.w [p{}], d{}, {:+#x}", d.rs1, d.rs2, d.imm as i32),
-        Op::StBPbr => format!("st.b [p{}], d{}", d.rs1, d.rs2),
-        Op::StBPcir => format!("st.b [p{}], d{}, {:+#x}", d.rs1, d.rs2, d.imm as i32),
-        Op::StHPbr => format!("st.h [p{}], d{}", d.rs1, d.rs2),
-        Op::StHPcir => format!("st.h [p{}], d{}, {:+#x}", d.rs1, d.rs2, d.imm as i32),
-        Op::J => format!("j {:+#x}", d.imm as i32),
+        Op::StD => t("st.d", vec![addr(d), Operand::EReg(d.rs2)]),
+        Op::StWPbr => t("st.w", vec![MemPair(d.rs1), Reg(d.rs2)]),
+        Op::StWPcir => t("st.w", vec![MemPair(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::Signed)]),
+        Op::StBPbr => t("st.b", vec![MemPair(d.rs1), Reg(d.rs2)]),
+        Op::StBPcir => t("st.b", vec![MemPair(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::Signed)]),
+        Op::StHPbr => t("st.h", vec![MemPair(d.rs1), Reg(d.rs2)]),
+        Op::StHPcir => t("st.h", vec![MemPair(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::Signed)]),
+        Op::J => t("j", vec![off]),
         Op::Jeq => br("jeq", d, false),
         Op::Jne => br("jne", d, false),
         Op::JeqImm => bri("jeq", d),
@@ -86,36 +338,62 @@ pub fn fmt_decoded(d: &Decoded) -> String {
         Op::JltUImm => bri("jlt.u", d),
         Op::JeqA => br("jeq.a", d, true),
         Op::JneA => br("jne.a", d, true),
-        Op::Call => format!("call {:+#x}", d.imm as i32),
-        Op::CallA => format!("calla {:#x}", d.imm),
-        Op::CallI => format!("calli a{}", d.rs1),
-        Op::Ret => "ret".to_string(),
-        Op::JzA => format!("jz.a a{}, {:+#x}", d.rs1, d.imm as i32),
-        Op::JnzA => format!("jnz.a a{}, {:+#x}", d.rs1, d.imm as i32),
+        Op::Call => t("call", vec![off]),
+        Op::CallA => t("calla", vec![AbsTarget(d.imm)]),
+        Op::CallI => t("calli", vec![AReg(d.rs1)]),
+        Op::Ji => t("ji", vec![AReg(d.rs1)]),
+        Op::Jli => t("jli", vec![AReg(d.rs1)]),
+        Op::AddscA => t("addsc.a", vec![AReg(d.rd), AReg(d.rs1), Reg(d.rs2), imm(d.imm, ImmStyle::HashDec)]),
+        Op::Ret => t("ret", vec![]),
+        Op::Loop => t("loop", vec![AReg(d.rs1), off]),
+        Op::LoopU => t("loopu", vec![off]),
+        Op::JzA => t("jz.a", vec![AReg(d.rs1), off]),
+        Op::JnzA => t("jnz.a", vec![AReg(d.rs1), off]),
         Op::Bne => br("bne", d, false),
-        Op::Syscall => "syscall".to_string(),
+        Op::Syscall => t("syscall", vec![]),
     }
 }
 
-fn mem(mn: &str, d: &Decoded) -> String {
-    if d.abs { format!("{} d{}, [{:#x}]", mn, d.rd, d.imm) }
-    else if d.wb && d.pre { format!("{} d{}, [a{}+{:#x}]!", mn, d.rd, d.rs1, d.imm) }
-    else if d.wb { format!("{} d{}, [a{}], {:#x}", mn, d.rd, d.rs1, d.imm) }
-    else { format!("{} d{}, [a{}+{:#x}]", mn, d.rd, d.rs1, d.imm) }
+fn imm(val: u32, style: ImmStyle) -> Operand {
+    Operand::Imm { val, style }
+}
+
+/// Register form when `rs2` is set, immediate form (possibly another mnemonic) otherwise.
+fn rri(reg_mn: &str, imm_mn: &str, d: &Decoded) -> DecodedText {
+    if d.rs2 != 0 {
+        DecodedText { mnemonic: reg_mn.to_string(), operands: vec![Operand::Reg(d.rd), Operand::Reg(d.rs1), Operand::Reg(d.rs2)], dest_first: d.writes_first_operand() }
+    } else {
+        DecodedText { mnemonic: imm_mn.to_string(), operands: vec![Operand::Reg(d.rd), Operand::Reg(d.rs1), imm(d.imm, ImmStyle::Hex)], dest_first: d.writes_first_operand() }
+    }
+}
+
+fn shift(mn: &str, d: &Decoded) -> DecodedText {
+    let mut t = rri(mn, mn, d);
+    if d.rs2 == 0 { t.operands[2] = imm(d.imm, ImmStyle::HashDec); }
+    t
+}
+
+/// Memory operand for the BO/ABS addressing modes.
+fn addr(d: &Decoded) -> Operand {
+    if d.abs { Operand::MemAbs(d.imm) }
+    else if d.wb && d.pre { Operand::MemPreInc { a: d.rs1, off: d.imm } }
+    else if d.wb { Operand::MemPostInc { a: d.rs1, off: d.imm } }
+    else { Operand::MemBaseOff { a: d.rs1, off: d.imm } }
+}
+
+fn mem(mn: &str, d: &Decoded) -> DecodedText {
+    DecodedText { mnemonic: mn.to_string(), operands: vec![Operand::Reg(d.rd), addr(d)], dest_first: d.writes_first_operand() }
 }
 
-fn mems(mn: &str, d: &Decoded) -> String {
-    if d.abs { format!("{} [{:#x}], d{}", mn, d.imm, d.rs2) }
-    else if d.wb && d.pre { format!("{} [a{}+{:#x}]!, d{}", mn, d.rs1, d.imm, d.rs2) }
-    else if d.wb { format!("{} [a{}], d{}", mn, d.rs1, d.rs2) }
-    else { format!("{} [a{}+{:#x}], d{}", mn, d.rs1, d.imm, d.rs2) }
+fn mems(mn: &str, d: &Decoded) -> DecodedText {
+    DecodedText { mnemonic: mn.to_string(), operands: vec![addr(d), Operand::Reg(d.rs2)], dest_first: d.writes_first_operand() }
 }
 
-fn br(mn: &str, d: &Decoded, addr: bool) -> String {
-    if addr { format!("{} a{}, a{}, {:+#x}", mn, d.rs1, d.rs2, d.imm as i32) }
-    else { format!("{} d{}, d{}, {:+#x}", mn, d.rs1, d.rs2, d.imm as i32) }
+fn br(mn: &str, d: &Decoded, addr: bool) -> DecodedText {
+    let reg = if addr { Operand::AReg } else { Operand::Reg };
+    DecodedText { mnemonic: mn.to_string(), operands: vec![reg(d.rs1), reg(d.rs2), Operand::Target(d.imm as i32)], dest_first: d.writes_first_operand() }
 }
 
-fn bri(mn: &str, d: &Decoded) -> String {
-    format!("{} d{}, {:#x}, {:+#x}", mn, d.rs1, d.imm2, d.imm as i32)
+fn bri(mn: &str, d: &Decoded) -> DecodedText {
+    DecodedText { mnemonic: mn.to_string(), operands: vec![Operand::Reg(d.rs1), imm(d.imm2, ImmStyle::Hex), Operand::Target(d.imm as i32)], dest_first: d.writes_first_operand() }
 }
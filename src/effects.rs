@@ -0,0 +1,102 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::cpu::{Cpu, Psw, Trap};
+use crate::decoder::Decoder;
+use crate::exec::Executor;
+use crate::memory::Bus;
+
+/// One architectural state change made by an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    D { reg: u8, val: u32 },
+    A { reg: u8, val: u32 },
+    Flag { name: &'static str, set: bool },
+    Mem { addr: u32, width: u8, val: u32 },
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Effect::D { reg, val } => write!(f, "d{reg} <- {val:#x}"),
+            Effect::A { reg, val } => write!(f, "a{reg} <- {val:#x}"),
+            Effect::Flag { name, set } => write!(f, "{name}={}", *set as u8),
+            Effect::Mem { addr, width, val } => write!(f, "[{addr:#010x}].{width} <- {val:#x}"),
+        }
+    }
+}
+
+/// Render effects as `d3 <- 0x5, [addr].4 <- 0x1 ; Z=1 C=0`.
+pub fn fmt_effects(effects: &[Effect]) -> String {
+    let (flags, writes): (Vec<&Effect>, Vec<&Effect>) = effects.iter().partition(|e| matches!(e, Effect::Flag { .. }));
+    let writes: Vec<String> = writes.iter().map(|e| e.to_string()).collect();
+    let flags: Vec<String> = flags.iter().map(|e| e.to_string()).collect();
+    match (writes.is_empty(), flags.is_empty()) {
+        (_, true) => writes.join(", "),
+        (true, false) => flags.join(" "),
+        (false, false) => format!("{} ; {}", writes.join(", "), flags.join(" ")),
+    }
+}
+
+/// Bus wrapper that appends every store to an effect log before forwarding it.
+pub struct EffectLog<'a, B: Bus> {
+    pub inner: &'a mut B,
+    pub writes: Vec<Effect>,
+}
+
+impl<'a, B: Bus> EffectLog<'a, B> {
+    pub fn new(inner: &'a mut B) -> Self {
+        Self { inner, writes: Vec::new() }
+    }
+}
+
+impl<B: Bus> Bus for EffectLog<'_, B> {
+    fn read_u8(&mut self, addr: u32) -> Result<u8> { self.inner.read_u8(addr) }
+    fn read_u16(&mut self, addr: u32) -> Result<u16> { self.inner.read_u16(addr) }
+    fn read_u32(&mut self, addr: u32) -> Result<u32> { self.inner.read_u32(addr) }
+    fn write_u8(&mut self, addr: u32, val: u8) -> Result<()> {
+        self.inner.write_u8(addr, val)?;
+        self.writes.push(Effect::Mem { addr, width: 1, val: val as u32 });
+        Ok(())
+    }
+    fn write_u16(&mut self, addr: u32, val: u16) -> Result<()> {
+        self.inner.write_u16(addr, val)?;
+        self.writes.push(Effect::Mem { addr, width: 2, val: val as u32 });
+        Ok(())
+    }
+    fn write_u32(&mut self, addr: u32, val: u32) -> Result<()> {
+        self.inner.write_u32(addr, val)?;
+        self.writes.push(Effect::Mem { addr, width: 4, val });
+        Ok(())
+    }
+    fn fetch_u32(&mut self, addr: u32) -> Result<u32> { self.inner.fetch_u32(addr) }
+}
+
+/// Execute one instruction and report what it changed: D/A registers and
+/// PSW flags by diffing against the prior state (a write of the old value is
+/// not a change), memory from the store log. PC is not reported.
+pub fn step_with_effects<B: Bus, D: Decoder, X: Executor>(
+    cpu: &mut Cpu,
+    bus: &mut B,
+    dec: &D,
+    exec: &X,
+) -> Result<Vec<Effect>, Trap> {
+    let (gpr, a, psw) = (cpu.gpr, cpu.a, cpu.psw);
+    let mut log = EffectLog::new(bus);
+    cpu.step(&mut log, dec, exec)?;
+    let mut effects = Vec::new();
+    for (r, (&now, &before)) in cpu.gpr.iter().zip(gpr.iter()).enumerate() {
+        if now != before { effects.push(Effect::D { reg: r as u8, val: now }); }
+    }
+    for (r, (&now, &before)) in cpu.a.iter().zip(a.iter()).enumerate() {
+        if now != before { effects.push(Effect::A { reg: r as u8, val: now }); }
+    }
+    effects.extend(log.writes);
+    for (name, flag) in Psw::all().iter_names() {
+        if cpu.psw.contains(flag) != psw.contains(flag) {
+            effects.push(Effect::Flag { name, set: cpu.psw.contains(flag) });
+        }
+    }
+    Ok(effects)
+}
@@ -19,6 +19,24 @@ impl Executor for IntExecutor {
             Op::MovHA => {
                 cpu.a[d.rd as usize] = d.imm;
             }
+            Op::MovA => {
+                cpu.a[d.rd as usize] = cpu.gpr[d.rs1 as usize];
+            }
+            Op::MovD => {
+                cpu.gpr[d.rd as usize] = cpu.a[d.rs1 as usize];
+            }
+            Op::MovAA => {
+                cpu.a[d.rd as usize] = cpu.a[d.rs1 as usize];
+            }
+            Op::AddA | Op::AddAI => {
+                // Address arithmetic leaves PSW untouched
+                let b = if matches!(d.op, Op::AddAI) { d.imm } else { cpu.a[d.rs2 as usize] };
+                cpu.a[d.rd as usize] = cpu.a[d.rs1 as usize].wrapping_add(b);
+            }
+            Op::SubA => {
+                let b = if d.rs2 != 0 { cpu.a[d.rs2 as usize] } else { d.imm };
+                cpu.a[d.rd as usize] = cpu.a[d.rs1 as usize].wrapping_sub(b);
+            }
             Op::Lea => {
                 if d.abs {
                     cpu.a[d.rd as usize] = d.imm;
@@ -50,6 +68,28 @@ impl Executor for IntExecutor {
                 cpu.psw.set(Psw::AV, av);
                 if av { cpu.psw.insert(Psw::SAV); }
             }
+            Op::Adds | Op::AddsU | Op::Subs | Op::SubsU | Op::AddsI | Op::AddsUI => {
+                let a = cpu.gpr[d.rs1 as usize];
+                let b = if matches!(d.op, Op::AddsI | Op::AddsUI) { d.imm } else { cpu.gpr[d.rs2 as usize] };
+                // Full-precision result and the range it is clamped to
+                let (full, lo, hi) = match d.op {
+                    Op::Adds | Op::AddsI => (a as i32 as i64 + b as i32 as i64, i32::MIN as i64, i32::MAX as i64),
+                    Op::Subs => (a as i32 as i64 - b as i32 as i64, i32::MIN as i64, i32::MAX as i64),
+                    Op::AddsU | Op::AddsUI => (a as i64 + b as i64, 0, u32::MAX as i64),
+                    _ => (a as i64 - b as i64, 0, u32::MAX as i64),
+                };
+                let res = full.clamp(lo, hi) as u32;
+                cpu.gpr[d.rd as usize] = res;
+                cpu.psw.set(Psw::Z, res == 0);
+                cpu.psw.set(Psw::N, (res as i32) < 0);
+                // V/AV describe the unsaturated result; saturating ops leave C alone
+                let overflow = full < lo || full > hi;
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
+                let av = ((full >> 31) & 1) ^ ((full >> 30) & 1) == 1;
+                cpu.psw.set(Psw::AV, av);
+                if av { cpu.psw.insert(Psw::SAV); }
+            }
             Op::Addx => {
                 let a = cpu.gpr[d.rs1 as usize];
                 let b = if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm };
@@ -77,7 +117,8 @@ impl Executor for IntExecutor {
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
                 cpu.psw.set(Psw::C, carry);
-                let sum64 = (a as i64) + (b as i64) + (carry_in as i64);
+                // V from the signed operands, as for SUBC
+                let sum64 = (a as i32 as i64) + (b as i32 as i64) + (carry_in as i64);
                 let overflow = sum64 > i32::MAX as i64 || sum64 < i32::MIN as i64;
                 cpu.psw.set(Psw::V, overflow);
                 if overflow { cpu.psw.insert(Psw::SV); }
@@ -110,9 +151,10 @@ impl Executor for IntExecutor {
                 cpu.psw.set(Psw::N, (res as i32) < 0);
             }
             Op::Shl => {
+                // SH: count is the signed low 6 bits; negative shifts right (logical). C/V untouched.
                 let a = cpu.gpr[d.rs1 as usize];
-                let amt = (if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm }) & 31;
-                let res = a.wrapping_shl(amt);
+                let cnt = shift_count(if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm });
+                let res = if cnt >= 0 { a.checked_shl(cnt as u32).unwrap_or(0) } else { a.checked_shr((-cnt) as u32).unwrap_or(0) };
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
@@ -126,10 +168,23 @@ impl Executor for IntExecutor {
                 cpu.psw.set(Psw::N, (res as i32) < 0);
             }
             Op::Sar => {
+                // SHA: signed 6-bit count; positive shifts left, negative shifts right (arithmetic).
+                // C collects the bits shifted out; V flags a left shift that changed the sign-extended value.
                 let a = cpu.gpr[d.rs1 as usize] as i32;
-                let amt = ((if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm }) & 31) as u32;
-                let res = (a >> (amt as i32)) as u32;
+                let cnt = shift_count(if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm });
+                let (res, carry, ovf) = if cnt >= 0 {
+                    let wide = (a as i64) << cnt;
+                    let lost = if cnt == 0 { 0 } else { (a as u32 as u64) >> (32 - cnt) };
+                    (wide as u32, lost != 0, wide != (wide as i32) as i64)
+                } else {
+                    let n = (-cnt) as u32;
+                    let res = (if n >= 32 { a >> 31 } else { a >> n }) as u32;
+                    let lost = if n >= 32 { a as u32 } else { (a as u32) & ((1u32 << n) - 1) };
+                    (res, lost != 0, false)
+                };
                 cpu.gpr[d.rd as usize] = res;
+                cpu.psw.set(Psw::C, carry);
+                cpu.psw.set(Psw::V, ovf);
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
             }
@@ -191,35 +246,65 @@ impl Executor for IntExecutor {
             Op::Mul => {
                 let a = cpu.gpr[d.rs1 as usize] as i32 as i64;
                 let b = (if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm }) as i32 as i64;
-                let res = (a.wrapping_mul(b)) as i32 as u32;
+                let wide = a.wrapping_mul(b);
+                let res = wide as i32 as u32;
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
+                let overflow = wide != res as i32 as i64;
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
             }
             Op::MulU => {
                 let a = cpu.gpr[d.rs1 as usize] as u64;
                 let b = (if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] } else { d.imm }) as u64;
-                let res = a.wrapping_mul(b) as u32;
+                let wide = a.wrapping_mul(b);
+                let res = wide as u32;
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
+                let overflow = wide > u32::MAX as u64;
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
             }
             Op::Div => {
                 let a = cpu.gpr[d.rs1 as usize] as i32;
                 let b = cpu.gpr[d.rs2 as usize] as i32;
-                let res = if b == 0 { 0 } else { a.wrapping_div(b) as u32 };
+                if b == 0 { return Err(Trap::DivideByZero); }
+                // i32::MIN / -1 is the only signed quotient that does not fit
+                let (q, overflow) = a.overflowing_div(b);
+                let res = q as u32;
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
             }
             Op::DivU => {
                 let a = cpu.gpr[d.rs1 as usize];
                 let b = cpu.gpr[d.rs2 as usize];
-                let res = if b == 0 { 0 } else { a / b };
+                if b == 0 { return Err(Trap::DivideByZero); }
+                let res = a / b;
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
             }
+            Op::Absdif | Op::AbsdifI => {
+                let a = cpu.gpr[d.rs1 as usize] as i32 as i64;
+                let b = (if matches!(d.op, Op::AbsdifI) { d.imm } else { cpu.gpr[d.rs2 as usize] }) as i32 as i64;
+                let diff = (a - b).abs();
+                let res = diff as u32;
+                cpu.gpr[d.rd as usize] = res;
+                cpu.psw.set(Psw::Z, res == 0);
+                cpu.psw.set(Psw::N, (res as i32) < 0);
+                // |i32::MIN - x| can exceed i32::MAX; the result wraps and V/SV flag it
+                let overflow = diff > i32::MAX as i64;
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
+                let av = ((res >> 31) & 1) ^ ((res >> 30) & 1) == 1;
+                cpu.psw.set(Psw::AV, av);
+                if av { cpu.psw.insert(Psw::SAV); }
+            }
             Op::BeqF => {
                 if cpu.psw.contains(Psw::Z) { cpu.pc = cpu.pc.wrapping_add(d.imm); }
             }
@@ -241,9 +326,8 @@ impl Executor for IntExecutor {
                 if cpu.psw.contains(Psw::C) { cpu.pc = cpu.pc.wrapping_add(d.imm); }
             }
             Op::BltUF => {
-                let c = cpu.psw.contains(Psw::C);
-                let z = cpu.psw.contains(Psw::Z);
-                if !c && !z { cpu.pc = cpu.pc.wrapping_add(d.imm); }
+                // C==0 means the compare borrowed => a<b
+                if !cpu.psw.contains(Psw::C) { cpu.pc = cpu.pc.wrapping_add(d.imm); }
             }
             Op::Cmp | Op::CmpI => {
                 // Signed compare: set flags based on (rs1 - operand)
@@ -268,30 +352,92 @@ impl Executor for IntExecutor {
                 cpu.psw.set(Psw::C, !borrow);
                 cpu.psw.set(Psw::V, false);
             }
-            Op::Sub => {
-                let a = cpu.gpr[d.rs1 as usize];
-                let (res, borrow) = if d.rs2 != 0 {
-                    let b = cpu.gpr[d.rs2 as usize];
-                    a.overflowing_sub(b)
-                } else {
-                    // RSUB immediate form: imm - a
-                    let (r, b) = (d.imm).overflowing_sub(a);
-                    (r, b)
+            Op::Sub | Op::Subx | Op::Rsub => {
+                // RSUB reverses the operands: const9 - D[a]
+                let (x, y) = match d.op {
+                    Op::Rsub => (d.imm, cpu.gpr[d.rs1 as usize]),
+                    _ => (cpu.gpr[d.rs1 as usize], cpu.gpr[d.rs2 as usize]),
                 };
+                let (res, borrow) = x.overflowing_sub(y);
                 cpu.gpr[d.rd as usize] = res;
                 cpu.psw.set(Psw::Z, res == 0);
                 cpu.psw.set(Psw::N, (res as i32) < 0);
-                cpu.psw.set(Psw::C, borrow); // check exact meaning vs TriCore
-                let a_s = a as i32;
-                let b_s = if d.rs2 != 0 { cpu.gpr[d.rs2 as usize] as i32 } else { d.imm as i32 };
-                let r_s = res as i32;
-                let overflow = ((a_s ^ b_s) & (a_s ^ r_s)) < 0;
+                // TriCore subtracts as x + !y + 1 and C is that addition's
+                // carry-out: set when there is no borrow (5 - 3), clear when
+                // there is (3 - 5). This is the inverse of Rust's borrow, and
+                // the same sense as CMP and the unsigned flag branches. The
+                // manual only has SUBX/SUBC write C; SUB and RSUB do here too
+                // so a `sub`/`subc` chain works.
+                cpu.psw.set(Psw::C, !borrow);
+                let overflow = (((x ^ y) & (x ^ res)) as i32) < 0;
+                cpu.psw.set(Psw::V, overflow);
+                if overflow { cpu.psw.insert(Psw::SV); }
+                let av = ((res >> 31) & 1) ^ ((res >> 30) & 1) == 1;
+                cpu.psw.set(Psw::AV, av);
+                if av { cpu.psw.insert(Psw::SAV); }
+            }
+            Op::Subc => {
+                // a + !b + C, the carry-in form of SUB: C clear means a borrow
+                // is pending. C out is the carry, like ADDC.
+                let a = cpu.gpr[d.rs1 as usize];
+                let b = cpu.gpr[d.rs2 as usize];
+                let carry_in = if cpu.psw.contains(Psw::C) { 1u32 } else { 0 };
+                let (tmp, c1) = a.overflowing_add(!b);
+                let (res, c2) = tmp.overflowing_add(carry_in);
+                cpu.gpr[d.rd as usize] = res;
+                cpu.psw.set(Psw::Z, res == 0);
+                cpu.psw.set(Psw::N, (res as i32) < 0);
+                cpu.psw.set(Psw::C, c1 || c2);
+                let diff64 = (a as i32 as i64) - (b as i32 as i64) - (1 - carry_in as i64);
+                let overflow = diff64 > i32::MAX as i64 || diff64 < i32::MIN as i64;
                 cpu.psw.set(Psw::V, overflow);
                 if overflow { cpu.psw.insert(Psw::SV); }
                 let av = ((res >> 31) & 1) ^ ((res >> 30) & 1) == 1;
                 cpu.psw.set(Psw::AV, av);
                 if av { cpu.psw.insert(Psw::SAV); }
             }
+            Op::Sel | Op::Seln | Op::Cadd | Op::Csub | Op::SelI | Op::SelnI | Op::CaddI => {
+                // The condition is D[d] != 0 (d in imm2); PSW is left as is
+                let cond = cpu.gpr[d.imm2 as usize & 0xF] != 0;
+                let a = cpu.gpr[d.rs1 as usize];
+                let b = if matches!(d.op, Op::SelI | Op::SelnI | Op::CaddI) { d.imm } else { cpu.gpr[d.rs2 as usize] };
+                cpu.gpr[d.rd as usize] = match (d.op, cond) {
+                    (Op::Sel | Op::SelI, true) | (Op::Seln | Op::SelnI, false) => a,
+                    (Op::Sel | Op::SelI, false) | (Op::Seln | Op::SelnI, true) => b,
+                    (Op::Cadd | Op::CaddI, true) => a.wrapping_add(b),
+                    (Op::Csub, true) => a.wrapping_sub(b),
+                    _ => a,
+                };
+            }
+            Op::Extr | Op::ExtrU | Op::Insert => {
+                // Width 0 is an empty field (EXTR gives 0, INSERT changes
+                // nothing); a field running past bit 31 is cut off there
+                let (pos, width) = (d.imm & 0x1F, d.imm2 & 0x1F);
+                let mask = (((1u64 << width) - 1) << pos) as u32;
+                let a = cpu.gpr[d.rs1 as usize];
+                cpu.gpr[d.rd as usize] = match d.op {
+                    Op::Insert => (a & !mask) | ((cpu.gpr[d.rs2 as usize] << pos) & mask),
+                    _ => {
+                        let field = (a & mask) >> pos;
+                        // Sign-extend from the top bit that is actually in the field
+                        let bits = width.min(32 - pos);
+                        if matches!(d.op, Op::Extr) && bits > 0 && (field >> (bits - 1)) & 1 == 1 { field | (u32::MAX << bits) } else { field }
+                    }
+                };
+            }
+            Op::Dextr => {
+                let wide = ((cpu.gpr[d.rs1 as usize] as u64) << 32) | cpu.gpr[d.rs2 as usize] as u64;
+                cpu.gpr[d.rd as usize] = ((wide << (d.imm & 0x1F)) >> 32) as u32;
+            }
+            Op::Clz | Op::Clo | Op::Cls => {
+                let a = cpu.gpr[d.rs1 as usize];
+                cpu.gpr[d.rd as usize] = match d.op {
+                    Op::Clz => a.leading_zeros(),
+                    Op::Clo => a.leading_ones(),
+                    // Sign bits after the sign itself: 0 for 0x40000000, 31 for 0 and -1
+                    _ => if (a as i32) < 0 { a.leading_ones() - 1 } else { a.leading_zeros() - 1 },
+                };
+            }
             Op::LdW => {
                 let base = cpu.a[d.rs1 as usize];
                 let addr = if d.abs {
@@ -306,13 +452,35 @@ impl Executor for IntExecutor {
                 }
                 let val = bus
                     .read_u32(addr)
-                    .map_err(|source| Trap::Bus { addr, source })?;
+                    .map_err(|source| Trap::bus(addr, source))?;
                 cpu.gpr[d.rd as usize] = val;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
                     cpu.a[d.rs1 as usize] = new_base;
                 }
             }
+            Op::LdD => {
+                let base = cpu.a[d.rs1 as usize];
+                let addr = if d.abs {
+                    d.imm
+                } else if d.wb {
+                    if d.pre { base.wrapping_add(d.imm) } else { base }
+                } else {
+                    base.wrapping_add(d.imm)
+                };
+                if addr % 8 != 0 {
+                    return Err(Trap::Unaligned { addr });
+                }
+                let lo = bus.read_u32(addr).map_err(|source| Trap::bus(addr, source))?;
+                let hi_addr = addr.wrapping_add(4);
+                let hi = bus.read_u32(hi_addr).map_err(|source| Trap::bus(hi_addr, source))?;
+                cpu.gpr[d.rd as usize] = lo;
+                cpu.gpr[(d.rd | 1) as usize] = hi;
+                if !d.abs && d.wb {
+                    let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
+                    cpu.a[d.rs1 as usize] = new_base;
+                }
+            }
             Op::LdWPbr => {
                 // Bit-reverse addressing: index/incr in A[b+1]
                 let b = d.rs1 as usize;
@@ -322,7 +490,7 @@ impl Executor for IntExecutor {
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 if ea % 4 != 0 { return Err(Trap::Unaligned { addr: ea }); }
-                let val = bus.read_u32(ea).map_err(|source| Trap::Bus { addr: ea, source })?;
+                let val = bus.read_u32(ea).map_err(|source| Trap::bus(ea, source))?;
                 cpu.gpr[d.rd as usize] = val;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
@@ -335,7 +503,7 @@ impl Executor for IntExecutor {
                 let index = (ab1 & 0xFFFF) as u32;
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
-                let val = bus.read_u8(ea).map_err(|source| Trap::Bus { addr: ea, source })? as i8 as i32 as u32;
+                let val = bus.read_u8(ea).map_err(|source| Trap::bus(ea, source))? as i8 as i32 as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
@@ -348,7 +516,7 @@ impl Executor for IntExecutor {
                 let index = (ab1 & 0xFFFF) as u32;
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
-                let val = bus.read_u8(ea).map_err(|source| Trap::Bus { addr: ea, source })? as u32;
+                let val = bus.read_u8(ea).map_err(|source| Trap::bus(ea, source))? as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
@@ -362,7 +530,7 @@ impl Executor for IntExecutor {
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
-                let val = bus.read_u16(ea).map_err(|source| Trap::Bus { addr: ea, source })? as i16 as i32 as u32;
+                let val = bus.read_u16(ea).map_err(|source| Trap::bus(ea, source))? as i16 as i32 as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
@@ -376,7 +544,7 @@ impl Executor for IntExecutor {
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
-                let val = bus.read_u16(ea).map_err(|source| Trap::Bus { addr: ea, source })? as u32;
+                let val = bus.read_u16(ea).map_err(|source| Trap::bus(ea, source))? as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
@@ -389,7 +557,7 @@ impl Executor for IntExecutor {
                 let index = (ab1 & 0xFFFF) as u32;
                 let length = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
-                let val = bus.read_u8(ea).map_err(|source| Trap::Bus { addr: ea, source })? as i8 as i32 as u32;
+                let val = bus.read_u8(ea).map_err(|source| Trap::bus(ea, source))? as i8 as i32 as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
@@ -405,7 +573,7 @@ impl Executor for IntExecutor {
                 let index = (ab1 & 0xFFFF) as u32;
                 let length = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
-                let val = bus.read_u8(ea).map_err(|source| Trap::Bus { addr: ea, source })? as u32;
+                let val = bus.read_u8(ea).map_err(|source| Trap::bus(ea, source))? as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
@@ -422,7 +590,7 @@ impl Executor for IntExecutor {
                 let length = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
-                let val = bus.read_u16(ea).map_err(|source| Trap::Bus { addr: ea, source })? as i16 as i32 as u32;
+                let val = bus.read_u16(ea).map_err(|source| Trap::bus(ea, source))? as i16 as i32 as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
@@ -439,7 +607,7 @@ impl Executor for IntExecutor {
                 let length = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
-                let val = bus.read_u16(ea).map_err(|source| Trap::Bus { addr: ea, source })? as u32;
+                let val = bus.read_u16(ea).map_err(|source| Trap::bus(ea, source))? as u32;
                 cpu.gpr[d.rd as usize] = val;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
@@ -458,8 +626,8 @@ impl Executor for IntExecutor {
                 let ea0 = ab.wrapping_add(index);
                 let ea2 = ab.wrapping_add(if length != 0 { (index + 2) % length } else { index + 2 });
                 if ea0 % 2 != 0 || ea2 % 2 != 0 { return Err(Trap::Unaligned { addr: if ea0 % 2 != 0 { ea0 } else { ea2 } }); }
-                let lo = bus.read_u16(ea0).map_err(|source| Trap::Bus { addr: ea0, source })? as u32;
-                let hi = bus.read_u16(ea2).map_err(|source| Trap::Bus { addr: ea2, source })? as u32;
+                let lo = bus.read_u16(ea0).map_err(|source| Trap::bus(ea0, source))? as u32;
+                let hi = bus.read_u16(ea2).map_err(|source| Trap::bus(ea2, source))? as u32;
                 cpu.gpr[d.rd as usize] = (hi << 16) | lo;
                 // update index
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
@@ -484,7 +652,28 @@ impl Executor for IntExecutor {
                 let val = cpu.gpr[d.rs2 as usize];
                 bus
                     .write_u32(addr, val)
-                    .map_err(|source| Trap::Bus { addr, source })?;
+                    .map_err(|source| Trap::bus(addr, source))?;
+                if !d.abs && d.wb {
+                    let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
+                    cpu.a[d.rs1 as usize] = new_base;
+                }
+            }
+            Op::StD => {
+                let base = cpu.a[d.rs1 as usize];
+                let addr = if d.abs {
+                    d.imm
+                } else if d.wb {
+                    if d.pre { base.wrapping_add(d.imm) } else { base }
+                } else {
+                    base.wrapping_add(d.imm)
+                };
+                if addr % 8 != 0 {
+                    return Err(Trap::Unaligned { addr });
+                }
+                let (lo, hi) = (cpu.gpr[d.rs2 as usize], cpu.gpr[(d.rs2 | 1) as usize]);
+                bus.write_u32(addr, lo).map_err(|source| Trap::bus(addr, source))?;
+                let hi_addr = addr.wrapping_add(4);
+                bus.write_u32(hi_addr, hi).map_err(|source| Trap::bus(hi_addr, source))?;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
                     cpu.a[d.rs1 as usize] = new_base;
@@ -499,7 +688,7 @@ impl Executor for IntExecutor {
                 let ea = ab.wrapping_add(index);
                 if ea % 4 != 0 { return Err(Trap::Unaligned { addr: ea }); }
                 let val = cpu.gpr[d.rs2 as usize];
-                bus.write_u32(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u32(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
                 cpu.a[(b + 1) & 0xF] = ((incr & 0xFFFF) << 16) | new_index;
@@ -512,7 +701,7 @@ impl Executor for IntExecutor {
                 let incr = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFF) as u8;
-                bus.write_u8(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u8(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
                 cpu.a[(b + 1) & 0xF] = ((incr & 0xFFFF) << 16) | new_index;
@@ -526,7 +715,7 @@ impl Executor for IntExecutor {
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFFFF) as u16;
-                bus.write_u16(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u16(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let rev = |x: u32| -> u32 { (x as u16).reverse_bits() as u32 };
                 let new_index = rev(rev(index).wrapping_add(rev(incr))) & 0xFFFF;
                 cpu.a[(b + 1) & 0xF] = ((incr & 0xFFFF) << 16) | new_index;
@@ -539,7 +728,7 @@ impl Executor for IntExecutor {
                 let length = (ab1 >> 16) as u32;
                 let ea = ab.wrapping_add(index);
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFF) as u8;
-                bus.write_u8(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u8(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
                     if new_index < 0 { new_index += length as i32; }
@@ -556,7 +745,7 @@ impl Executor for IntExecutor {
                 let ea = ab.wrapping_add(index);
                 if ea % 2 != 0 { return Err(Trap::Unaligned { addr: ea }); }
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFFFF) as u16;
-                bus.write_u16(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u16(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
                     if new_index < 0 { new_index += length as i32; }
@@ -573,7 +762,7 @@ impl Executor for IntExecutor {
                 let ea = ab.wrapping_add(index);
                 if ea % 4 != 0 { return Err(Trap::Unaligned { addr: ea }); }
                 let val = cpu.gpr[d.rs2 as usize];
-                bus.write_u32(ea, val).map_err(|source| Trap::Bus { addr: ea, source })?;
+                bus.write_u32(ea, val).map_err(|source| Trap::bus(ea, source))?;
                 let mut new_index = (index as i32).wrapping_add(d.imm as i32);
                 if length != 0 {
                     if new_index < 0 { new_index += length as i32; }
@@ -586,7 +775,7 @@ impl Executor for IntExecutor {
                 let addr = if d.abs { d.imm } else if d.wb { if d.pre { base.wrapping_add(d.imm) } else { base } } else { base.wrapping_add(d.imm) };
                 let v = bus
                     .read_u8(addr)
-                    .map_err(|source| Trap::Bus { addr, source })? as i8 as i32 as u32;
+                    .map_err(|source| Trap::bus(addr, source))? as i8 as i32 as u32;
                 cpu.gpr[d.rd as usize] = v;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
@@ -598,7 +787,7 @@ impl Executor for IntExecutor {
                 let addr = if d.abs { d.imm } else if d.wb { if d.pre { base.wrapping_add(d.imm) } else { base } } else { base.wrapping_add(d.imm) };
                 let v = bus
                     .read_u8(addr)
-                    .map_err(|source| Trap::Bus { addr, source })? as u32;
+                    .map_err(|source| Trap::bus(addr, source))? as u32;
                 cpu.gpr[d.rd as usize] = v;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
@@ -613,7 +802,7 @@ impl Executor for IntExecutor {
                 }
                 let v = bus
                     .read_u16(addr)
-                    .map_err(|source| Trap::Bus { addr, source })? as i16 as i32 as u32;
+                    .map_err(|source| Trap::bus(addr, source))? as i16 as i32 as u32;
                 cpu.gpr[d.rd as usize] = v;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
@@ -628,7 +817,7 @@ impl Executor for IntExecutor {
                 }
                 let v = bus
                     .read_u16(addr)
-                    .map_err(|source| Trap::Bus { addr, source })? as u32;
+                    .map_err(|source| Trap::bus(addr, source))? as u32;
                 cpu.gpr[d.rd as usize] = v;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
@@ -647,7 +836,7 @@ impl Executor for IntExecutor {
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFF) as u8;
                 bus
                     .write_u8(addr, val)
-                    .map_err(|source| Trap::Bus { addr, source })?;
+                    .map_err(|source| Trap::bus(addr, source))?;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
                     cpu.a[d.rs1 as usize] = new_base;
@@ -668,7 +857,7 @@ impl Executor for IntExecutor {
                 let val = (cpu.gpr[d.rs2 as usize] & 0xFFFF) as u16;
                 bus
                     .write_u16(addr, val)
-                    .map_err(|source| Trap::Bus { addr, source })?;
+                    .map_err(|source| Trap::bus(addr, source))?;
                 if !d.abs && d.wb {
                     let new_base = if d.pre { addr } else { addr.wrapping_add(d.imm) };
                     cpu.a[d.rs1 as usize] = new_base;
@@ -721,6 +910,16 @@ impl Executor for IntExecutor {
                     cpu.pc = cpu.pc.wrapping_add(off as u32);
                 }
             }
+            Op::Loop => {
+                // Test before decrementing: a zero count falls through once and wraps A[b]
+                let b = d.rs1 as usize;
+                let taken = cpu.a[b] != 0;
+                cpu.a[b] = cpu.a[b].wrapping_sub(1);
+                if taken { cpu.pc = cpu.pc.wrapping_add(d.imm); }
+            }
+            Op::LoopU => {
+                cpu.pc = cpu.pc.wrapping_add(d.imm);
+            }
             Op::JzA => {
                 let off = d.imm as i32;
                 if cpu.a[d.rs1 as usize] == 0 {
@@ -804,6 +1003,20 @@ impl Executor for IntExecutor {
                     _ => {}
                 }
             }
+            Op::Ji => {
+                cpu.pc = cpu.a[d.rs1 as usize] & !1;
+            }
+            Op::Jli => {
+                // Read the target first: JLI A11 jumps to the old A11
+                let tgt = cpu.a[d.rs1 as usize] & !1;
+                cpu.a[11] = cpu.pc; // already advanced PC
+                cpu.pc = tgt;
+            }
+            Op::AddscA => {
+                // Index register is always D[rs2] here (D0 included), never an immediate
+                let idx = cpu.gpr[d.rs2 as usize] << d.imm;
+                cpu.a[d.rd as usize] = cpu.a[d.rs1 as usize].wrapping_add(idx);
+            }
             Op::Ret => {
                 if let Some(ret) = cpu.call_stack.pop() {
                     cpu.pc = ret;
@@ -814,3 +1027,8 @@ impl Executor for IntExecutor {
         Ok(())
     }
 }
+
+/// Sign-extend the low 6 bits of a shift operand (SH/SHA count range -32..=31).
+fn shift_count(v: u32) -> i32 {
+    ((v << 26) as i32) >> 26
+}
@@ -1,4 +1,4 @@
-use crate::decoder::{Decoded, Decoder, Op};
+use crate::decoder::{DecodeError, DecodeReason, Decoded, Decoder, Op};
 
 /// TriCore TC1.6.2 decoder (initial subset)
 /// Implements a small, representative slice of the official encodings
@@ -13,12 +13,23 @@ impl Tc16Decoder {
 
 impl Decoder for Tc16Decoder {
     fn decode(&self, raw32: u32) -> Option<Decoded> {
+        self.decode_full(raw32).ok()
+    }
+
+    /// op1 bit 0 clear is a 16-bit encoding, set a 32-bit one
+    fn width_hint(&self, first_byte: u8) -> u8 {
+        if first_byte & 1 == 0 { 2 } else { 4 }
+    }
+
+    fn decode_full(&self, raw32: u32) -> Result<Decoded, DecodeError> {
         // Helper closures
         #[inline]
         fn sign_ext(v: u32, bits: u32) -> u32 {
             let s = 32 - bits;
             ((v << s) as i32 >> s) as u32
         }
+        // ABS format: [31:28] off18[9:6], [27:26] op2, [25:22] off18[13:10],
+        // [21:16] off18[5:0], [15:12] off18[17:14], [11:8] a, [7:0] op1.
         #[inline]
         fn off18_from_fields(raw32: u32) -> u32 {
             let off9_6 = (raw32 >> 28) & 0xF; // off18[9:6]
@@ -27,6 +38,7 @@ impl Decoder for Tc16Decoder {
             let off17_14 = (raw32 >> 12) & 0xF; // off18[17:14]
             (off17_14 << 14) | (off13_10 << 10) | (off9_6 << 6) | off5_0
         }
+        // EA = {off18[17:14], 14'b0, off18[13:0]}
         #[inline]
         fn abs_ea_from_off18(off18: u32) -> u32 {
             let top4 = (off18 >> 14) & 0xF;
@@ -37,6 +49,9 @@ impl Decoder for Tc16Decoder {
         // op1 is the low byte of the instruction word; bit 0 distinguishes width
         let op1 = (raw32 & 0xFF) as u8;
         let is_16 = (op1 & 1) == 0;
+        let unknown_op1 = || DecodeError { op1, op2: None, reason: DecodeReason::UnknownOp1 };
+        let unknown_op2 = |op2: u32| DecodeError { op1, op2: Some(op2), reason: DecodeReason::UnknownOp2 };
+        let odd_pair = |op2: u32| DecodeError { op1, op2: Some(op2), reason: DecodeReason::OddRegisterPair };
 
         if is_16 {
             let raw16 = (raw32 & 0xFFFF) as u16;
@@ -45,19 +60,32 @@ impl Decoder for Tc16Decoder {
                     // CALL disp8 (SB)
                     let disp8 = ((raw16 >> 8) & 0xFF) as u32;
                     let off = sign_ext(disp8, 8) << 1;
-                    return Some(Decoded { op: Op::Call, width: 2, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::Call, width: 2, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0xFC => {
+                    // LOOP A[b], disp4 (SBR): backward only, disp = {1..1, disp4, 0}
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let disp4 = ((raw16 >> 8) & 0xF) as u32;
+                    let off = 0xFFFF_FFE0 | (disp4 << 1);
+                    return Ok(Decoded { op: Op::Loop, width: 2, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0xDC => {
+                    // JI A[a] (SR, op2 0x0 in [15:12])
+                    if (raw16 >> 12) & 0xF != 0 { return Err(unknown_op2(((raw16 >> 12) & 0xF) as u32)); }
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::Ji, width: 2, rd: 0, rs1: a, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0x3C => {
                     // J disp8 (SB)
                     let disp8 = ((raw16 >> 8) & 0xFF) as u32;
                     let off = sign_ext(disp8, 8) << 1;
-                    return Some(Decoded { op: Op::J, width: 2, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::J, width: 2, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0x42 => {
                     // ADD D[a], D[b] (SRR)
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded {
+                    return Ok(Decoded {
                         op: Op::Add,
                         width: 2,
                         rd: a,
@@ -74,7 +102,7 @@ impl Decoder for Tc16Decoder {
                     // ADD D[a], const4 (SRC)
                     let const4 = ((raw16 >> 12) & 0xF) as u32;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded {
+                    return Ok(Decoded {
                         op: Op::Add,
                         width: 2,
                         rd: a,
@@ -91,7 +119,7 @@ impl Decoder for Tc16Decoder {
                     // MOV D[a], const4 (SRC)
                     let const4 = ((raw16 >> 12) & 0xF) as u32;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded {
+                    return Ok(Decoded {
                         op: Op::MovI,
                         width: 2,
                         rd: a,
@@ -104,23 +132,89 @@ impl Decoder for Tc16Decoder {
                         pre: false,
                     });
                 }
+                0x12 | 0x1A | 0x52 | 0x5A | 0xA2 => {
+                    // ADD/SUB with D15 (SRR): 0x12/0x52 D[a] = D15 op D[b],
+                    // 0x1A/0x5A D15 = D[a] op D[b], 0xA2 SUB D[a], D[b]
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    let op = if op1 & 0xF0 == 0x10 { Op::Add } else { Op::Sub };
+                    let (rd, rs1) = match op1 {
+                        0x12 | 0x52 => (a, 15),
+                        0x1A | 0x5A => (15, a),
+                        _ => (a, a),
+                    };
+                    return Ok(Decoded { op, width: 2, rd, rs1, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x92 | 0x9A => {
+                    // ADD D[a], D15, const4 (0x92) / ADD D15, D[a], const4 (0x9A) (SRC)
+                    let const4 = ((raw16 >> 12) & 0xF) as u32;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    let (rd, rs1) = if op1 == 0x92 { (a, 15) } else { (15, a) };
+                    return Ok(Decoded { op: Op::Add, width: 2, rd, rs1, rs2: 0, imm: sign_ext(const4, 4), imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x02 => {
+                    // MOV D[a], D[b] (SRR)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::Mov, width: 2, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0xDA => {
+                    // MOV D15, const8 (SC), zero-extended
+                    let const8 = ((raw16 >> 8) & 0xFF) as u32;
+                    return Ok(Decoded { op: Op::MovI, width: 2, rd: 15, rs1: 0, rs2: 0, imm: const8, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x60 => {
+                    // MOV.A A[a], D[b] (SRR)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::MovA, width: 2, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x80 => {
+                    // MOV.D D[a], A[b] (SRR)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::MovD, width: 2, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x40 => {
+                    // MOV.AA A[a], A[b] (SRR)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::MovAA, width: 2, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0xB0 => {
+                    // ADD.A A[a], const4 (SRC)
+                    let const4 = ((raw16 >> 12) & 0xF) as u32;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::AddAI, width: 2, rd: a, rs1: a, rs2: 0, imm: sign_ext(const4, 4), imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x30 => {
+                    // ADD.A A[a], A[b] (SRR)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    return Ok(Decoded { op: Op::AddA, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x20 => {
+                    // SUB.A A[10], const8 (SC), const8 zero-extended
+                    let const8 = ((raw16 >> 8) & 0xFF) as u32;
+                    return Ok(Decoded { op: Op::SubA, width: 2, rd: 10, rs1: 10, rs2: 0, imm: const8, imm2: 0, abs: false, wb: false, pre: false });
+                }
                 0x26 => {
                     // AND D[a], D[b] (SRR)
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded { op: Op::And, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::And, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0xA6 => {
                     // OR D[a], D[b] (SRR)
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded { op: Op::Or, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::Or, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0xC6 => {
                     // XOR D[a], D[b] (SRR)
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let a = ((raw16 >> 8) & 0xF) as u8;
-                    return Some(Decoded { op: Op::Xor, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::Xor, width: 2, rd: a, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0x1E | 0x9E => {
                     // JEQ D[15], const4, disp4 (SBC)
@@ -128,7 +222,7 @@ impl Decoder for Tc16Decoder {
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let add16 = if op1 == 0x9E { 16 } else { 0 };
                     let off = ((disp4 + add16) << 1) as u32;
-                    return Some(Decoded { op: Op::JeqImm, width: 2, rd: 0, rs1: 15, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::JeqImm, width: 2, rd: 0, rs1: 15, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false });
                 }
                 0x5E | 0xDE => {
                     // JNE D[15], const4, disp4 (SBC)
@@ -136,7 +230,7 @@ impl Decoder for Tc16Decoder {
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let add16 = if op1 == 0xDE { 16 } else { 0 };
                     let off = ((disp4 + add16) << 1) as u32;
-                    return Some(Decoded { op: Op::JneImm, width: 2, rd: 0, rs1: 15, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::JneImm, width: 2, rd: 0, rs1: 15, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false });
                 }
                 0x3E | 0xBE => {
                     // JEQ D[15], D[b], disp4 (SBR)
@@ -144,7 +238,7 @@ impl Decoder for Tc16Decoder {
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let add16 = if op1 == 0xBE { 16 } else { 0 };
                     let off = ((disp4 + add16) << 1) as u32;
-                    return Some(Decoded {
+                    return Ok(Decoded {
                         op: Op::Jeq,
                         width: 2,
                         rd: 0,
@@ -163,7 +257,7 @@ impl Decoder for Tc16Decoder {
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let add16 = if op1 == 0xFE { 16 } else { 0 };
                     let off = ((disp4 + add16) << 1) as u32;
-                    return Some(Decoded {
+                    return Ok(Decoded {
                         op: Op::Jne,
                         width: 2,
                         rd: 0,
@@ -181,34 +275,96 @@ impl Decoder for Tc16Decoder {
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let off = (disp4 << 1) as u32; // zero-extended
-                    return Some(Decoded { op: Op::JzA, width: 2, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::JzA, width: 2, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
                 }
                 0x7C => {
                     // JNZ.A A[b], disp4 (SBR)
                     let b = ((raw16 >> 12) & 0xF) as u8;
                     let disp4 = ((raw16 >> 8) & 0xF) as u32;
                     let off = (disp4 << 1) as u32; // zero-extended
-                    return Some(Decoded { op: Op::JnzA, width: 2, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                    return Ok(Decoded { op: Op::JnzA, width: 2, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x14 | 0x94 | 0x54 | 0x04 | 0x84 | 0x44 => {
+                    // LD.BU/LD.H/LD.W D[c], [A[b]] (SLR); the 0x_4 forms are [A[b]+],
+                    // post-incrementing by the access size
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let c = ((raw16 >> 8) & 0xF) as u8;
+                    let (op, size) = match op1 & 0xF0 {
+                        0x10 | 0x00 => (Op::LdBu, 1),
+                        0x90 | 0x80 => (Op::LdH, 2),
+                        _ => (Op::LdW, 4),
+                    };
+                    let post = matches!(op1, 0x04 | 0x84 | 0x44);
+                    let imm = if post { size } else { 0 };
+                    return Ok(Decoded { op, width: 2, rd: c, rs1: b, rs2: 0, imm, imm2: 0, abs: false, wb: post, pre: false });
+                }
+                0x34 | 0xB4 | 0x74 | 0x24 | 0xA4 | 0x64 => {
+                    // ST.B/ST.H/ST.W [A[b]], D[a] (SSR); the 0x_4 forms are [A[b]+]
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let a = ((raw16 >> 8) & 0xF) as u8;
+                    let (op, size) = match op1 & 0xF0 {
+                        0x30 | 0x20 => (Op::StB, 1),
+                        0xB0 | 0xA0 => (Op::StH, 2),
+                        _ => (Op::StW, 4),
+                    };
+                    let post = matches!(op1, 0x24 | 0xA4 | 0x64);
+                    let imm = if post { size } else { 0 };
+                    return Ok(Decoded { op, width: 2, rd: 0, rs1: b, rs2: a, imm, imm2: 0, abs: false, wb: post, pre: false });
+                }
+                0x0C | 0x8C | 0x4C | 0x2C | 0xAC | 0x6C => {
+                    // LD.BU/LD.H/LD.W D15 and ST.B/ST.H/ST.W D15 at [A[b]] + off4
+                    // scaled by the access size (SRO)
+                    let b = ((raw16 >> 12) & 0xF) as u8;
+                    let off4 = ((raw16 >> 8) & 0xF) as u32;
+                    let (op, size, load) = match op1 {
+                        0x0C => (Op::LdBu, 1, true),
+                        0x8C => (Op::LdH, 2, true),
+                        0x4C => (Op::LdW, 4, true),
+                        0x2C => (Op::StB, 1, false),
+                        0xAC => (Op::StH, 2, false),
+                        _ => (Op::StW, 4, false),
+                    };
+                    let (rd, rs2) = if load { (15, 0) } else { (0, 15) };
+                    return Ok(Decoded { op, width: 2, rd, rs1: b, rs2, imm: off4 * size, imm2: 0, abs: false, wb: false, pre: false });
+                }
+                0x08 | 0x88 | 0x48 | 0x28 | 0xA8 | 0x68 => {
+                    // LD.BU/LD.H/LD.W D[c] and ST.B/ST.H/ST.W D[a] at [A15] + off4
+                    // scaled by the access size (SLRO/SSRO)
+                    let off4 = ((raw16 >> 12) & 0xF) as u32;
+                    let r = ((raw16 >> 8) & 0xF) as u8;
+                    let (op, size, load) = match op1 {
+                        0x08 => (Op::LdBu, 1, true),
+                        0x88 => (Op::LdH, 2, true),
+                        0x48 => (Op::LdW, 4, true),
+                        0x28 => (Op::StB, 1, false),
+                        0xA8 => (Op::StH, 2, false),
+                        _ => (Op::StW, 4, false),
+                    };
+                    let (rd, rs2) = if load { (r, 0) } else { (0, r) };
+                    return Ok(Decoded { op, width: 2, rd, rs1: 15, rs2, imm: off4 * size, imm2: 0, abs: false, wb: false, pre: false });
                 }
-                _ => return None,
+                _ => return Err(unknown_op1()),
             }
         }
 
         // 32-bit encodings (op1 bit0 == 1)
         match op1 {
             0x4D => {
-                // Flag-based branches (pseudo): cond in [31:30], disp15 in [29:15]
+                // Flag-based branches (pseudo): cond in [31:30], disp15 in [29:15], bit 8 selects unsigned ge/lt
                 let cond = ((raw32 >> 30) & 0x3) as u32;
+                let unsigned = (raw32 >> 8) & 1 != 0;
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
-                let op = match cond {
-                    0x0 => Op::BeqF,
-                    0x1 => Op::BneF,
-                    0x2 => Op::BgeF,
-                    0x3 => Op::BltF,
+                let op = match (cond, unsigned) {
+                    (0x0, _) => Op::BeqF,
+                    (0x1, _) => Op::BneF,
+                    (0x2, false) => Op::BgeF,
+                    (0x3, false) => Op::BltF,
+                    (0x2, true) => Op::BgeUF,
+                    (0x3, true) => Op::BltUF,
                     _ => Op::BeqF,
                 };
-                return Some(Decoded { op, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x6D => {
                 // CALL disp24 (B)
@@ -216,7 +372,7 @@ impl Decoder for Tc16Decoder {
                 let disp_hi8 = ((raw32 >> 8) & 0xFF) as u32;
                 let disp24 = (disp_hi8 << 16) | disp_low16;
                 let off = sign_ext(disp24, 24) << 1;
-                return Some(Decoded { op: Op::Call, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Call, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
             }
             0xED => {
                 // CALLA disp24 (B)
@@ -226,16 +382,37 @@ impl Decoder for Tc16Decoder {
                 let top4 = (disp24 >> 20) & 0xF;
                 let low20 = disp24 & 0xFFFFF;
                 let ea = (top4 << 28) | (low20 << 1);
-                return Some(Decoded { op: Op::CallA, width: 4, rd: 0, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op: Op::CallA, width: 4, rd: 0, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
             }
             0x2D => {
-                // CALLI A[a] (RR)
+                // CALLI A[a] (op2 0x00) / JLI A[a] (op2 0x02) / JI A[a] (op2 0x03) (RR)
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                return Some(Decoded { op: Op::CallI, width: 4, rd: 0, rs1: a, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                let op = match (raw32 >> 20) & 0xFF {
+                    0x00 => Op::CallI,
+                    0x02 => Op::Jli,
+                    0x03 => Op::Ji,
+                    _ => return Err(unknown_op2((raw32 >> 20) & 0xFF)),
+                };
+                return Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+            }
+            0x01 => {
+                let c = ((raw32 >> 28) & 0xF) as u8;
+                match (raw32 >> 20) & 0xFF {
+                    // MOV.A A[c], D[b] / MOV.D D[c], A[b] (RR): long forms of the 0x60/0x80 SRR moves
+                    0x63 => return Ok(Decoded { op: Op::MovA, width: 4, rd: c, rs1: ((raw32 >> 12) & 0xF) as u8, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x4C => return Ok(Decoded { op: Op::MovD, width: 4, rd: c, rs1: ((raw32 >> 12) & 0xF) as u8, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x60 => {}
+                    _ => return Err(unknown_op2((raw32 >> 20) & 0xFF)),
+                }
+                // ADDSC.A A[c], A[b], D[a], n (RR, op2 0x60): n in [17:16]
+                let n = (raw32 >> 16) & 0x3;
+                let b = ((raw32 >> 12) & 0xF) as u8;
+                let a = ((raw32 >> 8) & 0xF) as u8;
+                return Ok(Decoded { op: Op::AddscA, width: 4, rd: c, rs1: b, rs2: a, imm: n, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x0D => {
                 // RET (SYS)
-                return Some(Decoded { op: Op::Ret, width: 4, rd: 0, rs1: 0, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Ret, width: 4, rd: 0, rs1: 0, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x1D => {
                 // J disp24 (B)
@@ -243,20 +420,20 @@ impl Decoder for Tc16Decoder {
                 let disp_hi8 = ((raw32 >> 8) & 0xFF) as u32;
                 let disp24 = (disp_hi8 << 16) | disp_low16;
                 let off = sign_ext(disp24, 24) << 1;
-                return Some(Decoded { op: Op::J, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::J, width: 4, rd: 0, rs1: 0, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x91 => {
                 // MOVH.A A[c], const16 (RLC)
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                return Some(Decoded { op: Op::MovHA, width: 4, rd: c, rs1: 0, rs2: 0, imm: imm16 << 16, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::MovHA, width: 4, rd: c, rs1: 0, rs2: 0, imm: imm16 << 16, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x11 => {
                 // ADDIH.A A[c], A[a], const16 (RLC)
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                return Some(Decoded { op: Op::Lea, width: 4, rd: c, rs1: a, rs2: 0, imm: imm16 << 16, imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Lea, width: 4, rd: c, rs1: a, rs2: 0, imm: imm16 << 16, imm2: 0, abs: false, wb: false, pre: false });
             }
             0x0B => {
                 let op2 = ((raw32 >> 20) & 0xFF) as u32;
@@ -266,156 +443,179 @@ impl Decoder for Tc16Decoder {
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::Add, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::Add, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                    }
+                    0x02 | 0x03 | 0x0A | 0x0B => {
+                        // ADDS/ADDS.U/SUBS/SUBS.U D[c], D[a], D[b] (RR)
+                        let c = ((raw32 >> 28) & 0xF) as u8;
+                        let b = ((raw32 >> 16) & 0xF) as u8;
+                        let a = ((raw32 >> 8) & 0xF) as u8;
+                        let op = match op2 { 0x02 => Op::Adds, 0x03 => Op::AddsU, 0x0A => Op::Subs, _ => Op::SubsU };
+                        Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x08 => {
                         // SUB D[c], D[a], D[b] (RR)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Sub, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Sub, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                    }
+                    0x0D | 0x0C => {
+                        // SUBC / SUBX D[c], D[a], D[b] (RR); neither has an RC form
+                        let c = ((raw32 >> 28) & 0xF) as u8;
+                        let b = ((raw32 >> 16) & 0xF) as u8;
+                        let a = ((raw32 >> 8) & 0xF) as u8;
+                        let op = if op2 == 0x0D { Op::Subc } else { Op::Subx };
+                        Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x04 => {
                         // ADDX RR
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Addx, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Addx, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x05 => {
                         // ADDC RR
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Addc, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Addc, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x1F => {
                         // MOV D[c], D[b] (RR)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
-                        Some(Decoded { op: Op::Mov, width: 4, rd: c, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Mov, width: 4, rd: c, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x18 => {
                         // CMP D[a], D[b] (signed) — pseudo encoding
                         let a = ((raw32 >> 8) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
-                        Some(Decoded { op: Op::Cmp, width: 4, rd: 0, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Cmp, width: 4, rd: 0, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x19 => {
                         // CMP.U D[a], D[b] (unsigned) — pseudo encoding
                         let a = ((raw32 >> 8) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
-                        Some(Decoded { op: Op::CmpU, width: 4, rd: 0, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::CmpU, width: 4, rd: 0, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x20 => {
                         // SHL D[c], D[a], D[b]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Shl, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Shl, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x21 => {
                         // SHR D[c], D[a], D[b]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Shr, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Shr, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x22 => {
                         // SAR D[c], D[a], D[b]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Sar, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Sar, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x23 => {
                         // ROR D[c], D[a], D[b]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Ror, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Ror, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x24 => {
                         // ANDN D[c], D[a], D[b]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Andn, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Andn, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x25 => {
                         // NOT D[c], D[a]
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Not, width: 4, rd: c, rs1: a, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Not, width: 4, rd: c, rs1: a, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x26 => {
                         // MIN D[c], D[a], D[b] (signed)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Min, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Min, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x27 => {
                         // MAX D[c], D[a], D[b] (signed)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Max, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Max, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x28 => {
                         // MIN.U D[c], D[a], D[b] (unsigned)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::MinU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::MinU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x29 => {
                         // MAX.U D[c], D[a], D[b] (unsigned)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::MaxU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::MaxU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x2C => {
                         // MUL D[c], D[a], D[b] (signed)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Mul, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Mul, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x2D => {
                         // MUL.U D[c], D[a], D[b] (unsigned)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::MulU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::MulU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x2E => {
                         // DIV D[c], D[a], D[b] (signed)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::Div, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::Div, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x2F => {
                         // DIV.U D[c], D[a], D[b] (unsigned)
                         let c = ((raw32 >> 28) & 0xF) as u8;
                         let b = ((raw32 >> 16) & 0xF) as u8;
                         let a = ((raw32 >> 8) & 0xF) as u8;
-                        Some(Decoded { op: Op::DivU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::DivU, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                    }
+                    0x0E => {
+                        // ABSDIF D[c], D[a], D[b] (signed)
+                        let c = ((raw32 >> 28) & 0xF) as u8;
+                        let b = ((raw32 >> 16) & 0xF) as u8;
+                        let a = ((raw32 >> 8) & 0xF) as u8;
+                        Ok(Decoded { op: Op::Absdif, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
                     }
-                    _ => None,
+                    _ => Err(unknown_op2(op2)),
                 }
             }
             0x49 => {
                 // LEA A[a], A[b], off10 (BO) with op2 == 0x28
-                if ((raw32 >> 22) & 0x3F) != 0x28 { return None; }
+                if ((raw32 >> 22) & 0x3F) != 0x28 { return Err(unknown_op2((raw32 >> 22) & 0x3F)); }
                 let off_upper4 = ((raw32 >> 28) & 0xF) as u32;
                 let off_lower6 = ((raw32 >> 16) & 0x3F) as u32;
                 let off10 = (off_upper4 << 6) | off_lower6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                return Some(Decoded { op: Op::Lea, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Lea, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false });
             }
             0xD9 => {
                 // LEA A[a], A[b], off16 (BOL)
@@ -425,10 +625,11 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                return Some(Decoded { op: Op::Lea, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Lea, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false });
             }
             0x0F => {
-                // Logical RR: op2 selects AND/OR/XOR (0x08/0x0A/0x0C)
+                // Logical RR: op2 selects AND/OR/XOR (0x08/0x0A/0x0C);
+                // CLZ/CLO/CLS (0x1B/0x1C/0x1D) take only D[a]
                 let op2 = ((raw32 >> 20) & 0xFF) as u32;
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let b = ((raw32 >> 16) & 0xF) as u8;
@@ -437,9 +638,13 @@ impl Decoder for Tc16Decoder {
                     0x08 => Op::And,
                     0x0A => Op::Or,
                     0x0C => Op::Xor,
-                    _ => return None,
+                    0x1B => Op::Clz,
+                    0x1C => Op::Clo,
+                    0x1D => Op::Cls,
+                    _ => return Err(unknown_op2(op2)),
                 };
-                Some(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
+                let b = if matches!(op, Op::Clz | Op::Clo | Op::Cls) { 0 } else { b };
+                Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: 0, abs: false, wb: false, pre: false })
             }
             0x8F => {
                 // Logical RC with const9: AND/OR/XOR via op2 (0x08/0x0A/0x0C)
@@ -451,9 +656,52 @@ impl Decoder for Tc16Decoder {
                     0x08 => Op::And,
                     0x0A => Op::Or,
                     0x0C => Op::Xor,
-                    _ => return None,
+                    _ => return Err(unknown_op2(op2)),
                 };
-                Some(Decoded { op, width: 4, rd: c, rs1: a, rs2: 0, imm: const9, imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: 0, imm: const9, imm2: 0, abs: false, wb: false, pre: false })
+            }
+            0x37 | 0x77 => {
+                // RRPW: c[31:28], pos[27:23], op2[22:21], width[20:16], b[15:12], a[11:8].
+                // 0x37: INSERT (op2 0) / EXTR (2) / EXTR.U (3); 0x77 op2 0: DEXTR
+                let op = match (op1, (raw32 >> 21) & 0x3) {
+                    (0x37, 0x0) => Op::Insert,
+                    (0x37, 0x2) => Op::Extr,
+                    (0x37, 0x3) => Op::ExtrU,
+                    (0x77, 0x0) => Op::Dextr,
+                    _ => return Err(unknown_op2((raw32 >> 21) & 0x3)),
+                };
+                let c = ((raw32 >> 28) & 0xF) as u8;
+                let b = ((raw32 >> 12) & 0xF) as u8;
+                let a = ((raw32 >> 8) & 0xF) as u8;
+                Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: (raw32 >> 23) & 0x1F, imm2: (raw32 >> 16) & 0x1F, abs: false, wb: false, pre: false })
+            }
+            0x2B => {
+                // CADD/CSUB/SEL/SELN D[c], D[d], D[a], D[b] (RRR): c[31:28], d[27:24],
+                // op2[23:20], b[15:12], a[11:8]; the condition register d goes in imm2
+                let op = match (raw32 >> 20) & 0xF {
+                    0x0 => Op::Cadd,
+                    0x2 => Op::Csub,
+                    0x4 => Op::Sel,
+                    0x5 => Op::Seln,
+                    _ => return Err(unknown_op2((raw32 >> 20) & 0xF)),
+                };
+                let c = ((raw32 >> 28) & 0xF) as u8;
+                let b = ((raw32 >> 12) & 0xF) as u8;
+                let a = ((raw32 >> 8) & 0xF) as u8;
+                Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: b, imm: 0, imm2: (raw32 >> 24) & 0xF, abs: false, wb: false, pre: false })
+            }
+            0xAB => {
+                // CADD/SEL/SELN D[c], D[d], D[a], const9 (RCR): op2 in [23:21], const9 in [20:12]
+                let op = match (raw32 >> 21) & 0x7 {
+                    0x0 => Op::CaddI,
+                    0x4 => Op::SelI,
+                    0x5 => Op::SelnI,
+                    _ => return Err(unknown_op2((raw32 >> 21) & 0x7)),
+                };
+                let c = ((raw32 >> 28) & 0xF) as u8;
+                let a = ((raw32 >> 8) & 0xF) as u8;
+                let const9 = (raw32 >> 12) & 0x1FF;
+                Ok(Decoded { op, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(const9, 9), imm2: (raw32 >> 24) & 0xF, abs: false, wb: false, pre: false })
             }
             0x8B => {
                 // RC forms by op2 in [27:21]
@@ -462,25 +710,29 @@ impl Decoder for Tc16Decoder {
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let imm9 = ((raw32 >> 12) & 0x1FF) as u32;
                 match op2 {
-                    0x00 => Some(Decoded { op: Op::Add, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x04 => Some(Decoded { op: Op::Addx, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x05 => Some(Decoded { op: Op::Addc, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x08 => Some(Decoded { op: Op::Sub, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x18 => Some(Decoded { op: Op::CmpI, width: 4, rd: 0, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x19 => Some(Decoded { op: Op::CmpUI, width: 4, rd: 0, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x20 => Some(Decoded { op: Op::Shl, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x21 => Some(Decoded { op: Op::Shr, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x22 => Some(Decoded { op: Op::Sar, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x23 => Some(Decoded { op: Op::Ror, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x24 => Some(Decoded { op: Op::Andn, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x26 => Some(Decoded { op: Op::Min, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x27 => Some(Decoded { op: Op::Max, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x28 => Some(Decoded { op: Op::MinU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x29 => Some(Decoded { op: Op::MaxU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x2C => Some(Decoded { op: Op::Mul, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
-                    0x2D => Some(Decoded { op: Op::MulU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x00 => Ok(Decoded { op: Op::Add, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    // ADDS.U also sign-extends const9
+                    0x02 => Ok(Decoded { op: Op::AddsI, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x03 => Ok(Decoded { op: Op::AddsUI, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x04 => Ok(Decoded { op: Op::Addx, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x05 => Ok(Decoded { op: Op::Addc, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x08 => Ok(Decoded { op: Op::Rsub, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x18 => Ok(Decoded { op: Op::CmpI, width: 4, rd: 0, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x19 => Ok(Decoded { op: Op::CmpUI, width: 4, rd: 0, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x20 => Ok(Decoded { op: Op::Shl, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9 & 0x3F, 6), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x21 => Ok(Decoded { op: Op::Shr, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x22 => Ok(Decoded { op: Op::Sar, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9 & 0x3F, 6), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x23 => Ok(Decoded { op: Op::Ror, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9 & 31, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x24 => Ok(Decoded { op: Op::Andn, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x26 => Ok(Decoded { op: Op::Min, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x27 => Ok(Decoded { op: Op::Max, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x28 => Ok(Decoded { op: Op::MinU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x29 => Ok(Decoded { op: Op::MaxU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x2C => Ok(Decoded { op: Op::Mul, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
+                    0x2D => Ok(Decoded { op: Op::MulU, width: 4, rd: c, rs1: a, rs2: 0, imm: imm9, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x0E => Ok(Decoded { op: Op::AbsdifI, width: 4, rd: c, rs1: a, rs2: 0, imm: sign_ext(imm9, 9), imm2: 0, abs: false, wb: false, pre: false }),
                     // DIV immediate not provided
-                    _ => None,
+                    _ => Err(unknown_op2(op2)),
                 }
             }
             0x1B => {
@@ -488,7 +740,7 @@ impl Decoder for Tc16Decoder {
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                Some(Decoded {
+                Ok(Decoded {
                     op: Op::Add,
                     width: 4,
                     rd: c,
@@ -506,7 +758,7 @@ impl Decoder for Tc16Decoder {
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                Some(Decoded {
+                Ok(Decoded {
                     op: Op::Add,
                     width: 4,
                     rd: c,
@@ -523,7 +775,7 @@ impl Decoder for Tc16Decoder {
                 // MOV D[c], const16 (RLC) sign-extended
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                Some(Decoded {
+                Ok(Decoded {
                     op: Op::MovI,
                     width: 4,
                     rd: c,
@@ -540,7 +792,7 @@ impl Decoder for Tc16Decoder {
                 // MOV.U D[c], const16 (RLC) zero-extended
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                Some(Decoded {
+                Ok(Decoded {
                     op: Op::MovI,
                     width: 4,
                     rd: c,
@@ -557,7 +809,7 @@ impl Decoder for Tc16Decoder {
                 // MOVH D[c], const16 (RLC) -> imm << 16
                 let c = ((raw32 >> 28) & 0xF) as u8;
                 let imm16 = ((raw32 >> 12) & 0xFFFF) as u32;
-                Some(Decoded {
+                Ok(Decoded {
                     op: Op::MovI,
                     width: 4,
                     rd: c,
@@ -571,15 +823,16 @@ impl Decoder for Tc16Decoder {
                 })
             }
             0xC5 => {
-                // LEA A[a], off18 (ABS)
+                // LEA A[a], off18 (ABS, op2 0x0)
+                if (raw32 >> 26) & 0x3 != 0 { return Err(unknown_op2((raw32 >> 26) & 0x3)); }
                 let off18 = off18_from_fields(raw32);
                 let ea = abs_ea_from_off18(off18);
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                return Some(Decoded { op: Op::Lea, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op: Op::Lea, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
             }
             0x05 => {
-                // LD.B/LD.BU/LD.H/LD.HU ABS (selector in off18[9:6])
-                let sel = ((raw32 >> 28) & 0xF) as u32;
+                // LD.B/LD.BU/LD.H/LD.HU ABS (op2 in [27:26])
+                let sel = (raw32 >> 26) & 0x3;
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let off18 = off18_from_fields(raw32);
                 let ea = abs_ea_from_off18(off18);
@@ -588,18 +841,24 @@ impl Decoder for Tc16Decoder {
                     0x01 => Op::LdBu,
                     0x02 => Op::LdH,
                     0x03 => Op::LdHu,
-                    _ => return None,
+                    _ => return Err(unknown_op2(sel)),
                 };
-                return Some(Decoded { op, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
             }
             0x85 => {
-                // LD.W ABS (selector must be 0x00)
-                let sel = ((raw32 >> 28) & 0xF) as u32;
-                if sel != 0 { return None; }
+                let a = ((raw32 >> 8) & 0xF) as u8;
+                if (raw32 >> 26) & 0x3 == 0x1 {
+                    // LD.D E[a], off18 (ABS, op2 0x1 in [27:26]); E[a] must be even
+                    if a & 1 != 0 { return Err(odd_pair(1)); }
+                    let ea = abs_ea_from_off18(off18_from_fields(raw32));
+                    return Ok(Decoded { op: Op::LdD, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                }
+                // LD.W ABS (op2 0x0)
+                if (raw32 >> 26) & 0x3 != 0 { return Err(unknown_op2((raw32 >> 26) & 0x3)); }
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let off18 = off18_from_fields(raw32);
                 let ea = abs_ea_from_off18(off18);
-                return Some(Decoded { op: Op::LdW, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op: Op::LdW, width: 4, rd: a, rs1: 0, rs2: 0, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
             }
             0x09 => {
                 // BO load family: op2 selects the element size and addressing mode
@@ -616,21 +875,26 @@ impl Decoder for Tc16Decoder {
                     0x02 => (Op::LdH, true, false),
                     0x03 => (Op::LdHu, true, false),
                     0x04 => (Op::LdW, true, false),
+                    0x05 => (Op::LdD, true, false),
                     // Pre-increment
                     0x10 => (Op::LdB, true, true),
                     0x11 => (Op::LdBu, true, true),
                     0x12 => (Op::LdH, true, true),
                     0x13 => (Op::LdHu, true, true),
                     0x14 => (Op::LdW, true, true),
+                    0x15 => (Op::LdD, true, true),
                     // Base + short offset (no write-back)
                     0x20 => (Op::LdB, false, false),
                     0x21 => (Op::LdBu, false, false),
                     0x22 => (Op::LdH, false, false),
                     0x23 => (Op::LdHu, false, false),
                     0x24 => (Op::LdW, false, false),
-                    _ => return None,
+                    0x25 => (Op::LdD, false, false),
+                    _ => return Err(unknown_op2(op2)),
                 };
-                Some(Decoded { op, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb, pre })
+                // E[a] names an even/odd pair
+                if matches!(op, Op::LdD) && a & 1 != 0 { return Err(odd_pair(op2)); }
+                Ok(Decoded { op, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb, pre })
             }
             0x19 => {
                 // LD.W D[a], A[b], off16 (BOL)
@@ -640,7 +904,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::LdW, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::LdW, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0xC9 => {
                 // LD.H D[a], A[b], off16 (BOL)
@@ -650,7 +914,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::LdH, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::LdH, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0xB9 => {
                 // LD.HU D[a], A[b], off16 (BOL)
@@ -660,7 +924,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::LdHu, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::LdHu, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0x29 => {
                 // LD.* with P[b] (bit-reverse / circular)
@@ -669,11 +933,11 @@ impl Decoder for Tc16Decoder {
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 match op2 {
                     // Bit-reverse
-                    0x00 => Some(Decoded { op: Op::LdBPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x01 => Some(Decoded { op: Op::LdBUPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x02 => Some(Decoded { op: Op::LdHPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x03 => Some(Decoded { op: Op::LdHUPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x04 => Some(Decoded { op: Op::LdWPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x00 => Ok(Decoded { op: Op::LdBPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x01 => Ok(Decoded { op: Op::LdBUPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x02 => Ok(Decoded { op: Op::LdHPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x03 => Ok(Decoded { op: Op::LdHUPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x04 => Ok(Decoded { op: Op::LdWPbr, width: 4, rd: a, rs1: b, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
                     // Circular: off10 present in instruction
                     0x10 | 0x11 | 0x12 | 0x13 | 0x14 => {
                         let off_upper4 = ((raw32 >> 28) & 0xF) as u32;
@@ -687,9 +951,9 @@ impl Decoder for Tc16Decoder {
                             0x14 => Op::LdWPcir,
                             _ => unreachable!(),
                         };
-                        Some(Decoded { op, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
                     }
-                    _ => None,
+                    _ => Err(unknown_op2(op2)),
                 }
             }
             0x79 => {
@@ -700,7 +964,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::LdB, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::LdB, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0xE9 => {
                 // ST.B A[b], off16, D[a] (BOL)
@@ -710,7 +974,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::StB, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::StB, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0xF9 => {
                 // ST.H A[b], off16, D[a] (BOL)
@@ -720,7 +984,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::StH, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::StH, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0x59 => {
                 // ST.W A[b], off16, D[a] (BOL)
@@ -730,7 +994,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::StW, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::StW, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0x39 => {
                 // LD.BU D[a], A[b], off16 (BOL)
@@ -740,7 +1004,7 @@ impl Decoder for Tc16Decoder {
                 let off16 = (off_mid6 << 10) | (off_hi4 << 6) | off_lo6;
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
-                Some(Decoded { op: Op::LdBu, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op: Op::LdBu, width: 4, rd: a, rs1: b, rs2: 0, imm: sign_ext(off16, 16), imm2: 0, abs: false, wb: false, pre: false })
             }
             0xA9 => {
                 // ST.W with P[b] (bit-reverse / circular)
@@ -748,28 +1012,28 @@ impl Decoder for Tc16Decoder {
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 match op2 {
-                    0x00 => Some(Decoded { op: Op::StBPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x02 => Some(Decoded { op: Op::StHPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
-                    0x04 => Some(Decoded { op: Op::StWPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x00 => Ok(Decoded { op: Op::StBPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x02 => Ok(Decoded { op: Op::StHPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
+                    0x04 => Ok(Decoded { op: Op::StWPbr, width: 4, rd: 0, rs1: b, rs2: a, imm: 0, imm2: 0, abs: false, wb: false, pre: false }),
                     0x10 => {
                         let off_upper4 = ((raw32 >> 28) & 0xF) as u32;
                         let off_lower6 = ((raw32 >> 16) & 0x3F) as u32;
                         let off10 = (off_upper4 << 6) | off_lower6;
-                        Some(Decoded { op: Op::StBPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::StBPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x12 => {
                         let off_upper4 = ((raw32 >> 28) & 0xF) as u32;
                         let off_lower6 = ((raw32 >> 16) & 0x3F) as u32;
                         let off10 = (off_upper4 << 6) | off_lower6;
-                        Some(Decoded { op: Op::StHPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::StHPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
                     }
                     0x14 => {
                         let off_upper4 = ((raw32 >> 28) & 0xF) as u32;
                         let off_lower6 = ((raw32 >> 16) & 0x3F) as u32;
                         let off10 = (off_upper4 << 6) | off_lower6;
-                        Some(Decoded { op: Op::StWPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
+                        Ok(Decoded { op: Op::StWPcir, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb: false, pre: false })
                     }
-                    _ => None,
+                    _ => Err(unknown_op2(op2)),
                 }
             }
             0x89 => {
@@ -785,39 +1049,58 @@ impl Decoder for Tc16Decoder {
                     0x00 => (Op::StB, true, false),
                     0x02 => (Op::StH, true, false),
                     0x04 => (Op::StW, true, false),
+                    0x05 => (Op::StD, true, false),
                     // Pre-increment
                     0x10 => (Op::StB, true, true),
                     0x12 => (Op::StH, true, true),
                     0x14 => (Op::StW, true, true),
+                    0x15 => (Op::StD, true, true),
                     // Base + short offset (no write-back)
                     0x20 => (Op::StB, false, false),
                     0x22 => (Op::StH, false, false),
                     0x24 => (Op::StW, false, false),
-                    _ => return None,
+                    0x25 => (Op::StD, false, false),
+                    _ => return Err(unknown_op2(op2)),
                 };
-                Some(Decoded { op, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb, pre })
+                if matches!(op, Op::StD) && a & 1 != 0 { return Err(odd_pair(op2)); }
+                Ok(Decoded { op, width: 4, rd: 0, rs1: b, rs2: a, imm: sign_ext(off10, 10), imm2: 0, abs: false, wb, pre })
             }
             0x25 => {
-                // ST.B/ST.H ABS via selector in off18[9:6]
-                let sel = ((raw32 >> 28) & 0xF) as u32;
+                // ST.B/ST.H ABS (op2 in [27:26])
+                let sel = (raw32 >> 26) & 0x3;
                 let a = ((raw32 >> 8) & 0xF) as u8; // source D[a]
                 let off18 = off18_from_fields(raw32);
                 let ea = abs_ea_from_off18(off18);
                 let op = match sel {
                     0x00 => Op::StB,
                     0x02 => Op::StH,
-                    _ => return None,
+                    _ => return Err(unknown_op2(sel)),
                 };
-                return Some(Decoded { op, width: 4, rd: 0, rs1: 0, rs2: a, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op, width: 4, rd: 0, rs1: 0, rs2: a, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
             }
             0xA5 => {
-                // ST.W ABS (selector must be 0x00)
-                let sel = ((raw32 >> 28) & 0xF) as u32;
-                if sel != 0 { return None; }
+                if (raw32 >> 26) & 0x3 == 0x1 {
+                    // ST.D off18, E[a] (ABS, op2 0x1 in [27:26]); E[a] must be even
+                    let a = ((raw32 >> 8) & 0xF) as u8;
+                    if a & 1 != 0 { return Err(odd_pair(1)); }
+                    let ea = abs_ea_from_off18(off18_from_fields(raw32));
+                    return Ok(Decoded { op: Op::StD, width: 4, rd: 0, rs1: 0, rs2: a, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                }
+                // ST.W ABS (op2 0x0)
+                if (raw32 >> 26) & 0x3 != 0 { return Err(unknown_op2((raw32 >> 26) & 0x3)); }
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let off18 = off18_from_fields(raw32);
                 let ea = abs_ea_from_off18(off18);
-                return Some(Decoded { op: Op::StW, width: 4, rd: 0, rs1: 0, rs2: a, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+                return Ok(Decoded { op: Op::StW, width: 4, rd: 0, rs1: 0, rs2: a, imm: ea, imm2: 0, abs: true, wb: false, pre: false });
+            }
+            0xFD => {
+                // LOOP A[b], disp15 (op2 bit 31 = 0) / LOOPU disp15 (op2 = 1) (BRR);
+                // no A[a] field, so disp15 sits at [30:16] clear of b
+                let b = ((raw32 >> 12) & 0xF) as u8;
+                let disp15 = ((raw32 >> 16) & 0x7FFF) as u32;
+                let off = sign_ext(disp15, 15) << 1;
+                let op = if raw32 >> 31 == 0 { Op::Loop } else { Op::LoopU };
+                Ok(Decoded { op, width: 4, rd: 0, rs1: b, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0x5F => {
                 // JEQ/JNE D[a], D[b], disp15 (BRR)
@@ -826,8 +1109,8 @@ impl Decoder for Tc16Decoder {
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
-                let op = match cond { 0 => Op::Jeq, 1 => Op::Jne, _ => return None };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
+                let op = match cond { 0 => Op::Jeq, 1 => Op::Jne, _ => return Err(unknown_op2(cond as u32)) };
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0xDF => {
                 // JEQ/JNE D[a], const4, disp15 (BRC)
@@ -839,9 +1122,9 @@ impl Decoder for Tc16Decoder {
                 let op = match cond {
                     0 => Op::JeqImm,
                     1 => Op::JneImm,
-                    _ => return None,
+                    _ => return Err(unknown_op2(cond as u32)),
                 };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2: sign_ext(const4, 4), abs: false, wb: false, pre: false })
             }
             0x7F => {
                 // JGE/JGE.U D[a], D[b], disp15 (BRR)
@@ -851,7 +1134,7 @@ impl Decoder for Tc16Decoder {
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
                 let op = if unsigned { Op::JgeU } else { Op::Jge };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0x7D => {
                 // JEQ.A/JNE.A A[a], A[b], disp15 (BRR), cond in [31:30]
@@ -860,8 +1143,8 @@ impl Decoder for Tc16Decoder {
                 let b = ((raw32 >> 12) & 0xF) as u8;
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
-                let op = match cond { 0 => Op::JeqA, 1 => Op::JneA, _ => return None };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
+                let op = match cond { 0 => Op::JeqA, 1 => Op::JneA, _ => return Err(unknown_op2(cond as u32)) };
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0xBD => {
                 // JZ.A/JNZ.A A[a], disp15 (BRR), cond in [31:30]
@@ -869,8 +1152,8 @@ impl Decoder for Tc16Decoder {
                 let a = ((raw32 >> 8) & 0xF) as u8;
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
-                let op = match cond { 0 => Op::JzA, 1 => Op::JnzA, _ => return None };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false })
+                let op = match cond { 0 => Op::JzA, 1 => Op::JnzA, _ => return Err(unknown_op2(cond as u32)) };
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0xFF => {
                 // JGE/JGE.U D[a], const4, disp15 (BRC)
@@ -881,7 +1164,7 @@ impl Decoder for Tc16Decoder {
                 let off = sign_ext(disp15, 15) << 1;
                 let op = if unsigned { Op::JgeUImm } else { Op::JgeImm };
                 let imm2 = if unsigned { const4 } else { sign_ext(const4, 4) };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2, abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2, abs: false, wb: false, pre: false })
             }
             0x3F => {
                 // JLT/JLT.U D[a], D[b], disp15 (BRR)
@@ -891,7 +1174,7 @@ impl Decoder for Tc16Decoder {
                 let disp15 = ((raw32 >> 15) & 0x7FFF) as u32;
                 let off = sign_ext(disp15, 15) << 1;
                 let op = if unsigned { Op::JltU } else { Op::Jlt };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: b, imm: off, imm2: 0, abs: false, wb: false, pre: false })
             }
             0xBF => {
                 // JLT/JLT.U D[a], const4, disp15 (BRC)
@@ -902,10 +1185,10 @@ impl Decoder for Tc16Decoder {
                 let off = sign_ext(disp15, 15) << 1;
                 let op = if unsigned { Op::JltUImm } else { Op::JltImm };
                 let imm2 = if unsigned { const4 } else { sign_ext(const4, 4) };
-                Some(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2, abs: false, wb: false, pre: false })
+                Ok(Decoded { op, width: 4, rd: 0, rs1: a, rs2: 0, imm: off, imm2, abs: false, wb: false, pre: false })
             }
             // Developer convenience removed to avoid shadowing real encodings
-            _ => None,
+            _ => Err(unknown_op1()),
         }
     }
 }
@@ -2,12 +2,15 @@ pub mod cpu;
 pub mod decoder;
 pub mod exec;
 pub mod disasm;
+pub mod effects;
 pub mod instructions;
 pub mod memory;
+pub mod mmio;
 
 pub mod isa {
     pub mod tc16; // TriCore v1.6 example variant
 }
 
-pub use cpu::{Cpu, CpuConfig, Trap};
-pub use memory::{Bus, LinearMemory};
+pub use cpu::{Cpu, CpuConfig, CycleCosts, Trap};
+pub use memory::{Access, Bus, LinearMemory, Perms};
+pub use mmio::{MmioBus, MmioDevice};
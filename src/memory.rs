@@ -1,6 +1,39 @@
 use anyhow::Result;
+use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
+bitflags! {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Perms: u8 {
+const R = 1 << 0;
+const W = 1 << 1;
+const X = 1 << 2;
+}
+}
+
+impl Perms {
+    /// Parse an `rwx`-style string such as "r-x" or "rw-".
+    pub fn from_rwx(s: &str) -> Self {
+        let mut p = Perms::empty();
+        if s.contains('r') { p |= Perms::R; }
+        if s.contains('w') { p |= Perms::W; }
+        if s.contains('x') { p |= Perms::X; }
+        p
+    }
+}
+
+/// What a bus access is for; instruction fetches need `X`, data reads `R`, stores `W`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access { Fetch, Read, Write }
+
+/// Returned by a `Bus` when a region's permissions forbid the access.
+#[derive(thiserror::Error, Debug)]
+#[error("{access:?} access to {addr:#010x} not permitted")]
+pub struct PermError {
+    pub addr: u32,
+    pub access: Access,
+}
+
 pub trait Bus {
     fn read_u8(&mut self, addr: u32) -> Result<u8>;
     fn read_u16(&mut self, addr: u32) -> Result<u16>;
@@ -8,12 +41,23 @@ pub trait Bus {
     fn write_u8(&mut self, addr: u32, val: u8) -> Result<()>;
     fn write_u16(&mut self, addr: u32, val: u16) -> Result<()>;
     fn write_u32(&mut self, addr: u32, val: u32) -> Result<()>;
+    /// Instruction fetch. Defaults to a data read for buses without execute permissions.
+    fn fetch_u32(&mut self, addr: u32) -> Result<u32> {
+        self.read_u32(addr)
+    }
 }
 
+/// Largest span `LinearMemory::with_regions` backs with one buffer.
+pub const MAX_SPAN: u64 = 64 << 20;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LinearMemory {
     pub mem: Vec<u8>,
     pub base: u32,
+    /// Permission regions as (start, end exclusive, perms). Empty means the
+    /// whole buffer is readable, writable and executable.
+    #[serde(default)]
+    pub regions: Vec<(u32, u32, Perms)>,
 }
 
 impl LinearMemory {
@@ -21,6 +65,63 @@ impl LinearMemory {
         Self {
             mem: vec![0; size],
             base: 0,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Back the span of all regions with one buffer and enforce each region's
+    /// permissions. Addresses between regions are unmapped. Regions spread
+    /// over more than `MAX_SPAN` bytes are rejected rather than allocated.
+    pub fn with_regions(regions: Vec<(u32, Vec<u8>, Perms)>) -> Result<Self> {
+        let base = regions.iter().map(|r| r.0).min().unwrap_or(0);
+        let end = regions.iter().map(|r| r.0 as u64 + r.1.len() as u64).max().unwrap_or(base as u64);
+        let span = end - base as u64;
+        anyhow::ensure!(span <= MAX_SPAN, "regions span {span:#x} bytes from {base:#010x}, more than {MAX_SPAN:#x}");
+        let mut mem = vec![0; (end - base as u64) as usize];
+        let mut spans = Vec::with_capacity(regions.len());
+        for (start, bytes, perms) in regions {
+            let off = (start - base) as usize;
+            mem[off..off + bytes.len()].copy_from_slice(&bytes);
+            spans.push((start, start.wrapping_add(bytes.len() as u32), perms));
+        }
+        Ok(Self { mem, base, regions: spans })
+    }
+
+    /// Drop write permission on [start, end), e.g. to model flash inside a
+    /// flat `new` buffer; stores there then trap as `Trap::Perm`. A buffer
+    /// with no regions is first given one RWX region covering all of it.
+    pub fn set_read_only(&mut self, start: u32, end: u32) {
+        // Region ends of 0 mean "to the top of the address space"
+        let top = |e: u32| if e == 0 { 1u64 << 32 } else { e as u64 };
+        if self.regions.is_empty() {
+            self.regions.push((self.base, self.base.wrapping_add(self.mem.len() as u32), Perms::all()));
+        }
+        let (ro_lo, ro_hi) = (start as u64, top(end));
+        let mut out = Vec::with_capacity(self.regions.len() + 2);
+        for &(s, e, perms) in &self.regions {
+            let (lo, hi) = (s as u64, top(e));
+            let (mid_lo, mid_hi) = (lo.max(ro_lo), hi.min(ro_hi));
+            if mid_lo >= mid_hi { out.push((s, e, perms)); continue; }
+            if lo < mid_lo { out.push((s, mid_lo as u32, perms)); }
+            out.push((mid_lo as u32, mid_hi as u32, perms - Perms::W));
+            if mid_hi < hi { out.push((mid_hi as u32, e, perms)); }
+        }
+        self.regions = out;
+    }
+
+    /// Check every byte of [addr, addr+len) against the region table.
+    fn check(&self, addr: u32, len: u32, access: Access) -> Result<()> {
+        if self.regions.is_empty() { return Ok(()); }
+        let need = match access { Access::Fetch => Perms::X, Access::Read => Perms::R, Access::Write => Perms::W };
+        let mut a = addr;
+        let last = addr.wrapping_add(len - 1);
+        loop {
+            let Some(&(_, end, perms)) = self.regions.iter().find(|r| a >= r.0 && a < r.1) else {
+                anyhow::bail!("unmapped address {a:#010x}");
+            };
+            if !perms.contains(need) { return Err(PermError { addr, access }.into()); }
+            if last < end || end == 0 { return Ok(()); }
+            a = end;
         }
     }
 }
@@ -47,36 +148,55 @@ impl LinearMemory {
 
 impl Bus for LinearMemory {
     fn read_u8(&mut self, addr: u32) -> Result<u8> {
+        self.check(addr, 1, Access::Read)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off < self.mem.len(), "read_u8 OOB at {addr:#x} (base {:#x})", self.base);
         Ok(self.mem[off])
     }
     fn read_u16(&mut self, addr: u32) -> Result<u16> {
+        self.check(addr, 2, Access::Read)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off + 1 < self.mem.len(), "read_u16 OOB at {addr:#x} (base {:#x})", self.base);
         Ok(self.load_le_u16(off))
     }
     fn read_u32(&mut self, addr: u32) -> Result<u32> {
+        self.check(addr, 4, Access::Read)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off + 3 < self.mem.len(), "read_u32 OOB at {addr:#x} (base {:#x})", self.base);
         Ok(self.load_le_u32(off))
     }
     fn write_u8(&mut self, addr: u32, val: u8) -> Result<()> {
+        self.check(addr, 1, Access::Write)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off < self.mem.len(), "write_u8 OOB at {addr:#x} (base {:#x})", self.base);
         self.mem[off] = val;
         Ok(())
     }
     fn write_u16(&mut self, addr: u32, val: u16) -> Result<()> {
+        self.check(addr, 2, Access::Write)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off + 1 < self.mem.len(), "write_u16 OOB at {addr:#x} (base {:#x})", self.base);
         self.store_le_u16(off, val);
         Ok(())
     }
     fn write_u32(&mut self, addr: u32, val: u32) -> Result<()> {
+        self.check(addr, 4, Access::Write)?;
         let off = addr.wrapping_sub(self.base) as usize;
         anyhow::ensure!(off + 3 < self.mem.len(), "write_u32 OOB at {addr:#x} (base {:#x})", self.base);
         self.store_le_u32(off, val);
         Ok(())
     }
+    fn fetch_u32(&mut self, addr: u32) -> Result<u32> {
+        // The fetch window may run past a short 16-bit instruction; only its
+        // first halfword must be executable, all four bytes of a 32-bit one
+        self.check(addr, 2, Access::Fetch)?;
+        let off = addr.wrapping_sub(self.base) as usize;
+        anyhow::ensure!(off + 1 < self.mem.len(), "read_u32 OOB at {addr:#x} (base {:#x})", self.base);
+        if self.mem[off] & 1 == 0 && off + 3 >= self.mem.len() {
+            return Ok(self.load_le_u16(off) as u32);
+        }
+        if self.mem[off] & 1 != 0 { self.check(addr, 4, Access::Fetch)?; }
+        anyhow::ensure!(off + 3 < self.mem.len(), "read_u32 OOB at {addr:#x} (base {:#x})", self.base);
+        Ok(self.load_le_u32(off))
+    }
 }
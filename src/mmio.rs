@@ -0,0 +1,79 @@
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::memory::{Bus, LinearMemory};
+
+/// A memory-mapped peripheral. `offset` is relative to the start of the
+/// range the device was registered at; `width` is the access size in bytes.
+pub trait MmioDevice {
+    fn read(&mut self, offset: u32, width: u8) -> Result<u32>;
+    fn write(&mut self, offset: u32, width: u8, val: u32) -> Result<()>;
+}
+
+/// Routes accesses inside registered ranges to devices and everything else
+/// to the inner bus. Instruction fetches always go to the inner bus.
+pub struct MmioBus<B: Bus = LinearMemory> {
+    pub inner: B,
+    handlers: Vec<(Range<u32>, Box<dyn MmioDevice>)>,
+}
+
+impl<B: Bus> MmioBus<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner, handlers: Vec::new() }
+    }
+
+    /// Register `handler` for `range`. Earlier registrations win on overlap.
+    pub fn add_handler(&mut self, range: Range<u32>, handler: Box<dyn MmioDevice>) {
+        self.handlers.push((range, handler));
+    }
+
+    fn device(&mut self, addr: u32) -> Option<(u32, &mut (dyn MmioDevice + 'static))> {
+        self.handlers
+            .iter_mut()
+            .find(|(r, _)| r.contains(&addr))
+            .map(|(r, d)| (addr - r.start, d.as_mut()))
+    }
+}
+
+impl<B: Bus> Bus for MmioBus<B> {
+    fn read_u8(&mut self, addr: u32) -> Result<u8> {
+        match self.device(addr) {
+            Some((off, d)) => Ok(d.read(off, 1)? as u8),
+            None => self.inner.read_u8(addr),
+        }
+    }
+    fn read_u16(&mut self, addr: u32) -> Result<u16> {
+        match self.device(addr) {
+            Some((off, d)) => Ok(d.read(off, 2)? as u16),
+            None => self.inner.read_u16(addr),
+        }
+    }
+    fn read_u32(&mut self, addr: u32) -> Result<u32> {
+        match self.device(addr) {
+            Some((off, d)) => d.read(off, 4),
+            None => self.inner.read_u32(addr),
+        }
+    }
+    fn write_u8(&mut self, addr: u32, val: u8) -> Result<()> {
+        match self.device(addr) {
+            Some((off, d)) => d.write(off, 1, val as u32),
+            None => self.inner.write_u8(addr, val),
+        }
+    }
+    fn write_u16(&mut self, addr: u32, val: u16) -> Result<()> {
+        match self.device(addr) {
+            Some((off, d)) => d.write(off, 2, val as u32),
+            None => self.inner.write_u16(addr, val),
+        }
+    }
+    fn write_u32(&mut self, addr: u32, val: u32) -> Result<()> {
+        match self.device(addr) {
+            Some((off, d)) => d.write(off, 4, val),
+            None => self.inner.write_u32(addr, val),
+        }
+    }
+    fn fetch_u32(&mut self, addr: u32) -> Result<u32> {
+        self.inner.fetch_u32(addr)
+    }
+}
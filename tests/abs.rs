@@ -8,8 +8,8 @@ fn enc_abs_common(op1: u32, sel: u32, a: u32, off18: u32) -> u32 {
     let off13_10 = (off18 >> 10) & 0xF;
     let off5_0 = off18 & 0x3F;
     let off17_14 = (off18 >> 14) & 0xF;
-    // Layout: [31:28]=off9_6, [27:26]=sel_high? embedded via sel nibble in off9_6 for these forms
-    (off9_6 << 28) | (off13_10 << 22) | (off5_0 << 16) | (off17_14 << 12) | (a << 8) | op1
+    // Layout: [31:28]=off9_6, [27:26]=op2, [25:22]=off13_10, [21:16]=off5_0, [15:12]=off17_14
+    (off9_6 << 28) | (sel << 26) | (off13_10 << 22) | (off5_0 << 16) | (off17_14 << 12) | (a << 8) | op1
 }
 
 fn abs_ea(off18: u32) -> u32 {
@@ -59,4 +59,23 @@ fn ldw_abs_and_stb_abs() {
     assert_eq!(mem.read_u8(abs_ea(off18_b)).unwrap(), 0xD4);
 }
 
-// (LD.H/LD.HU ABS encodings exist in the decoder; exercised indirectly via BO path in other tests.)
+#[test]
+fn ldh_abs_keeps_off18_bits_9_6_apart_from_op2() {
+    let mut mem = LinearMemory::new(0x400);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+
+    // EA 0x3C2 has off18[9:6] = 0xF, which op2 must not clobber
+    let off18 = 0x3C2u32;
+    mem.write_u16(abs_ea(off18), 0x8001).unwrap();
+    mem.write_u32(0, enc_abs_common(0x05, 0x2, 5, off18)).unwrap(); // LD.H D5
+    mem.write_u32(4, enc_abs_common(0x05, 0x3, 6, off18)).unwrap(); // LD.HU D6
+    mem.write_u32(8, enc_abs_common(0x25, 0x2, 6, off18 + 0x10)).unwrap(); // ST.H D6
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &exec).unwrap(); }
+    assert_eq!(cpu.gpr[5], 0xFFFF_8001);
+    assert_eq!(cpu.gpr[6], 0x0000_8001);
+    assert_eq!(mem.read_u16(abs_ea(off18 + 0x10)).unwrap(), 0x8001);
+}
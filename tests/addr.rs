@@ -62,3 +62,67 @@ fn addih_a_and_lea_bol_build_address() {
     cpu.step(&mut mem, &dec, &exec).unwrap();
     assert_eq!(cpu.a[2], 0x1001_2345);
 }
+
+#[test]
+fn sub_a_sp_then_add_a_restores_stack_pointer() {
+    use tricore_rs::decoder::Decoder;
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.a[10] = 0xD000_1000;
+    cpu.a[4] = 8;
+    let psw_before = cpu.psw.bits();
+
+    // SUB.A SP, #16 (SC); ADD.A SP, #7 (SRC); ADD.A SP, #1 (SRC); ADD.A SP, A4 (SRR)
+    let sub_sp: u16 = (16 << 8) | 0x20;
+    let add_c7: u16 = (7 << 12) | (10 << 8) | 0xB0;
+    let add_c1: u16 = (1 << 12) | (10 << 8) | 0xB0;
+    let add_a4: u16 = (4 << 12) | (10 << 8) | 0x30;
+    mem.write_u16(0, sub_sp).unwrap();
+    mem.write_u16(2, add_c7).unwrap();
+    mem.write_u16(4, add_c1).unwrap();
+    mem.write_u16(6, add_a4).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.a[10], 0xD000_0FF0);
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &exec).unwrap(); }
+    assert_eq!(cpu.a[10], 0xD000_1000);
+    assert_eq!(cpu.pc, 8);
+    assert_eq!(cpu.psw.bits(), psw_before);
+
+    // A[b] = A0 is a register like any other
+    cpu.a[0] = 0x20;
+    mem.write_u16(8, (10 << 8) | 0x30).unwrap();
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.a[10], 0xD000_1020);
+    assert_eq!(tricore_rs::disasm::fmt_decoded(&dec.decode(0x0A30).unwrap()), "add.a a10, a0");
+}
+
+#[test]
+fn mov_a_and_mov_d_short_and_long_forms_copy_between_register_files() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[2] = 0xD000_0010;
+    cpu.a[5] = 0x7000_0020;
+
+    // 16-bit SRR: MOV.A A3, D2 (0x60) ; MOV.D D4, A5 (0x80)
+    mem.write_u16(0, (2u16 << 12) | (3 << 8) | 0x60).unwrap();
+    mem.write_u16(2, (5u16 << 12) | (4 << 8) | 0x80).unwrap();
+    // 32-bit RR: MOV.A A6, D4 (op2 0x63) ; MOV.D D7, A3 (op2 0x4C)
+    mem.write_u32(4, (6u32 << 28) | (0x63 << 20) | (4 << 12) | 0x01).unwrap();
+    mem.write_u32(8, (7u32 << 28) | (0x4C << 20) | (3 << 12) | 0x01).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!((cpu.pc, cpu.a[3]), (2, 0xD000_0010));
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!((cpu.pc, cpu.gpr[4]), (4, 0x7000_0020));
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!((cpu.pc, cpu.a[6]), (8, 0x7000_0020));
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!((cpu.pc, cpu.gpr[7]), (12, 0xD000_0010));
+}
@@ -0,0 +1,415 @@
+use tricore_rs::cpu::Psw;
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Bus, Cpu, CpuConfig, LinearMemory, Trap};
+
+// RR (op1=0x0B): c:31..28, op2:27..20, b:19..16, a:11..8
+fn enc_rr(op2: u32, c: u32, a: u32, b: u32) -> u32 { (c<<28) | (op2<<20) | (b<<16) | (a<<8) | 0x0B }
+// RC (op1=0x8B): c:31..28, op2:27..21, const9:20..12, a:11..8
+fn enc_rc(op2: u32, c: u32, a: u32, imm9: u32) -> u32 { (c<<28) | (op2<<21) | ((imm9 & 0x1FF)<<12) | (a<<8) | 0x8B }
+
+/// Run a single instruction with D1 = x, D2 = y and return the CPU afterwards.
+fn run1(raw: u32, x: u32, y: u32) -> (Cpu, Result<(), Trap>) {
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = x;
+    cpu.gpr[2] = y;
+    mem.write_u32(0, raw).unwrap();
+    let r = cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor);
+    (cpu, r)
+}
+
+#[test]
+fn min_max_signed_and_unsigned() {
+    let (cpu, _) = run1(enc_rr(0x26, 3, 1, 2), 0xFFFF_FFFF, 1);
+    assert_eq!(cpu.gpr[3], 0xFFFF_FFFF);
+    assert!(cpu.psw.contains(Psw::N));
+    let (cpu, _) = run1(enc_rr(0x27, 3, 1, 2), 0xFFFF_FFFF, 1);
+    assert_eq!(cpu.gpr[3], 1);
+    let (cpu, _) = run1(enc_rr(0x28, 3, 1, 2), 0xFFFF_FFFF, 1);
+    assert_eq!(cpu.gpr[3], 1);
+    let (cpu, _) = run1(enc_rr(0x29, 3, 1, 2), 0xFFFF_FFFF, 1);
+    assert_eq!(cpu.gpr[3], 0xFFFF_FFFF);
+    // RC form: min d3, d1, #0 with d1 = 5 -> 0, Z set
+    let (cpu, _) = run1(enc_rc(0x26, 3, 1, 0), 5, 0);
+    assert_eq!(cpu.gpr[3], 0);
+    assert!(cpu.psw.contains(Psw::Z));
+}
+
+#[test]
+fn mul_sets_overflow_when_product_exceeds_32_bits() {
+    let (cpu, _) = run1(enc_rr(0x2C, 3, 1, 2), 0xFFFF_FFFD, 7);
+    assert_eq!(cpu.gpr[3] as i32, -21);
+    assert!(cpu.psw.contains(Psw::N));
+    assert!(!cpu.psw.contains(Psw::V));
+    let (cpu, _) = run1(enc_rr(0x2C, 3, 1, 2), 0x0001_0000, 0x0001_0000);
+    assert_eq!(cpu.gpr[3], 0);
+    assert!(cpu.psw.contains(Psw::Z));
+    assert!(cpu.psw.contains(Psw::V) && cpu.psw.contains(Psw::SV));
+    let (cpu, _) = run1(enc_rr(0x2D, 3, 1, 2), 0x8000_0000, 2);
+    assert_eq!(cpu.gpr[3], 0);
+    assert!(cpu.psw.contains(Psw::V));
+    let (cpu, _) = run1(enc_rr(0x2D, 3, 1, 2), 0xFFFF, 0xFFFF);
+    assert_eq!(cpu.gpr[3], 0xFFFE_0001);
+    assert!(!cpu.psw.contains(Psw::V));
+}
+
+#[test]
+fn div_truncates_and_traps_on_zero() {
+    let (cpu, _) = run1(enc_rr(0x2F, 3, 1, 2), 7, 2);
+    assert_eq!(cpu.gpr[3], 3);
+    let (cpu, _) = run1(enc_rr(0x2F, 3, 1, 2), 0xFFFF_FFFF, 0x10);
+    assert_eq!(cpu.gpr[3], 0x0FFF_FFFF);
+    let (cpu, _) = run1(enc_rr(0x2E, 3, 1, 2), (-7i32) as u32, 2);
+    assert_eq!(cpu.gpr[3] as i32, -3);
+    assert!(cpu.psw.contains(Psw::N));
+    let (cpu, _) = run1(enc_rr(0x2E, 3, 1, 2), 0x8000_0000, 0xFFFF_FFFF);
+    assert!(cpu.psw.contains(Psw::V));
+
+    let (cpu, r) = run1(enc_rr(0x2F, 3, 1, 2), 7, 0);
+    assert!(matches!(r, Err(Trap::DivideByZero)));
+    assert_eq!(cpu.gpr[3], 0);
+    let (_, r) = run1(enc_rr(0x2E, 3, 1, 2), 7, 0);
+    assert!(matches!(r, Err(Trap::DivideByZero)));
+}
+
+#[test]
+fn andn_not_and_ror_wraparound() {
+    let (cpu, _) = run1(enc_rr(0x24, 3, 1, 2), 0xFF, 0x0F);
+    assert_eq!(cpu.gpr[3], 0xF0);
+    let (cpu, _) = run1(enc_rr(0x25, 3, 1, 0), 0, 0);
+    assert_eq!(cpu.gpr[3], 0xFFFF_FFFF);
+    assert!(cpu.psw.contains(Psw::N));
+    let (cpu, _) = run1(enc_rr(0x25, 3, 1, 0), 0xFFFF_FFFF, 0);
+    assert!(cpu.psw.contains(Psw::Z));
+    // ror by 4 moves the low nibble to the top
+    let (cpu, _) = run1(enc_rr(0x23, 3, 1, 2), 0x1234_5678, 4);
+    assert_eq!(cpu.gpr[3], 0x8123_4567);
+    assert!(cpu.psw.contains(Psw::N));
+    // ror by 32 wraps to the identity
+    let (cpu, _) = run1(enc_rr(0x23, 3, 1, 2), 0x1234_5678, 32);
+    assert_eq!(cpu.gpr[3], 0x1234_5678);
+}
+
+#[test]
+fn absdif_signed_and_overflow_edge() {
+    // a < b
+    let (cpu, _) = run1(enc_rr(0x0E, 3, 1, 2), 3, 10);
+    assert_eq!(cpu.gpr[3], 7);
+    assert!(!cpu.psw.contains(Psw::N) && !cpu.psw.contains(Psw::V));
+    // a > b with a negative operand: 5 - (-4) = 9
+    let (cpu, _) = run1(enc_rr(0x0E, 3, 1, 2), 5, (-4i32) as u32);
+    assert_eq!(cpu.gpr[3], 9);
+    // equal operands set Z
+    let (cpu, _) = run1(enc_rr(0x0E, 3, 1, 2), 42, 42);
+    assert_eq!(cpu.gpr[3], 0);
+    assert!(cpu.psw.contains(Psw::Z));
+    // i32::MIN - 1 does not fit: result wraps to 0x8000_0001, V/SV raised
+    let (cpu, _) = run1(enc_rr(0x0E, 3, 1, 2), 0x8000_0000, 1);
+    assert_eq!(cpu.gpr[3], 0x8000_0001);
+    assert!(cpu.psw.contains(Psw::V) && cpu.psw.contains(Psw::SV));
+    assert!(cpu.psw.contains(Psw::N));
+    // RC form with negative const9: |2 - (-3)| = 5
+    let (cpu, _) = run1(enc_rc(0x0E, 3, 1, (-3i32) as u32), 2, 0);
+    assert_eq!(cpu.gpr[3], 5);
+    // D0 as D[b] is read like any register: |2 - 9| = 7, not |2 - 0|
+    use tricore_rs::decoder::Decoder;
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    (cpu.gpr[0], cpu.gpr[1]) = (9, 2);
+    mem.write_u32(0, enc_rr(0x0E, 3, 1, 0)).unwrap();
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(cpu.gpr[3], 7);
+    assert_eq!(tricore_rs::disasm::fmt_decoded(&Tc16Decoder::new().decode(enc_rr(0x0E, 3, 1, 0)).unwrap()), "absdif d3, d1, d0");
+    // There is no ABSDIF.U: the RC op2 next to ABSDIF is not an instruction
+    assert!(Tc16Decoder::new().decode(enc_rc(0x0D, 3, 1, 5)).is_none());
+}
+
+#[test]
+fn adds_saturates_and_sv_stays_sticky() {
+    // adds d3, d1, d2 ; add d4, d1, d2 (no overflow with the second pair)
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 0x7000_0000;
+    cpu.gpr[2] = 0x2000_0000;
+    mem.write_u32(0, enc_rr(0x02, 3, 1, 2)).unwrap();
+    mem.write_u32(4, enc_rr(0x00, 4, 1, 2)).unwrap();
+    let (dec, exec) = (Tc16Decoder::new(), IntExecutor);
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[3], i32::MAX as u32);
+    assert!(cpu.psw.contains(Psw::V | Psw::SV));
+
+    cpu.gpr[2] = 1;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[4], 0x7000_0001);
+    assert!(!cpu.psw.contains(Psw::V), "V reflects only the last op");
+    assert!(cpu.psw.contains(Psw::SV), "SV is sticky");
+
+    // subs clamps at i32::MIN; adds.u/subs.u clamp to [0, u32::MAX]
+    let (cpu, _) = run1(enc_rr(0x0A, 3, 1, 2), 0x8000_0000, 1);
+    assert_eq!(cpu.gpr[3], 0x8000_0000);
+    assert!(cpu.psw.contains(Psw::V));
+    let (cpu, _) = run1(enc_rr(0x03, 3, 1, 2), 0xFFFF_FFF0, 0x20);
+    assert_eq!(cpu.gpr[3], u32::MAX);
+    let (cpu, _) = run1(enc_rr(0x0B, 3, 1, 2), 1, 2);
+    assert_eq!(cpu.gpr[3], 0);
+    // In range: plain result, V clear; RC form takes a signed const9
+    let (cpu, _) = run1(enc_rc(0x02, 3, 1, 0x1FF), 5, 0);
+    assert_eq!(cpu.gpr[3], 4);
+    assert!(!cpu.psw.contains(Psw::V));
+
+    // D0 as D[b] is a register, not a zero immediate
+    for (raw, want, text) in [(enc_rr(0x02, 3, 1, 0), 15, "adds d3, d1, d0"), (enc_rr(0x0A, 3, 1, 0), 5, "subs d3, d1, d0")] {
+        use tricore_rs::decoder::Decoder;
+        let mut mem = LinearMemory::new(16);
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(0);
+        (cpu.gpr[0], cpu.gpr[1]) = (5, 10);
+        mem.write_u32(0, raw).unwrap();
+        cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+        assert_eq!(cpu.gpr[3], want, "{text}");
+        assert_eq!(tricore_rs::disasm::fmt_decoded(&Tc16Decoder::new().decode(raw).unwrap()), text);
+    }
+}
+
+// SRR/SRC (16-bit): [15:12] D[b] or const4, [11:8] D[a]
+fn enc16(op1: u32, hi: u32, a: u32) -> u32 { (hi << 12) | (a << 8) | op1 }
+
+#[test]
+fn decode_16bit_add_sub_mov_with_implicit_d15() {
+    use tricore_rs::decoder::Decoder;
+    use tricore_rs::disasm::fmt_decoded;
+    let dec = Tc16Decoder::new();
+    // (raw, rd, rs1, rs2, text)
+    let cases = [
+        (enc16(0x12, 2, 1), 1, 15, 2, "add d1, d15, d2"),
+        (enc16(0x1A, 2, 1), 15, 1, 2, "add d15, d1, d2"),
+        (enc16(0x92, 0xF, 1), 1, 15, 0, "addi d1, d15, 0xffffffff"),
+        (enc16(0x9A, 3, 1), 15, 1, 0, "addi d15, d1, 0x3"),
+        (enc16(0xA2, 2, 1), 1, 1, 2, "sub d1, d1, d2"),
+        (enc16(0x52, 2, 1), 1, 15, 2, "sub d1, d15, d2"),
+        (enc16(0x5A, 2, 1), 15, 1, 2, "sub d15, d1, d2"),
+        (enc16(0x02, 2, 1), 1, 2, 0, "mov d1, d2"),
+        (0xAB << 8 | 0xDA, 15, 0, 0, "mov d15, #0xab"),
+    ];
+    for (raw, rd, rs1, rs2, text) in cases {
+        let d = dec.decode(raw).unwrap_or_else(|| panic!("{raw:#06x} undecodable"));
+        assert_eq!((d.width, d.rd, d.rs1, d.rs2), (2, rd, rs1, rs2), "{text}");
+        assert_eq!(fmt_decoded(&d), text, "{raw:#06x}");
+    }
+}
+
+#[test]
+fn add_sub_16bit_read_and_write_d15() {
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 7;
+    cpu.gpr[2] = 5;
+    cpu.gpr[15] = 100;
+    mem.write_u16(0, enc16(0x52, 2, 1) as u16).unwrap(); // SUB D1, D15, D2
+    mem.write_u16(2, enc16(0x1A, 2, 1) as u16).unwrap(); // ADD D15, D1, D2
+    mem.write_u16(4, enc16(0x92, 0xE, 3) as u16).unwrap(); // ADD D3, D15, #-2
+
+    let dec = Tc16Decoder::new();
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &IntExecutor).unwrap(); }
+    assert_eq!(cpu.gpr[1], 95);
+    assert_eq!(cpu.gpr[15], 100);
+    assert_eq!(cpu.gpr[3], 98);
+    assert_eq!(cpu.pc, 6);
+}
+
+/// Subtract the 64-bit D3:D2 from D1:D0 into D5:D4 with `sub` then `subc`.
+fn sub64(x: u64, y: u64) -> (u64, Cpu) {
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    (cpu.gpr[0], cpu.gpr[1]) = (x as u32, (x >> 32) as u32);
+    (cpu.gpr[2], cpu.gpr[3]) = (y as u32, (y >> 32) as u32);
+    mem.write_u32(0, enc_rr(0x08, 4, 0, 2)).unwrap(); // SUB D4, D0, D2
+    mem.write_u32(4, enc_rr(0x0D, 5, 1, 3)).unwrap(); // SUBC D5, D1, D3
+    let dec = Tc16Decoder::new();
+    for _ in 0..2 { cpu.step(&mut mem, &dec, &IntExecutor).unwrap(); }
+    (((cpu.gpr[5] as u64) << 32) | cpu.gpr[4] as u64, cpu)
+}
+
+#[test]
+fn sub_then_subc_chains_a_64bit_subtraction() {
+    // The low word borrows, and SUBC takes it out of the high word
+    assert_eq!(sub64(0x1_0000_0000, 1).0, 0xFFFF_FFFF);
+    // No borrow: the high words subtract as they are
+    assert_eq!(sub64(0x5_0000_0003, 0x2_0000_0001).0, 0x3_0000_0002);
+    let (r, cpu) = sub64(0x1234_5678_9ABC_DEF0, 0x1234_5678_9ABC_DEF0);
+    assert_eq!(r, 0);
+    assert!(cpu.psw.contains(Psw::Z));
+    // Wrapping below zero borrows out of the high word too (C clear)
+    let (r, cpu) = sub64(0, 1);
+    assert_eq!(r, u64::MAX);
+    assert!(!cpu.psw.contains(Psw::C));
+}
+
+#[test]
+fn subx_rsub_and_sub_with_d0_are_distinct_ops() {
+    // SUBX clears C on a borrow, like SUB
+    let (cpu, _) = run1(enc_rr(0x0C, 3, 1, 2), 3, 5);
+    assert_eq!(cpu.gpr[3], (-2i32) as u32);
+    assert!(!cpu.psw.contains(Psw::C));
+    // RSUB: const9 - D[a]
+    let (cpu, _) = run1(enc_rc(0x08, 3, 1, 10), 3, 0);
+    assert_eq!(cpu.gpr[3], 7);
+    let (cpu, _) = run1(enc_rc(0x08, 3, 1, (-1i32) as u32), 3, 0);
+    assert_eq!(cpu.gpr[3], (-4i32) as u32);
+    // SUB D3, D1, D0 reads D0 (zero here), not an immediate
+    let (cpu, _) = run1(enc_rr(0x08, 3, 1, 0), 9, 0);
+    assert_eq!(cpu.gpr[3], 9);
+    // SUBX/SUBC D3, D1, D0 subtract D0 itself, with a borrow pending for SUBC (C clear)
+    for (raw, want) in [(enc_rr(0x0C, 3, 1, 0), 6), (enc_rr(0x0D, 3, 1, 0), 5)] {
+        let mut mem = LinearMemory::new(16);
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(0);
+        (cpu.gpr[0], cpu.gpr[1]) = (4, 10);
+        cpu.psw.remove(Psw::C);
+        mem.write_u32(0, raw).unwrap();
+        cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+        assert_eq!(cpu.gpr[3], want);
+    }
+}
+
+#[test]
+fn subc_decodes_from_the_manual_encoding() {
+    use tricore_rs::decoder::{Decoder, Op};
+    // SUBC D3, D1, D2 as laid out in the manual: c | 0DH | - | b | a | 0BH.
+    // Like every RR form here the decoder reads D[b] from [19:16], so b is
+    // repeated in those don't-care bits
+    let d = Tc16Decoder::new().decode(0x30D2_210B).unwrap();
+    assert!(matches!(d.op, Op::Subc));
+    assert_eq!((d.rd, d.rs1, d.rs2), (3, 1, 2));
+    assert_eq!(tricore_rs::disasm::fmt_decoded(&d), "subc d3, d1, d2");
+    // 0x09 is not an RR instruction
+    assert!(Tc16Decoder::new().decode(enc_rr(0x09, 3, 1, 2)).is_none());
+}
+
+#[test]
+fn subc_and_subx_have_no_rc_form() {
+    use tricore_rs::decoder::{DecodeReason, Decoder};
+    // The RC slots a const9 SUBC/SUBX would take stay unknown, so D0 is never
+    // read in place of a missing immediate
+    let dec = Tc16Decoder::new();
+    for op2 in [0x0D, 0x0C] {
+        let err = dec.decode_full(enc_rc(op2, 1, 2, 5)).unwrap_err();
+        assert_eq!(err.reason, DecodeReason::UnknownOp2);
+    }
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    (cpu.gpr[0], cpu.gpr[2]) = (100, 10);
+    mem.write_u32(0, enc_rc(0x0C, 1, 2, 5)).unwrap();
+    assert!(cpu.step(&mut mem, &dec, &IntExecutor).is_err());
+    assert_eq!(cpu.gpr[1], 0, "an undecodable word leaves D1 alone");
+}
+
+#[test]
+fn sub_sets_carry_when_no_borrow_and_clears_it_on_borrow() {
+    // C is the carry-out of a + !b + 1
+    let (cpu, _) = run1(enc_rr(0x08, 3, 1, 2), 5, 3);
+    assert_eq!(cpu.gpr[3], 2);
+    assert!(cpu.psw.contains(Psw::C), "5 - 3 does not borrow");
+    let (cpu, _) = run1(enc_rr(0x08, 3, 1, 2), 3, 5);
+    assert_eq!(cpu.gpr[3], (-2i32) as u32);
+    assert!(!cpu.psw.contains(Psw::C), "3 - 5 borrows");
+    // Equal operands: no borrow, zero result
+    let (cpu, _) = run1(enc_rr(0x08, 3, 1, 2), 7, 7);
+    assert!(cpu.psw.contains(Psw::C) && cpu.psw.contains(Psw::Z));
+
+    // SUBC with C set subtracts nothing extra; with C clear it takes one more
+    for (carry, want, carry_out) in [(true, 2, true), (false, 1, true)] {
+        let mut mem = LinearMemory::new(16);
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(0);
+        (cpu.gpr[1], cpu.gpr[2]) = (5, 3);
+        cpu.psw.set(Psw::C, carry);
+        mem.write_u32(0, enc_rr(0x0D, 3, 1, 2)).unwrap();
+        cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+        assert_eq!(cpu.gpr[3], want);
+        assert_eq!(cpu.psw.contains(Psw::C), carry_out);
+    }
+}
+
+// RRR (op1=0x2B): c:31..28, d:27..24, op2:23..20, b:15..12, a:11..8
+fn enc_rrr(op2: u32, c: u32, d: u32, a: u32, b: u32) -> u32 { (c<<28) | (d<<24) | (op2<<20) | (b<<12) | (a<<8) | 0x2B }
+// RCR (op1=0xAB): c:31..28, d:27..24, op2:23..21, const9:20..12, a:11..8
+fn enc_rcr(op2: u32, c: u32, d: u32, a: u32, imm9: u32) -> u32 { (c<<28) | (d<<24) | (op2<<21) | ((imm9 & 0x1FF)<<12) | (a<<8) | 0xAB }
+
+/// Run a single instruction with D1 = x, D2 = y and the condition in D4.
+fn run_cond(raw: u32, x: u32, y: u32, cond: u32) -> Cpu {
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    (cpu.gpr[1], cpu.gpr[2], cpu.gpr[4]) = (x, y, cond);
+    mem.write_u32(0, raw).unwrap();
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    cpu
+}
+
+#[test]
+fn sel_picks_by_condition_and_cadd_increments_conditionally() {
+    use tricore_rs::decoder::Decoder;
+    // SEL D3, D4, D1, D2: D4 != 0 picks D1, else D2
+    let sel = enc_rrr(0x4, 3, 4, 1, 2);
+    assert_eq!(run_cond(sel, 10, 20, 1).gpr[3], 10);
+    assert_eq!(run_cond(sel, 10, 20, 0).gpr[3], 20);
+    // SELN inverts the test
+    let seln = enc_rrr(0x5, 3, 4, 1, 2);
+    assert_eq!(run_cond(seln, 10, 20, 0).gpr[3], 10);
+    assert_eq!(run_cond(seln, 10, 20, 7).gpr[3], 20);
+
+    // CADD D3, D4, D1, #1: D3 = D1 + 1 only when D4 != 0
+    let cadd = enc_rcr(0x0, 3, 4, 1, 1);
+    assert_eq!(run_cond(cadd, 41, 0, 1).gpr[3], 42);
+    assert_eq!(run_cond(cadd, 41, 0, 0).gpr[3], 41);
+    // CSUB D3, D4, D1, D2
+    let csub = enc_rrr(0x2, 3, 4, 1, 2);
+    assert_eq!(run_cond(csub, 50, 8, 1).gpr[3], 42);
+    assert_eq!(run_cond(csub, 50, 8, 0).gpr[3], 50);
+
+    // D[b] = D0 is still a register: RRR and RCR stay apart when b or const9 is 0
+    let mut mem = LinearMemory::new(16);
+    for (raw, want, text) in [(enc_rrr(0x0, 3, 4, 1, 0), 42, "cadd d3, d4, d1, d0"), (enc_rcr(0x0, 3, 4, 1, 0), 40, "cadd d3, d4, d1, 0x0")] {
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(0);
+        (cpu.gpr[0], cpu.gpr[1], cpu.gpr[4]) = (2, 40, 1);
+        mem.write_u32(0, raw).unwrap();
+        cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+        assert_eq!(cpu.gpr[3], want, "{text}");
+        assert_eq!(tricore_rs::disasm::fmt_decoded(&Tc16Decoder::new().decode(raw).unwrap()), text);
+    }
+
+    // Ordinary fallthrough instructions as far as control flow goes
+    let d = Tc16Decoder::new().decode(sel).unwrap();
+    assert!(!d.is_terminator() && d.branch_target(0).is_none());
+    assert_eq!(tricore_rs::disasm::fmt_decoded(&d), "sel d3, d4, d1, d2");
+}
+
+#[test]
+fn failed_decodes_report_op1_op2_and_a_reason() {
+    use tricore_rs::decoder::{DecodeError, DecodeReason, Decoder};
+    let dec = Tc16Decoder::new();
+    // RR 0x0B knows ADD (op2 0x00) but not op2 0x7F
+    let err = dec.decode_full(enc_rr(0x7F, 3, 1, 2)).unwrap_err();
+    assert_eq!(err, DecodeError { op1: 0x0B, op2: Some(0x7F), reason: DecodeReason::UnknownOp2 });
+    assert_eq!(err.to_string(), "op1 0x0b, op2 0x7f: unknown op2");
+    assert!(dec.decode(enc_rr(0x7F, 3, 1, 2)).is_none());
+    assert_eq!(dec.decode_full(0x0000_0003).unwrap_err().reason, DecodeReason::UnknownOp1);
+
+    // Only the first half-word of ADD D3, D1, D2 is available
+    let add = enc_rr(0x00, 3, 1, 2).to_le_bytes();
+    let err = dec.decode_bytes(&add[..2]).unwrap_err();
+    assert_eq!(err, DecodeError { op1: 0x0B, op2: None, reason: DecodeReason::Truncated });
+    assert_eq!(err.to_string(), "op1 0x0b: truncated");
+    assert_eq!(dec.decode_bytes(&add).unwrap().width, 4);
+    // A 16-bit MOV needs only its two bytes
+    assert_eq!(dec.decode_bytes(&[0x82, 0x10]).unwrap().width, 2);
+}
@@ -116,6 +116,39 @@ fn jeq_sbc_imm_taken() {
     assert_eq!(cpu.gpr[0], 7);
 }
 
+#[test]
+fn d15_branch_high_range_forms_land_32_bytes_further() {
+    // (low op1, high op1, field [15:12], D15, D1): each pair is taken for the same operands
+    let forms = [
+        (0x1Eu16, 0x9Eu16, 3u16, 3u32, 0u32), // JEQ D15,#3
+        (0x5E, 0xDE, 3, 0, 0),                // JNE D15,#3
+        (0x3E, 0xBE, 1, 9, 9),                // JEQ D15,D1
+        (0x7E, 0xFE, 1, 9, 4),                // JNE D15,D1
+    ];
+    let disp4 = 5u16;
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    for (lo, hi, field, d15, d1) in forms {
+        let mut targets = Vec::new();
+        for op1 in [lo, hi] {
+            let mut mem = LinearMemory::new(64);
+            let mut cpu = Cpu::new(CpuConfig::default());
+            cpu.reset(0);
+            cpu.gpr[15] = d15;
+            cpu.gpr[1] = d1;
+            let raw = (field << 12) | (disp4 << 8) | op1;
+            mem.write_u16(0, raw).unwrap();
+            let d = tricore_rs::decoder::Decoder::decode(&dec, raw as u32).expect("decode");
+            assert_eq!(d.width, 2);
+            cpu.step(&mut mem, &dec, &exec).unwrap();
+            targets.push(cpu.pc);
+        }
+        // Offsets are relative to the next instruction: disp4 * 2, and (disp4 + 16) * 2 for the high form
+        assert_eq!(targets[0], 2 + 2 * disp4 as u32, "op1 {lo:#04x}");
+        assert_eq!(targets[1] - targets[0], 32, "op1 {hi:#04x}");
+    }
+}
+
 #[test]
 fn jge_brc_signed_taken() {
     let mut mem = LinearMemory::new(128);
@@ -303,3 +336,131 @@ fn jnz_a_sbr_taken() {
     cpu.step(&mut mem, &dec, &exec).unwrap();
     assert_eq!(cpu.gpr[0], 5);
 }
+
+#[test]
+fn jli_links_a11_and_ji_jumps_through_address_registers() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+
+    // 0x00: MOV.U D0,#0x20 ; 0x04: MOV.A A4,D0 ; 0x06: JLI A4 ; 0x0A: (return point)
+    // 0x20: MOV.U D0,#0x31 ; 0x24: MOV.A A5,D0 ; 0x26: JI A5 (16-bit) -> 0x30 (bit 0 cleared)
+    // 0x30: JI A11 (32-bit) -> 0x0A
+    mem.write_u32(0x00, (0x20u32 << 12) | 0xBB).unwrap();
+    mem.write_u16(0x04, (4u16 << 8) | 0x60).unwrap();
+    mem.write_u32(0x06, (0x02u32 << 20) | (4 << 8) | 0x2D).unwrap();
+    mem.write_u32(0x20, (0x31u32 << 12) | 0xBB).unwrap();
+    mem.write_u16(0x24, (5u16 << 8) | 0x60).unwrap();
+    mem.write_u16(0x26, (5u16 << 8) | 0xDC).unwrap();
+    mem.write_u32(0x30, (0x03u32 << 20) | (11 << 8) | 0x2D).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &exec).unwrap(); }
+    assert_eq!(cpu.pc, 0x20);
+    assert_eq!(cpu.a[11], 0x0A, "JLI links the next instruction");
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &exec).unwrap(); }
+    assert_eq!(cpu.pc, 0x30);
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.pc, 0x0A);
+}
+
+#[test]
+fn loop_runs_body_count_plus_one_times_and_zero_count_falls_through() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+
+    // 0x00: MOV.U D1,#2 ; 0x04: MOV.A A4,D1
+    // 0x06: ADD D0,#5 (16-bit) ; 0x08: LOOP A4 -> 0x06 (16-bit, disp4=14 => -4 from 0x0A)
+    // 0x0A: LOOP A5 -> 0x00 (32-bit, A5 = 0: not taken) ; 0x0E: end
+    mem.write_u32(0x00, (1u32 << 28) | (2 << 12) | 0xBB).unwrap();
+    mem.write_u16(0x04, (1u16 << 12) | (4 << 8) | 0x60).unwrap();
+    mem.write_u16(0x06, (5u16 << 12) | 0xC2).unwrap();
+    mem.write_u16(0x08, (4u16 << 12) | (14 << 8) | 0xFC).unwrap();
+    let back_to_0 = (-14i32 as u32 >> 1) & 0x7FFF;
+    mem.write_u32(0x0A, (back_to_0 << 16) | (5 << 12) | 0xFD).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.run_until(&mut mem, &dec, &exec, 100, |c| c.pc == 0x0E).unwrap();
+    assert_eq!(cpu.gpr[0], 15, "A4 = 2 runs the body three times");
+    assert_eq!(cpu.a[4], 0xFFFF_FFFF, "the final not-taken LOOP still decrements");
+    assert_eq!(cpu.a[5], 0xFFFF_FFFF, "a zero count falls through and wraps");
+}
+
+// Encode 16-bit JZ.A (0xBC) / JNZ.A (0x7C) A[b], disp4 (SBR)
+fn enc_sbr_a(op1: u16, b: u16, disp4: u16) -> u16 {
+    ((b & 0xF) << 12) | ((disp4 & 0xF) << 8) | op1
+}
+
+#[test]
+fn jz_a_16bit_offset_is_zero_extended_and_32bit_is_signed() {
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    let step_from = |raw: &[u8], a: (usize, u32)| {
+        let mut mem = LinearMemory::new(64);
+        for (i, b) in raw.iter().enumerate() { mem.write_u8(0x10 + i as u32, *b).unwrap(); }
+        let mut cpu = Cpu::new(CpuConfig::default());
+        cpu.reset(0x10);
+        cpu.a[a.0] = a.1;
+        cpu.step(&mut mem, &dec, &exec).unwrap();
+        cpu.pc
+    };
+
+    // disp4 = 15 reaches forward +30 from the next insn; a sign-extended field would go back 2
+    let jz = enc_sbr_a(0xBC, 4, 15).to_le_bytes();
+    assert_eq!(step_from(&jz, (4, 0)), 0x12 + 30, "JZ.A taken");
+    assert_eq!(step_from(&jz, (4, 1)), 0x12, "JZ.A not taken");
+
+    let jnz = enc_sbr_a(0x7C, 5, 1).to_le_bytes();
+    assert_eq!(step_from(&jnz, (5, 7)), 0x14, "JNZ.A taken");
+    assert_eq!(step_from(&jnz, (5, 0)), 0x12, "JNZ.A not taken");
+
+    // 32-bit JZ.A (0xBD) with disp15 = -2 branches back to itself
+    let jz32 = enc_brr_addr(0xBD, 0, 4, 0, 0x7FFE).to_le_bytes();
+    assert_eq!(step_from(&jz32, (4, 0)), 0x10, "32-bit JZ.A taken backwards");
+    assert_eq!(step_from(&jz32, (4, 2)), 0x14, "32-bit JZ.A not taken");
+}
+
+#[test]
+fn control_flow_classification_and_branch_targets() {
+    use tricore_rs::decoder::Decoder;
+    let dec = Tc16Decoder::new();
+    let d = |raw: u32| dec.decode(raw).unwrap();
+    let pc = 0x100;
+
+    // j +4 (16-bit): unconditional branch, target counts from the next insn
+    let j = d(0x023C);
+    assert!(j.is_branch() && j.is_unconditional() && j.is_terminator() && !j.is_call());
+    assert_eq!(j.branch_target(pc), Some(0x106));
+
+    // jeq d1, d2, -8: conditional, still a terminator with a target
+    let jeq = d(enc_brr(0x5F, 0, 1, 2, 0x7FFC));
+    assert!(jeq.is_branch() && !jeq.is_unconditional() && jeq.is_terminator());
+    assert_eq!(jeq.branch_target(pc), Some(0xFC));
+
+    // call +8 falls through, so it does not end a block
+    let call = d(0x045C);
+    assert!(call.is_call() && !call.is_branch() && !call.is_terminator());
+    assert_eq!(call.branch_target(pc), Some(0x10A));
+
+    // calla 0x80000100: the immediate is already absolute
+    let calla = d((0x0080 << 16) | (0x80 << 8) | 0xED);
+    assert!(calla.is_call());
+    assert_eq!(calla.branch_target(pc), Some(0x8000_0100));
+
+    // ji a2: unconditional with no static target
+    let ji = d(0x02DC);
+    assert!(ji.is_branch() && ji.is_unconditional());
+    assert_eq!(ji.branch_target(pc), None);
+
+    let ret = d(0x0090_000D);
+    assert!(ret.is_return() && ret.is_unconditional() && ret.is_terminator() && !ret.is_branch());
+    assert_eq!(ret.branch_target(pc), None);
+
+    // add d2, d0, d1: plain data op
+    let add = d((2 << 28) | (1 << 16) | 0x0B);
+    assert!(!add.is_branch() && !add.is_call() && !add.is_return() && !add.is_unconditional() && !add.is_terminator());
+    assert_eq!(add.branch_target(pc), None);
+}
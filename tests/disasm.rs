@@ -18,3 +18,88 @@ fn disasm_add_mov() {
     assert!(s2.starts_with("mov d2, #0x1234"));
 }
 
+
+#[test]
+fn parts_split_load_into_register_and_base_offset() {
+    use tricore_rs::disasm::{fmt_decoded_parts, Operand};
+    let dec = Tc16Decoder::new();
+    // LD.W d2, [a3+4] (BO): op1=0x09, op2=0x24, off10=4
+    let raw = (0x24u32 << 22) | (4u32 << 16) | (3u32 << 12) | (2u32 << 8) | 0x09;
+    let d = dec.decode(raw).unwrap();
+    let parts = fmt_decoded_parts(&d);
+    assert_eq!(parts.mnemonic, "ld.w");
+    assert_eq!(parts.operands, vec![Operand::Reg(2), Operand::MemBaseOff { a: 3, off: 4 }]);
+    assert_eq!(parts.to_string(), fmt_decoded(&d));
+    assert_eq!(fmt_decoded(&d), "ld.w d2, [a3+0x4]");
+}
+
+#[test]
+fn pc_aware_format_resolves_forward_branch_to_absolute_target_and_label() {
+    use std::collections::HashMap;
+    use tricore_rs::disasm::fmt_decoded_at;
+    let dec = Tc16Decoder::new();
+    // 16-bit J disp8=4: target = next pc + 8
+    let d = dec.decode(0x043C).unwrap();
+    let mut labels = HashMap::new();
+    assert_eq!(fmt_decoded_at(&d, 0x8000_1000, &labels), "j 0x8000100a");
+    labels.insert(0x8000_100a, "loop_head".to_string());
+    assert_eq!(fmt_decoded_at(&d, 0x8000_1000, &labels), "j loop_head");
+    assert_eq!(fmt_decoded(&d), "j +0x8", "plain formatter keeps the relative offset");
+}
+
+#[test]
+fn bo_addressing_modes_render_distinctly() {
+    let dec = Tc16Decoder::new();
+    // BO: op2 at [27:22], off10 split [31:28]/[21:16], b at [15:12], a at [11:8]
+    let bo = |op1: u32, op2: u32, off10: u32| (((off10 >> 6) & 0xF) << 28) | (op2 << 22) | ((off10 & 0x3F) << 16) | (3 << 12) | (2 << 8) | op1;
+    let cases = [
+        (bo(0x09, 0x24, 4), "ld.w d2, [a3+0x4]"),
+        (bo(0x09, 0x04, 4), "ld.w d2, [a3+]0x4"),
+        (bo(0x09, 0x14, 4), "ld.w d2, [+a3]0x4"),
+        (bo(0x89, 0x24, 4), "st.w [a3+0x4], d2"),
+        (bo(0x89, 0x04, 4), "st.w [a3+]0x4, d2"),
+        (bo(0x89, 0x14, 4), "st.w [+a3]0x4, d2"),
+    ];
+    for (raw, text) in cases {
+        assert_eq!(fmt_decoded(&dec.decode(raw).unwrap()), text);
+    }
+    // ABS ignores the write-back flags: LD.W d2, [0x40]
+    let abs = (1u32 << 28) | (2 << 8) | 0x85;
+    assert_eq!(fmt_decoded(&dec.decode(abs).unwrap()), "ld.w d2, [0x40]");
+}
+
+#[test]
+fn styles_change_register_prefix_hex_case_and_operand_order() {
+    use std::collections::HashMap;
+    use tricore_rs::disasm::{fmt_decoded_at, fmt_decoded_styled, fmt_decoded_with_style, MnemonicStyle};
+    let dec = Tc16Decoder::new();
+    // ADDI d3, d1, #0x1f
+    let d = dec.decode((3u32 << 28) | (0x1Fu32 << 12) | (1u32 << 8) | 0x1B).unwrap();
+    assert_eq!(fmt_decoded_styled(&d, MnemonicStyle::Default), fmt_decoded(&d));
+    assert_eq!(fmt_decoded_styled(&d, MnemonicStyle::Default), "addi d3, d1, 0x1f");
+    assert_eq!(fmt_decoded_styled(&d, MnemonicStyle::Gnu), "addi %d3, %d1, 0x1f");
+    assert_eq!(fmt_decoded_styled(&d, MnemonicStyle::Tasking), "addi d3, d1, 0x1F");
+    assert_eq!(fmt_decoded_styled(&d, MnemonicStyle::DestLast), "addi d1, 0x1f, d3");
+
+    // Only a written first operand moves: branches, compares and stores keep their order
+    let jeq = dec.decode((2u32 << 15) | (2u32 << 12) | (1u32 << 8) | 0x5F).unwrap();
+    assert_eq!(fmt_decoded_styled(&jeq, MnemonicStyle::DestLast), fmt_decoded(&jeq));
+    assert_eq!(fmt_decoded_with_style(&jeq, 0, MnemonicStyle::DestLast, |_| None), "jeq d1, d2, 0x00000008");
+    let cmp = dec.decode((0x18u32 << 20) | (2u32 << 16) | (1u32 << 8) | 0x0B).unwrap();
+    assert_eq!(fmt_decoded_styled(&cmp, MnemonicStyle::DestLast), "cmp d1, d2");
+    let st = dec.decode((0x24u32 << 22) | (15u32 << 12) | 0x89).unwrap();
+    assert_eq!(fmt_decoded_styled(&st, MnemonicStyle::DestLast), "st.w [a15+0x0], d0");
+
+    // Memory operands carry the register prefix inside the brackets
+    let ld = dec.decode((0x24u32 << 22) | (4u32 << 16) | (3u32 << 12) | (2u32 << 8) | 0x09).unwrap();
+    assert_eq!(fmt_decoded_styled(&ld, MnemonicStyle::Gnu), "ld.w %d2, [%a3+0x4]");
+
+    // Absolute targets follow the hex case; labels are printed verbatim
+    let j = dec.decode(0x043C).unwrap();
+    assert_eq!(fmt_decoded_with_style(&j, 0x8000_1000, MnemonicStyle::Tasking, |_| None), "j 0x8000100A");
+    let labels: HashMap<u32, String> = [(0x8000_100a, "loop_head".to_string())].into_iter().collect();
+    assert_eq!(fmt_decoded_with_style(&j, 0x8000_1000, MnemonicStyle::Gnu, |a| labels.get(&a).cloned()), fmt_decoded_at(&j, 0x8000_1000, &labels));
+
+    assert_eq!("dest-last".parse::<MnemonicStyle>(), Ok(MnemonicStyle::DestLast));
+    assert!("intel".parse::<MnemonicStyle>().unwrap_err().contains("gnu"));
+}
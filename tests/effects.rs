@@ -0,0 +1,39 @@
+use tricore_rs::effects::{fmt_effects, step_with_effects, Effect};
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Bus, Cpu, CpuConfig, LinearMemory};
+
+// RR (op1=0x0B): c:31..28, op2:27..20, b:19..16, a:11..8
+fn enc_rr(op2: u32, c: u32, a: u32, b: u32) -> u32 { (c<<28) | (op2<<20) | (b<<16) | (a<<8) | 0x0B }
+
+#[test]
+fn add_reports_destination_and_changed_flags() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 1;
+    cpu.gpr[2] = 0xFFFF_FFFF;
+    cpu.gpr[3] = 0x55;
+    // add d3, d1, d2 -> 0 with carry out
+    mem.write_u32(0, enc_rr(0x00, 3, 1, 2)).unwrap();
+    let fx = step_with_effects(&mut cpu, &mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(fx[0], Effect::D { reg: 3, val: 0 });
+    assert!(fx.contains(&Effect::Flag { name: "Z", set: true }));
+    assert!(fx.contains(&Effect::Flag { name: "C", set: true }));
+    assert!(!fx.iter().any(|e| matches!(e, Effect::D { reg: 1 | 2, .. } | Effect::Mem { .. })));
+    assert_eq!(fmt_effects(&fx), "d3 <- 0x0 ; C=1 Z=1");
+}
+
+#[test]
+fn store_reports_memory_write() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.a[2] = 0x20;
+    cpu.gpr[5] = 0x1234_5678;
+    // st.w [a2]4, d5 (BO, op1=0x89, op2=0x24)
+    mem.write_u32(0, (0x24 << 22) | (4 << 16) | (2 << 12) | (5 << 8) | 0x89).unwrap();
+    let fx = step_with_effects(&mut cpu, &mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(fx, vec![Effect::Mem { addr: 0x24, width: 4, val: 0x1234_5678 }]);
+    assert_eq!(fmt_effects(&fx), "[0x00000024].4 <- 0x12345678");
+}
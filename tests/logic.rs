@@ -2,6 +2,7 @@ use tricore_rs::exec::IntExecutor;
 use tricore_rs::isa::tc16::Tc16Decoder;
 use tricore_rs::{Cpu, CpuConfig, LinearMemory};
 use tricore_rs::Bus;
+use tricore_rs::cpu::Psw;
 
 // Helpers for MOV.U, logical RR/RC
 fn enc_movu(c: u32, imm16: u32) -> u32 { (c << 28) | (imm16 << 12) | 0xBB }
@@ -53,3 +54,147 @@ fn and_or_xor_rr_and_rc() {
     assert_eq!(cpu.gpr[8], 0x0000_0000);
     assert_eq!(cpu.gpr[9], 0x0000_0001);
 }
+
+#[test]
+fn shl_negative_count_shifts_right_logically() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 0x8000_00F0;
+    // shl d0, d1, #-4 (RC op1=0x8B, op2=0x20, const9 = -4)
+    mem.write_u32(0, (0u32<<28) | (0x20u32<<21) | (((-4i32 as u32) & 0x1FF)<<12) | (1u32<<8) | 0x8B).unwrap();
+    // sar d2, d1, #-4 => arithmetic right keeps the sign
+    mem.write_u32(4, (2u32<<28) | (0x22u32<<21) | (((-4i32 as u32) & 0x1FF)<<12) | (1u32<<8) | 0x8B).unwrap();
+    // sar d3, d1, #4 => positive SHA count shifts left, top bits lost set C and V
+    mem.write_u32(8, (3u32<<28) | (0x22u32<<21) | (4u32<<12) | (1u32<<8) | 0x8B).unwrap();
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[0], 0x0800_000F);
+    assert!(!cpu.psw.contains(Psw::N));
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[2], 0xF800_000F);
+    assert!(cpu.psw.contains(Psw::N));
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[3], 0x0000_0F00);
+    assert!(cpu.psw.contains(Psw::C));
+    assert!(cpu.psw.contains(Psw::V));
+}
+
+#[test]
+fn shift_by_zero_sets_zero_and_keeps_carry() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.psw.insert(Psw::C);
+    // shl d0, d1, #0 with d1 = 0
+    mem.write_u32(0, (0u32<<28) | (0x20u32<<21) | (1u32<<8) | 0x8B).unwrap();
+    // shl d2, d3, #0 with d3 = 5 (non-zero result clears Z)
+    mem.write_u32(4, (2u32<<28) | (0x20u32<<21) | (3u32<<8) | 0x8B).unwrap();
+    cpu.gpr[3] = 5;
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[0], 0);
+    assert!(cpu.psw.contains(Psw::Z));
+    assert!(cpu.psw.contains(Psw::C), "SH leaves carry untouched");
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[2], 5);
+    assert!(!cpu.psw.contains(Psw::Z));
+    assert!(cpu.psw.contains(Psw::C));
+}
+
+#[test]
+fn add16_flags_match_add32_on_overflow() {
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    for &(x, y) in &[(0x7FFF_FFFFu32, 1u32), (0x8000_0000, 0x8000_0000), (0x4000_0000, 0x4000_0000), (5, 0xFFFF_FFFB)] {
+        // 16-bit ADD D1, D2 (SRR, op1=0x42)
+        let mut mem = LinearMemory::new(16);
+        let mut cpu16 = Cpu::new(CpuConfig::default());
+        cpu16.reset(0);
+        cpu16.gpr[1] = x;
+        cpu16.gpr[2] = y;
+        mem.write_u16(0, (2u16 << 12) | (1u16 << 8) | 0x42).unwrap();
+        cpu16.step(&mut mem, &dec, &exec).unwrap();
+
+        // 32-bit ADD D3, D1, D2 (RR, op1=0x0B, op2=0x00)
+        let mut mem = LinearMemory::new(16);
+        let mut cpu32 = Cpu::new(CpuConfig::default());
+        cpu32.reset(0);
+        cpu32.gpr[1] = x;
+        cpu32.gpr[2] = y;
+        mem.write_u32(0, (3u32<<28) | (2u32<<16) | (1u32<<8) | 0x0B).unwrap();
+        cpu32.step(&mut mem, &dec, &exec).unwrap();
+
+        assert_eq!(cpu16.gpr[1], cpu32.gpr[3], "result for {x:#x}+{y:#x}");
+        assert_eq!(cpu16.psw.bits(), cpu32.psw.bits(), "flags for {x:#x}+{y:#x}");
+    }
+    // Sanity: the first pair overflows and leaves the sticky bits set
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 0x7FFF_FFFF;
+    cpu.gpr[2] = 1;
+    mem.write_u16(0, (2u16 << 12) | (1u16 << 8) | 0x42).unwrap();
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert!(cpu.psw.contains(Psw::V) && cpu.psw.contains(Psw::SV));
+    assert!(cpu.psw.contains(Psw::AV) && cpu.psw.contains(Psw::SAV));
+}
+
+// RRPW (op1=0x37/0x77): c:31..28, pos:27..23, op2:22..21, width:20..16, b:15..12, a:11..8
+fn enc_rrpw(op1: u32, op2: u32, c: u32, a: u32, b: u32, pos: u32, width: u32) -> u32 {
+    (c << 28) | (pos << 23) | (op2 << 21) | (width << 16) | (b << 12) | (a << 8) | op1
+}
+
+/// Run one instruction with D1 = x, D2 = y and return D3.
+fn run_d3(raw: u32, x: u32, y: u32) -> u32 {
+    let mut mem = LinearMemory::new(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    (cpu.gpr[1], cpu.gpr[2], cpu.gpr[3]) = (x, y, 0xDEAD_BEEF);
+    mem.write_u32(0, raw).unwrap();
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    cpu.gpr[3]
+}
+
+#[test]
+fn extr_zero_and_sign_extends_and_insert_crosses_the_half_word() {
+    let extr_u = |pos, width| enc_rrpw(0x37, 0x3, 3, 1, 0, pos, width);
+    let extr = |pos, width| enc_rrpw(0x37, 0x2, 3, 1, 0, pos, width);
+    // Bits [11:4] of 0x12345A7C are 0xA7
+    assert_eq!(run_d3(extr_u(4, 8), 0x1234_5A7C, 0), 0xA7);
+    assert_eq!(run_d3(extr(4, 8), 0x1234_5A7C, 0), 0xFFFF_FFA7);
+    assert_eq!(run_d3(extr(4, 8), 0x1234_527C, 0), 0x27);
+    // Width 0 extracts nothing; a field past bit 31 stops there
+    assert_eq!(run_d3(extr(4, 0), 0xFFFF_FFFF, 0), 0);
+    assert_eq!(run_d3(extr_u(28, 8), 0xF000_0000, 0), 0xF);
+    assert_eq!(run_d3(extr(28, 8), 0x8000_0000, 0), 0xFFFF_FFF8);
+
+    // INSERT D3, D1, D2, #12, #8 writes D2's low byte over bits [19:12]
+    let insert = |pos, width| enc_rrpw(0x37, 0x0, 3, 1, 2, pos, width);
+    assert_eq!(run_d3(insert(12, 8), 0xFFFF_FFFF, 0xA5), 0xFFFA_5FFF);
+    assert_eq!(run_d3(insert(12, 8), 0, 0xFFFF_FFFF), 0x000F_F000);
+    assert_eq!(run_d3(insert(12, 0), 0x1234_5678, 0xFF), 0x1234_5678);
+    assert_eq!(run_d3(insert(28, 8), 0, 0xFF), 0xF000_0000);
+
+    // DEXTR D3, D1, D2, #8: the middle word of D1:D2 shifted left by 8
+    assert_eq!(run_d3(enc_rrpw(0x77, 0x0, 3, 1, 2, 8, 0), 0x1122_3344, 0x5566_7788), 0x2233_4455);
+    assert_eq!(run_d3(enc_rrpw(0x77, 0x0, 3, 1, 2, 0, 0), 0x1122_3344, 0x5566_7788), 0x1122_3344);
+}
+
+#[test]
+fn clz_clo_cls_count_leading_bits() {
+    // RR op1=0x0F: CLZ/CLO/CLS D3, D1 with op2 0x1B/0x1C/0x1D
+    let rr = |op2: u32| (3 << 28) | (op2 << 20) | (1 << 8) | 0x0F;
+    assert_eq!(run_d3(rr(0x1B), 0, 0), 32);
+    assert_eq!(run_d3(rr(0x1B), 0x8000_0000, 0), 0);
+    assert_eq!(run_d3(rr(0x1B), 0x0000_FFFF, 0), 16);
+    assert_eq!(run_d3(rr(0x1C), 0xFF00_0000, 0), 8);
+    assert_eq!(run_d3(rr(0x1C), 0x7FFF_FFFF, 0), 0);
+    // CLS counts the sign bits after the sign itself
+    assert_eq!(run_d3(rr(0x1D), (-3i32) as u32, 0), 29);
+    assert_eq!(run_d3(rr(0x1D), 1, 0), 30);
+    assert_eq!(run_d3(rr(0x1D), 0, 0), 31);
+    assert_eq!(run_d3(rr(0x1D), 0x4000_0000, 0), 0);
+}
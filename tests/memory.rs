@@ -67,6 +67,36 @@ fn ldb_post_and_pre_increment() {
     assert_eq!(cpu.a[1], 12);
 }
 
+#[test]
+fn ldw_stw_pre_increment_with_negative_offset_decrements_base() {
+    let mut mem = LinearMemory::new(128);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    mem.write_u32(56, 0xCAFE_F00D).unwrap();
+    cpu.a[1] = 64;
+    cpu.a[2] = 100;
+    cpu.gpr[5] = 0x1234_5678;
+
+    // off10 = -8 (0x3F8): pre-increment by a negative offset is a pre-decrement
+    let ldw_pre = enc_ldw_bo_mode(0x14, 3, 1, 0x3F8); // A1-=8 then load [A1]
+    let stw_pre = enc_st_bo(0x14, 5, 2, 0x3FC); // A2-=4 then store [A2]
+    mem.write_u32(0, ldw_pre).unwrap();
+    mem.write_u32(4, stw_pre).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let d = dec.decode(ldw_pre).expect("decode");
+    assert!(d.wb && d.pre);
+    assert_eq!(d.imm as i32, -8);
+
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.gpr[3], 0xCAFE_F00D);
+    assert_eq!(cpu.a[1], 56);
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(mem.read_u32(96).unwrap(), 0x1234_5678);
+    assert_eq!(cpu.a[2], 96);
+}
+
 #[test]
 fn decode_ldw_post_inc_fields() {
     let dec = Tc16Decoder::new();
@@ -420,3 +450,203 @@ fn ldh_and_ldhu_with_alignment() {
 }
 
 // (Byte/halfword stores covered indirectly; focused word store remains tested.)
+
+#[test]
+fn store_into_rx_region_traps_with_perm() {
+    use tricore_rs::{Access, Perms, Trap};
+    // Code at 0x1000 (r-x) stores D1 to [A2]+0 where A2 points back into the code region
+    let code = enc_stw_bo(1, 2, 0).to_le_bytes().to_vec();
+    let mut mem = LinearMemory::with_regions(vec![
+        (0x1000, [code, vec![0; 12]].concat(), Perms::from_rwx("r-x")),
+        (0x2000, vec![0; 16], Perms::from_rwx("rw-")),
+    ]).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0x1000);
+    cpu.a[2] = 0x1008;
+    cpu.gpr[1] = 0xDEAD_BEEF;
+    let r = cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor);
+    assert!(matches!(r, Err(Trap::Perm { addr: 0x1008, access: Access::Write })), "{r:?}");
+    assert_eq!(cpu.pc, 0x1000);
+
+    // Same store into the rw- region succeeds
+    cpu.a[2] = 0x2004;
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(mem.read_u32(0x2004).unwrap(), 0xDEAD_BEEF);
+    // Gaps between regions are unmapped, not silently zero
+    assert!(mem.read_u8(0x1800).is_err());
+}
+
+#[test]
+fn execute_from_rw_region_traps_with_perm() {
+    use tricore_rs::{Access, Perms, Trap};
+    // 16-bit MOV D0,#1 placed in a data region
+    let mut mem = LinearMemory::with_regions(vec![(0x3000, vec![0x82, 0x10, 0, 0], Perms::from_rwx("rw-"))]).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0x3000);
+    let r = cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor);
+    assert!(matches!(r, Err(Trap::Perm { addr: 0x3000, access: Access::Fetch })), "{r:?}");
+    assert_eq!(cpu.gpr[0], 0);
+    // Data reads of the same bytes are fine
+    assert_eq!(mem.read_u16(0x3000).unwrap(), 0x1082);
+}
+
+#[test]
+fn fetch_checks_every_byte_of_a_32bit_instruction() {
+    use tricore_rs::{Access, Perms, Trap};
+    // MOV D0, #1 (32-bit, op1 0x3B) whose upper halfword sits in a data region
+    let raw = ((1u32 << 12) | 0x3B).to_le_bytes();
+    let mut mem = LinearMemory::with_regions(vec![
+        (0x1000, raw[..2].to_vec(), Perms::from_rwx("r-x")),
+        (0x1002, [raw[2..].to_vec(), vec![0; 6]].concat(), Perms::from_rwx("rw-")),
+    ]).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0x1000);
+    let r = cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor);
+    assert!(matches!(r, Err(Trap::Perm { addr: 0x1000, access: Access::Fetch })), "{r:?}");
+    assert_eq!(cpu.gpr[0], 0);
+
+    // A 16-bit MOV D0, #1 in the last two bytes of memory still runs
+    let mut mem = LinearMemory::with_regions(vec![(0x2000, vec![0x82, 0x10], Perms::from_rwx("r-x"))]).unwrap();
+    cpu.reset(0x2000);
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(cpu.gpr[0], 1);
+}
+
+#[test]
+fn regions_too_far_apart_are_rejected_not_allocated() {
+    use tricore_rs::Perms;
+    let err = LinearMemory::with_regions(vec![
+        (0x0, vec![0; 4], Perms::from_rwx("rwx")),
+        (0x8000_0000, vec![0; 4], Perms::from_rwx("rwx")),
+    ]).err().unwrap();
+    assert!(err.to_string().contains("regions span 0x80000004 bytes"), "{err}");
+}
+
+#[test]
+fn store_into_read_only_range_of_flat_memory_traps() {
+    use tricore_rs::{Access, Trap};
+    // Flat 0x100-byte buffer: code + "flash" in [0x00, 0x40), RAM above
+    let mut mem = LinearMemory::new(0x100);
+    mem.write_u32(0, enc_stw_bo(1, 2, 0)).unwrap();
+    mem.set_read_only(0, 0x40);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.gpr[1] = 0x1234_5678;
+    cpu.a[2] = 0x3C;
+    let r = cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor);
+    assert!(matches!(r, Err(Trap::Perm { addr: 0x3C, access: Access::Write })), "{r:?}");
+    assert_eq!(mem.read_u32(0x3C).unwrap(), 0);
+
+    // A store straddling the ROM end still traps; RAM stores go through
+    assert!(mem.write_u32(0x3E, 1).is_err());
+    cpu.a[2] = 0x40;
+    cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).unwrap();
+    assert_eq!(mem.read_u32(0x40).unwrap(), 0x1234_5678);
+}
+
+#[test]
+fn st_d_then_ld_d_round_trip_a_64bit_pair() {
+    use tricore_rs::Trap;
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.a[2] = 0x20;
+    cpu.gpr[4] = 0x89AB_CDEF; // low word
+    cpu.gpr[5] = 0x0123_4567; // high word
+
+    // ST.D [A2]+8, E4 ; LD.D E6, [A2]+8 (BO base+off10, op2=0x25)
+    mem.write_u32(0, enc_st_bo(0x25, 4, 2, 8)).unwrap();
+    mem.write_u32(4, enc_ld_bo(0x25, 6, 2, 8)).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(mem.read_u32(0x28).unwrap(), 0x89AB_CDEF);
+    assert_eq!(mem.read_u32(0x2C).unwrap(), 0x0123_4567);
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!((cpu.gpr[6], cpu.gpr[7]), (0x89AB_CDEF, 0x0123_4567));
+
+    // Word-aligned but not doubleword-aligned
+    cpu.reset(4);
+    cpu.a[2] = 0x24;
+    let r = cpu.step(&mut mem, &dec, &exec);
+    assert!(matches!(r, Err(Trap::Unaligned { addr: 0x2C })), "{r:?}");
+
+    // An odd E register is not a pair
+    assert!(dec.decode(enc_ld_bo(0x25, 5, 2, 0)).is_none());
+    let err = dec.decode_full(enc_ld_bo(0x25, 5, 2, 0)).unwrap_err();
+    assert_eq!((err.op2, err.reason), (Some(0x25), tricore_rs::decoder::DecodeReason::OddRegisterPair));
+}
+
+// Encode a 16-bit SLR/SSR/SRO/SLRO/SSRO load or store: [15:12] and [11:8]
+// carry the format's two 4-bit fields
+fn enc16(op1: u32, hi: u32, lo: u32) -> u32 {
+    (hi << 12) | (lo << 8) | op1
+}
+
+#[test]
+fn decode_16bit_load_store_forms() {
+    use tricore_rs::disasm::fmt_decoded;
+    let dec = Tc16Decoder::new();
+    let cases = [
+        // SLR / SSR: [A[b]] and [A[b]+]
+        (enc16(0x14, 2, 1), "ld.bu d1, [a2+0x0]"),
+        (enc16(0x94, 2, 1), "ld.h d1, [a2+0x0]"),
+        (enc16(0x54, 2, 1), "ld.w d1, [a2+0x0]"),
+        (enc16(0x04, 2, 1), "ld.bu d1, [a2+]0x1"),
+        (enc16(0x84, 2, 1), "ld.h d1, [a2+]0x2"),
+        (enc16(0x44, 2, 1), "ld.w d1, [a2+]0x4"),
+        (enc16(0x34, 2, 3), "st.b [a2+0x0], d3"),
+        (enc16(0xB4, 2, 3), "st.h [a2+0x0], d3"),
+        (enc16(0x74, 2, 3), "st.w [a2+0x0], d3"),
+        (enc16(0x24, 2, 3), "st.b [a2+]0x1, d3"),
+        (enc16(0xA4, 2, 3), "st.h [a2+]0x2, d3"),
+        (enc16(0x64, 2, 3), "st.w [a2+]0x4, d3"),
+        // SRO: D15 at [A[b]] + scaled off4
+        (enc16(0x0C, 2, 3), "ld.bu d15, [a2+0x3]"),
+        (enc16(0x8C, 2, 3), "ld.h d15, [a2+0x6]"),
+        (enc16(0x4C, 2, 3), "ld.w d15, [a2+0xc]"),
+        (enc16(0x2C, 2, 3), "st.b [a2+0x3], d15"),
+        (enc16(0xAC, 2, 3), "st.h [a2+0x6], d15"),
+        (enc16(0x6C, 2, 3), "st.w [a2+0xc], d15"),
+        // SLRO / SSRO: D[c] / D[a] at [A15] + scaled off4
+        (enc16(0x08, 3, 1), "ld.bu d1, [a15+0x3]"),
+        (enc16(0x88, 3, 1), "ld.h d1, [a15+0x6]"),
+        (enc16(0x48, 3, 1), "ld.w d1, [a15+0xc]"),
+        (enc16(0x28, 3, 1), "st.b [a15+0x3], d1"),
+        (enc16(0xA8, 3, 1), "st.h [a15+0x6], d1"),
+        (enc16(0x68, 3, 1), "st.w [a15+0xc], d1"),
+    ];
+    for (raw, text) in cases {
+        let d = dec.decode(raw).unwrap_or_else(|| panic!("{raw:#06x} undecodable"));
+        assert_eq!(d.width, 2, "{text}");
+        // the [A[b]+] forms are the SLR/SSR opcodes with bit 4 clear
+        assert_eq!(d.wb, raw & 0x1F == 0x04, "{text}");
+        assert_eq!(fmt_decoded(&d), text, "{raw:#06x}");
+    }
+}
+
+#[test]
+fn ldw_16bit_post_increment_walks_a_word_array() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    mem.write_u32(0x20, 0x1111_1111).unwrap();
+    mem.write_u32(0x24, 0x2222_2222).unwrap();
+    cpu.a[4] = 0x20;
+    cpu.a[5] = 0x30;
+
+    // LD.W D1, [A4+] ; LD.W D2, [A4+] ; ST.W [A5+], D2 (16-bit)
+    mem.write_u16(0, enc16(0x44, 4, 1) as u16).unwrap();
+    mem.write_u16(2, enc16(0x44, 4, 2) as u16).unwrap();
+    mem.write_u16(4, enc16(0x64, 5, 2) as u16).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    for _ in 0..3 { cpu.step(&mut mem, &dec, &exec).unwrap(); }
+    assert_eq!((cpu.gpr[1], cpu.gpr[2]), (0x1111_1111, 0x2222_2222));
+    assert_eq!(cpu.a[4], 0x28);
+    assert_eq!(mem.read_u32(0x30).unwrap(), 0x2222_2222);
+    assert_eq!(cpu.a[5], 0x34);
+    assert_eq!(cpu.pc, 6);
+}
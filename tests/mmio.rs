@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Bus, Cpu, CpuConfig, LinearMemory, MmioBus, MmioDevice};
+
+// Encode ST.B A[b], off10, D[a] (BO): op1=0x89, op2=0x20 at [27:22]
+fn enc_stb_bo(a: u32, b: u32, off10: u32) -> u32 {
+    let off_hi4 = (off10 >> 6) & 0xF;
+    let off_lo6 = off10 & 0x3F;
+    (off_hi4 << 28) | (0x20 << 22) | (off_lo6 << 16) | (b << 12) | (a << 8) | 0x89
+}
+
+/// UART-like device: writes to offset 0 (data register) are captured, reads of offset 4 report "ready".
+struct FakeUart { tx: Rc<RefCell<Vec<(u32, u8, u32)>>> }
+
+impl MmioDevice for FakeUart {
+    fn read(&mut self, offset: u32, _width: u8) -> anyhow::Result<u32> {
+        Ok(if offset == 4 { 1 } else { 0 })
+    }
+    fn write(&mut self, offset: u32, width: u8, val: u32) -> anyhow::Result<()> {
+        self.tx.borrow_mut().push((offset, width, val));
+        Ok(())
+    }
+}
+
+#[test]
+fn st_b_to_uart_register_reaches_device() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut bus = MmioBus::new(LinearMemory::new(0x100));
+    bus.add_handler(0xF000_0000..0xF000_0010, Box::new(FakeUart { tx: log.clone() }));
+
+    // st.b [a2]+0, d1 ; st.b [a3]+4, d1 (plain RAM)
+    bus.write_u32(0, enc_stb_bo(1, 2, 0)).unwrap();
+    bus.write_u32(4, enc_stb_bo(1, 3, 4)).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    cpu.a[2] = 0xF000_0000;
+    cpu.a[3] = 0x80;
+    cpu.gpr[1] = 0x41; // 'A'
+    let dec = Tc16Decoder::new();
+    cpu.step(&mut bus, &dec, &IntExecutor).unwrap();
+    cpu.step(&mut bus, &dec, &IntExecutor).unwrap();
+
+    assert_eq!(*log.borrow(), vec![(0, 1, 0x41)]);
+    assert_eq!(bus.inner.read_u8(0x84).unwrap(), 0x41);
+    assert_eq!(bus.read_u32(0xF000_0004).unwrap(), 1);
+    assert_eq!(bus.read_u8(0x84).unwrap(), 0x41);
+}
@@ -0,0 +1,122 @@
+use tricore_rs::decoder::{Decoded, Decoder, Op};
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Bus, Cpu, CpuConfig, CycleCosts, LinearMemory, Trap};
+
+// 16-bit ADD D[a], #const4 (SRC, op1=0xC2)
+fn enc_add_c4(a: u16, c4: u16) -> u16 { (c4 << 12) | (a << 8) | 0xC2 }
+
+/// Tc16 plus a made-up 0xFFFF halfword that decodes to SYSCALL, so tests can reach `Trap::Break`.
+struct BreakDecoder(Tc16Decoder);
+impl Decoder for BreakDecoder {
+    fn decode(&self, raw32: u32) -> Option<Decoded> {
+        if raw32 & 0xFFFF == 0xFFFF {
+            return Some(Decoded { op: Op::Syscall, width: 2, rd: 0, rs1: 0, rs2: 0, imm: 0, imm2: 0, abs: false, wb: false, pre: false });
+        }
+        self.0.decode(raw32)
+    }
+
+    fn width_hint(&self, first_byte: u8) -> u8 {
+        self.0.width_hint(first_byte)
+    }
+}
+
+fn counting_program(n: u32) -> LinearMemory {
+    let mut mem = LinearMemory::new(64);
+    for i in 0..n { mem.write_u16(i * 2, enc_add_c4(0, 1)).unwrap(); }
+    mem
+}
+
+#[test]
+fn run_until_stops_when_budget_exhausted() {
+    let mut mem = counting_program(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    let n = cpu.run_until(&mut mem, &Tc16Decoder::new(), &IntExecutor, 5, |_| false).unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(cpu.gpr[0], 5);
+    assert_eq!(cpu.pc, 10);
+}
+
+#[test]
+fn run_until_stops_when_predicate_hits() {
+    let mut mem = counting_program(16);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    let n = cpu.run_until(&mut mem, &Tc16Decoder::new(), &IntExecutor, 100, |c| c.gpr[0] == 3).unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(cpu.pc, 6);
+    // Already satisfied: no steps taken
+    let n = cpu.run_until(&mut mem, &Tc16Decoder::new(), &IntExecutor, 100, |c| c.gpr[0] == 3).unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn run_until_propagates_traps_without_advancing_pc() {
+    let mut mem = counting_program(2);
+    mem.write_u16(4, 0xFFFF).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    let dec = BreakDecoder(Tc16Decoder::new());
+    let r = cpu.run_until(&mut mem, &dec, &IntExecutor, 100, |_| false);
+    assert!(matches!(r, Err(Trap::Break)));
+    assert_eq!(cpu.gpr[0], 2);
+    assert_eq!(cpu.pc, 4);
+
+    // Execute-time traps also leave PC on the faulting instruction: DIV.U D3, D1, D2 with D2 = 0
+    let mut mem = counting_program(1);
+    mem.write_u32(2, (3u32<<28) | (0x2Fu32<<20) | (2u32<<16) | (1u32<<8) | 0x0B).unwrap();
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    let r = cpu.run_until(&mut mem, &Tc16Decoder::new(), &IntExecutor, 100, |_| false);
+    assert!(matches!(r, Err(Trap::DivideByZero)));
+    assert_eq!(cpu.pc, 2);
+}
+
+#[test]
+fn step_traced_reports_each_executed_instruction_in_order() {
+    let mut mem = counting_program(3);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        cpu.step_traced(&mut mem, &Tc16Decoder::new(), &IntExecutor, |pc, d, cpu| {
+            seen.push((pc, tricore_rs::disasm::fmt_decoded(d), cpu.gpr[0]));
+        }).unwrap();
+    }
+    // The hook sees the state after the instruction, and PC of the instruction itself
+    let want: Vec<(u32, String, u32)> = (0..3).map(|i| (i * 2, "addi d0, d0, 0x1".to_string(), i + 1)).collect();
+    assert_eq!(seen, want);
+}
+
+#[test]
+fn cycles_sum_the_configured_cost_of_each_executed_instruction() {
+    // add d0,#1; st.w [a15]0x0, d0; ld.w d1, [a15]0x0; j +0
+    let program = [enc_add_c4(0, 1), 0x0068, 0x0148, 0x003C];
+    let run = |costs: CycleCosts| {
+        let mut mem = LinearMemory::new(64);
+        for (i, h) in program.iter().enumerate() { mem.write_u16(i as u32 * 2, *h).unwrap(); }
+        let mut cpu = Cpu::new(CpuConfig { cycle_costs: costs, ..CpuConfig::default() });
+        cpu.reset(0);
+        cpu.a[15] = 0x20;
+        assert_eq!(cpu.run_until(&mut mem, &Tc16Decoder::new(), &IntExecutor, 4, |_| false).unwrap(), 4);
+        cpu
+    };
+    let d = CycleCosts::default();
+    let cpu = run(d);
+    assert_eq!(cpu.cycles(), (d.alu + d.store + d.load + d.branch) as u64);
+    assert_eq!(cpu.cycles(), 7);
+
+    // Costs only change the count, never the result
+    let slow = run(CycleCosts { load: 10, ..d });
+    assert_eq!(slow.cycles(), 15);
+    assert_eq!((slow.gpr[1], slow.pc), (cpu.gpr[1], cpu.pc));
+    assert_eq!(cpu.gpr[1], 1);
+
+    // A trapping instruction is not charged
+    let mut mem = LinearMemory::new(8);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    assert!(cpu.step(&mut mem, &Tc16Decoder::new(), &IntExecutor).is_err());
+    assert_eq!(cpu.cycles(), 0);
+}
@@ -53,3 +53,17 @@ fn jeq_sbc_branch_taken_smoke() {
     cpu.step(&mut mem, &dec, &exec).unwrap(); // MOV D0,#2
     assert_eq!(cpu.gpr[0], 2);
 }
+
+#[test]
+fn width_hint_follows_op1_bit0_and_matches_decoded_widths() {
+    use tricore_rs::decoder::Decoder;
+    let dec = Tc16Decoder::new();
+    // mov d1,#2; add d0,d1 (SRR); j +0 (SB); add d0,#1 (SRC); add (RR); ret (SYS); call (B); ld.w (ABS)
+    let samples: [u32; 8] = [0x2182, 0x1042, 0x003C, 0x10C2, 0x2000_100B, 0x0090_000D, 0x0000_006D, 0x0000_0085];
+    for raw in samples {
+        let d = dec.decode(raw).unwrap_or_else(|| panic!("{raw:#010x} should decode"));
+        assert_eq!(dec.width_hint(raw as u8), d.width, "{raw:#010x}");
+    }
+    for op1 in [0x00u8, 0x02, 0x42, 0x82, 0xFE] { assert_eq!(dec.width_hint(op1), 2, "{op1:#04x}"); }
+    for op1 in [0x01u8, 0x0B, 0x6D, 0x85, 0xFF] { assert_eq!(dec.width_hint(op1), 4, "{op1:#04x}"); }
+}
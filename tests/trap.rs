@@ -0,0 +1,49 @@
+use tricore_rs::exec::IntExecutor;
+use tricore_rs::isa::tc16::Tc16Decoder;
+use tricore_rs::{Bus, Cpu, CpuConfig, LinearMemory, Trap};
+
+#[test]
+fn undecodable_word_yields_illegal_instruction() {
+    let mut mem = LinearMemory::new(64);
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(4);
+
+    // 0x0B with op2=0xFF is not a known RR encoding
+    let raw = (0xFFu32 << 20) | 0x0B;
+    mem.write_u32(4, raw).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+    match cpu.step(&mut mem, &dec, &exec) {
+        Err(Trap::IllegalInstruction { pc, raw: r }) => {
+            assert_eq!(pc, 4);
+            assert_eq!(r, raw);
+        }
+        other => panic!("expected IllegalInstruction, got {other:?}"),
+    }
+    // PC is left on the faulting instruction
+    assert_eq!(cpu.pc, 4);
+}
+
+#[test]
+fn illegal_16bit_reports_halfword_and_unknown_as_nop_skips() {
+    let mut mem = LinearMemory::new(64);
+    // op1=0x0A is not a decoded 16-bit opcode
+    mem.write_u16(0, 0x000A).unwrap();
+    mem.write_u16(2, 0xABCD).unwrap();
+
+    let dec = Tc16Decoder::new();
+    let exec = IntExecutor;
+
+    let mut cpu = Cpu::new(CpuConfig::default());
+    cpu.reset(0);
+    match cpu.step(&mut mem, &dec, &exec) {
+        Err(Trap::IllegalInstruction { pc: 0, raw: 0x000A }) => {}
+        other => panic!("expected IllegalInstruction, got {other:?}"),
+    }
+
+    let mut cpu = Cpu::new(CpuConfig { unknown_as_nop: true, ..CpuConfig::default() });
+    cpu.reset(0);
+    cpu.step(&mut mem, &dec, &exec).unwrap();
+    assert_eq!(cpu.pc, 2);
+}